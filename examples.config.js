@@ -14,14 +14,14 @@ const EXAMPLE_FEATURES = {
   random: {
     enabled: true,
     description: "Random data generation, password creation, dice rolling, character creation",
-    dependencies: ["rand", "getrandom", "serde", "serde-wasm-bindgen"]
+    dependencies: ["rand", "rand_distr", "getrandom", "serde", "serde-wasm-bindgen", "serde_json"]
   },
 
   // Mathematical computing with nalgebra
   math: {
     enabled: true,
     description: "Linear algebra, matrix operations, 3D transformations, vector math",
-    dependencies: ["nalgebra"]
+    dependencies: ["nalgebra", "serde", "serde-wasm-bindgen", "num-rational", "num-traits"]
   },
 
   // GPU computing (problematic - disabled by default)
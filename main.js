@@ -156,6 +156,7 @@ function setupEventListeners() {
     const character = wasm.create_random_character(name);
     document.getElementById('wasm-output').textContent = 
       `Character: ${character.name}\n` +
+      `Race: ${character.race}, Class: ${character.class}\n` +
       `Level: ${character.level}\n` +
       `Health: ${character.health}, Mana: ${character.mana}\n` +
       `Stats:\n` +
@@ -0,0 +1,599 @@
+#[cfg(feature = "random")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "random")]
+use wasm_bindgen::{Clamped, JsCast};
+#[cfg(feature = "random")]
+use rand::prelude::*;
+#[cfg(feature = "random")]
+use std::fmt;
+#[cfg(feature = "random")]
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, ImageData};
+#[cfg(feature = "random")]
+use super::palette::Rgb;
+
+/// RLE body lines are wrapped at this width, as most pattern files are.
+#[cfg(feature = "random")]
+const RLE_LINE_WIDTH: usize = 70;
+
+/// Birth/survival rule for a 2D outer-totalistic automaton.
+#[cfg(feature = "random")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LifeRule {
+    pub birth: [bool; 9],
+    pub survive: [bool; 9],
+}
+
+#[cfg(feature = "random")]
+impl LifeRule {
+    pub fn conway() -> Self {
+        LifeRule::parse("B3/S23").unwrap()
+    }
+
+    /// Accepts "B3/S23" notation (either order, case-insensitive) and the
+    /// older "23/3" survival/birth form.
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        let digits = |part: &str| -> Result<[bool; 9], String> {
+            let mut set = [false; 9];
+            for c in part.chars() {
+                match c.to_digit(10) {
+                    Some(d) if d <= 8 => set[d as usize] = true,
+                    _ => return Err(format!("Invalid rule: {}", rule)),
+                }
+            }
+            Ok(set)
+        };
+
+        let parts: Vec<&str> = rule.trim().split('/').collect();
+        if parts.len() != 2 {
+            return Err(format!("Invalid rule: {}", rule));
+        }
+        let (mut birth, mut survive) = (None, None);
+        for part in &parts {
+            let upper = part.to_uppercase();
+            if let Some(rest) = upper.strip_prefix('B') {
+                birth = Some(digits(rest)?);
+            } else if let Some(rest) = upper.strip_prefix('S') {
+                survive = Some(digits(rest)?);
+            }
+        }
+        match (birth, survive) {
+            (Some(birth), Some(survive)) => Ok(LifeRule { birth, survive }),
+            (None, None) => Ok(LifeRule { survive: digits(parts[0])?, birth: digits(parts[1])? }),
+            _ => Err(format!("Invalid rule: {}", rule)),
+        }
+    }
+}
+
+#[cfg(feature = "random")]
+impl fmt::Display for LifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let list = |set: &[bool; 9]| -> String { (0..9).filter(|&i| set[i]).map(|i| i.to_string()).collect() };
+        write!(f, "B{}/S{}", list(&self.birth), list(&self.survive))
+    }
+}
+
+/// A 2D grid of live (1) and dead (0) cells, stored row-major.
+#[cfg(feature = "random")]
+#[derive(Clone, Debug)]
+pub struct LifeGrid {
+    pub width: usize,
+    pub height: usize,
+    pub rule: LifeRule,
+    pub toroidal: bool,
+    pub generation: u64,
+    cells: Vec<u8>,
+    scratch: Vec<u8>,
+}
+
+#[cfg(feature = "random")]
+impl LifeGrid {
+    pub fn new(width: usize, height: usize, rule: LifeRule, toroidal: bool) -> Self {
+        LifeGrid {
+            width,
+            height,
+            rule,
+            toroidal,
+            generation: 0,
+            cells: vec![0; width * height],
+            scratch: vec![0; width * height],
+        }
+    }
+
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.cells[y * self.width + x] == 1
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, alive: bool) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = alive as u8;
+        }
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|&&c| c == 1).count()
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill(0);
+        self.generation = 0;
+    }
+
+    pub fn randomize<R: Rng>(&mut self, density: f64, rng: &mut R) {
+        let density = density.clamp(0.0, 1.0);
+        for cell in &mut self.cells {
+            *cell = rng.gen_bool(density) as u8;
+        }
+        self.generation = 0;
+    }
+
+    /// Coordinates of live cells, row by row.
+    pub fn live_cells(&self) -> Vec<(usize, usize)> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.cells[y * self.width + x] == 1)
+            .collect()
+    }
+
+    pub fn step(&mut self) {
+        let (w, h) = (self.width, self.height);
+        if w == 0 || h == 0 {
+            return;
+        }
+
+        for y in 0..h {
+            // Neighbour rows, or None past a bounded edge.
+            let rows = [
+                if y > 0 { Some(y - 1) } else if self.toroidal { Some(h - 1) } else { None },
+                Some(y),
+                if y + 1 < h { Some(y + 1) } else if self.toroidal { Some(0) } else { None },
+            ];
+            for x in 0..w {
+                let columns = [
+                    if x > 0 { Some(x - 1) } else if self.toroidal { Some(w - 1) } else { None },
+                    Some(x),
+                    if x + 1 < w { Some(x + 1) } else if self.toroidal { Some(0) } else { None },
+                ];
+                let mut neighbours = 0;
+                for (ri, row) in rows.iter().enumerate() {
+                    let Some(row) = row else { continue };
+                    for (ci, column) in columns.iter().enumerate() {
+                        if let Some(column) = column {
+                            if ri != 1 || ci != 1 {
+                                neighbours += self.cells[row * w + column] as usize;
+                            }
+                        }
+                    }
+                }
+                let alive = self.cells[y * w + x] == 1;
+                self.scratch[y * w + x] =
+                    if alive { self.rule.survive[neighbours] } else { self.rule.birth[neighbours] } as u8;
+            }
+        }
+
+        std::mem::swap(&mut self.cells, &mut self.scratch);
+        self.generation += 1;
+    }
+
+    pub fn step_n(&mut self, n: u32) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+
+    /// Stamps a pattern with its top-left corner at (x, y), wrapping on a
+    /// toroidal grid and clipping on a bounded one.
+    pub fn place(&mut self, pattern: &RlePattern, x: i64, y: i64) {
+        for &(px, py) in &pattern.cells {
+            let (cx, cy) = (x + px as i64, y + py as i64);
+            if self.toroidal {
+                self.set(cx.rem_euclid(self.width as i64) as usize, cy.rem_euclid(self.height as i64) as usize, true);
+            } else if cx >= 0 && cy >= 0 {
+                self.set(cx as usize, cy as usize, true);
+            }
+        }
+    }
+
+    /// Exports the bounding box of live cells as an RLE pattern.
+    pub fn to_pattern(&self) -> RlePattern {
+        let live = self.live_cells();
+        let (min_x, min_y) = (
+            live.iter().map(|c| c.0).min().unwrap_or(0),
+            live.iter().map(|c| c.1).min().unwrap_or(0),
+        );
+        let (max_x, max_y) = (
+            live.iter().map(|c| c.0).max().unwrap_or(0),
+            live.iter().map(|c| c.1).max().unwrap_or(0),
+        );
+        RlePattern {
+            width: if live.is_empty() { 0 } else { max_x - min_x + 1 },
+            height: if live.is_empty() { 0 } else { max_y - min_y + 1 },
+            rule: Some(self.rule),
+            cells: live.into_iter().map(|(x, y)| (x - min_x, y - min_y)).collect(),
+        }
+    }
+}
+
+/// A pattern in Golly/LifeWiki run-length encoding.
+#[cfg(feature = "random")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RlePattern {
+    pub width: usize,
+    pub height: usize,
+    pub rule: Option<LifeRule>,
+    pub cells: Vec<(usize, usize)>,
+}
+
+#[cfg(feature = "random")]
+impl RlePattern {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut header: Option<(usize, usize, Option<LifeRule>)> = None;
+        let mut body = String::new();
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if header.is_none() && line.starts_with('x') {
+                let (mut width, mut height, mut rule) = (None, None, None);
+                for field in line.split(',') {
+                    let (key, value) = field.split_once('=').ok_or_else(|| format!("Invalid RLE header: {}", line))?;
+                    match key.trim() {
+                        "x" => width = value.trim().parse().ok(),
+                        "y" => height = value.trim().parse().ok(),
+                        "rule" => rule = Some(LifeRule::parse(value.trim())?),
+                        _ => {}
+                    }
+                }
+                match (width, height) {
+                    (Some(w), Some(h)) => header = Some((w, h, rule)),
+                    _ => return Err(format!("Invalid RLE header: {}", line)),
+                }
+                continue;
+            }
+            body.push_str(line);
+        }
+
+        let (width, height, rule) = header.ok_or("RLE pattern is missing its 'x = .., y = ..' header")?;
+        let mut cells = Vec::new();
+        let (mut x, mut y, mut count) = (0usize, 0usize, 0usize);
+        let too_big = || format!("RLE cells exceed the declared {}x{} size", width, height);
+        for c in body.chars() {
+            match c {
+                '0'..='9' => {
+                    count = count
+                        .checked_mul(10)
+                        .and_then(|n| n.checked_add(c.to_digit(10).unwrap() as usize))
+                        .ok_or_else(too_big)?;
+                }
+                'b' | '.' => {
+                    x = x.checked_add(count.max(1)).filter(|&x| x <= width).ok_or_else(too_big)?;
+                    count = 0;
+                }
+                '$' => {
+                    y = y.checked_add(count.max(1)).filter(|&y| y <= height).ok_or_else(too_big)?;
+                    x = 0;
+                    count = 0;
+                }
+                '!' => break,
+                c if c.is_ascii_alphabetic() => {
+                    let end = x.checked_add(count.max(1)).filter(|&end| end <= width && y < height).ok_or_else(too_big)?;
+                    cells.extend((x..end).map(|cx| (cx, y)));
+                    x = end;
+                    count = 0;
+                }
+                c if c.is_whitespace() => {}
+                other => return Err(format!("Unexpected character in RLE: {}", other)),
+            }
+        }
+
+        Ok(RlePattern { width, height, rule, cells })
+    }
+
+    pub fn to_rle(&self) -> String {
+        let mut rows = vec![Vec::new(); self.height];
+        for &(x, y) in &self.cells {
+            rows[y].push(x);
+        }
+
+        let mut runs: Vec<(usize, char)> = Vec::new();
+        let push = |runs: &mut Vec<(usize, char)>, count: usize, tag: char| {
+            if count == 0 {
+                return;
+            }
+            match runs.last_mut() {
+                Some((n, t)) if *t == tag => *n += count,
+                _ => runs.push((count, tag)),
+            }
+        };
+        for (y, row) in rows.iter_mut().enumerate() {
+            row.sort_unstable();
+            let mut x = 0;
+            for &cx in row.iter() {
+                push(&mut runs, cx - x, 'b');
+                push(&mut runs, 1, 'o');
+                x = cx + 1;
+            }
+            if y + 1 < self.height {
+                push(&mut runs, 1, '$');
+            }
+        }
+        // Trailing blank rows carry no information.
+        while matches!(runs.last(), Some((_, '$'))) {
+            runs.pop();
+        }
+
+        let mut text = match self.rule {
+            Some(rule) => format!("x = {}, y = {}, rule = {}\n", self.width, self.height, rule),
+            None => format!("x = {}, y = {}\n", self.width, self.height),
+        };
+        let mut line = String::new();
+        for token in runs.iter().map(|&(n, t)| if n == 1 { t.to_string() } else { format!("{}{}", n, t) }).chain(["!".to_string()]) {
+            if line.len() + token.len() > RLE_LINE_WIDTH {
+                text.push_str(&line);
+                text.push('\n');
+                line.clear();
+            }
+            line.push_str(&token);
+        }
+        text.push_str(&line);
+        text.push('\n');
+        text
+    }
+}
+
+/// Runs a 1D elementary automaton (Wolfram rule 0-255) for `steps`
+/// generations, returning every row including the initial one.
+#[cfg(feature = "random")]
+pub fn elementary_automaton(rule: u8, initial: &[u8], steps: usize, wrap: bool) -> Vec<Vec<u8>> {
+    let width = initial.len();
+    let mut rows = vec![initial.to_vec()];
+    for _ in 0..steps {
+        let current = rows.last().unwrap();
+        let cell = |i: isize| -> u8 {
+            if (0..width as isize).contains(&i) {
+                current[i as usize]
+            } else if wrap {
+                current[i.rem_euclid(width as isize) as usize]
+            } else {
+                0
+            }
+        };
+        let next = (0..width as isize)
+            .map(|i| {
+                let index = (cell(i - 1) << 2) | (cell(i) << 1) | cell(i + 1);
+                (rule >> index) & 1
+            })
+            .collect();
+        rows.push(next);
+    }
+    rows
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub struct LifeGame {
+    grid: LifeGrid,
+    pixels: Vec<u8>,
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+impl LifeGame {
+    /// `rule` is a B/S rulestring such as "B3/S23" (Conway) or "B36/S23".
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u32, height: u32, rule: &str, toroidal: bool) -> Result<LifeGame, JsValue> {
+        if width == 0 || height == 0 {
+            return Err(JsValue::from_str("Grid dimensions must be positive"));
+        }
+        let rule = LifeRule::parse(rule).map_err(|e| JsValue::from_str(&e))?;
+        Ok(LifeGame { grid: LifeGrid::new(width as usize, height as usize, rule, toroidal), pixels: Vec::new() })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.grid.width as u32
+    }
+
+    pub fn height(&self) -> u32 {
+        self.grid.height as u32
+    }
+
+    pub fn generation(&self) -> f64 {
+        self.grid.generation as f64
+    }
+
+    pub fn population(&self) -> u32 {
+        self.grid.population() as u32
+    }
+
+    pub fn rule(&self) -> String {
+        self.grid.rule.to_string()
+    }
+
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), JsValue> {
+        self.grid.rule = LifeRule::parse(rule).map_err(|e| JsValue::from_str(&e))?;
+        Ok(())
+    }
+
+    pub fn randomize(&mut self, density: f64, seed: Option<u32>) {
+        self.grid.randomize(density, &mut super::random::seeded_rng(seed));
+    }
+
+    pub fn clear(&mut self) {
+        self.grid.clear();
+    }
+
+    pub fn step(&mut self, n: Option<u32>) {
+        self.grid.step_n(n.unwrap_or(1));
+    }
+
+    pub fn get_cell(&self, x: u32, y: u32) -> bool {
+        self.grid.get(x as usize, y as usize)
+    }
+
+    pub fn set_cell(&mut self, x: u32, y: u32, alive: bool) {
+        self.grid.set(x as usize, y as usize, alive);
+    }
+
+    pub fn toggle(&mut self, x: u32, y: u32) {
+        let alive = self.grid.get(x as usize, y as usize);
+        self.grid.set(x as usize, y as usize, !alive);
+    }
+
+    /// Row-major cell states, 1 for live.
+    pub fn cells(&self) -> js_sys::Uint8Array {
+        js_sys::Uint8Array::from(self.grid.cells())
+    }
+
+    /// Stamps an RLE pattern; without a position it is centred. A rule in
+    /// the RLE header replaces the current rule.
+    pub fn load_rle(&mut self, rle: &str, x: Option<i32>, y: Option<i32>) -> Result<(), JsValue> {
+        let pattern = RlePattern::parse(rle).map_err(|e| JsValue::from_str(&e))?;
+        if let Some(rule) = pattern.rule {
+            self.grid.rule = rule;
+        }
+        let x = x.map(|v| v as i64).unwrap_or((self.grid.width as i64 - pattern.width as i64) / 2);
+        let y = y.map(|v| v as i64).unwrap_or((self.grid.height as i64 - pattern.height as i64) / 2);
+        self.grid.place(&pattern, x, y);
+        Ok(())
+    }
+
+    pub fn to_rle(&self) -> String {
+        self.grid.to_pattern().to_rle()
+    }
+
+    /// Draws the grid with `cell_size` pixels per cell, resizing the canvas
+    /// to fit. Colors are hex strings.
+    pub fn render_to_canvas(
+        &mut self,
+        canvas: &HtmlCanvasElement,
+        cell_size: u32,
+        alive_color: &str,
+        dead_color: &str,
+    ) -> Result<(), JsValue> {
+        let rgba = |hex: &str| -> Result<[u8; 4], JsValue> {
+            let c = Rgb::from_hex(hex).map_err(|e| JsValue::from_str(&e))?;
+            Ok([(c.r * 255.0).round() as u8, (c.g * 255.0).round() as u8, (c.b * 255.0).round() as u8, 255])
+        };
+        let colors = [rgba(dead_color)?, rgba(alive_color)?];
+        let cell_size = cell_size.max(1) as usize;
+        let (width, height) = (self.grid.width * cell_size, self.grid.height * cell_size);
+
+        // Build one pixel row per grid row, then repeat it `cell_size` times.
+        self.pixels.resize(width * height * 4, 0);
+        let row_bytes = width * 4;
+        for y in 0..self.grid.height {
+            let start = y * cell_size * row_bytes;
+            let row = &self.grid.cells()[y * self.grid.width..(y + 1) * self.grid.width];
+            for (x, &cell) in row.iter().enumerate() {
+                for dx in 0..cell_size {
+                    let offset = start + (x * cell_size + dx) * 4;
+                    self.pixels[offset..offset + 4].copy_from_slice(&colors[cell as usize]);
+                }
+            }
+            for dy in 1..cell_size {
+                self.pixels.copy_within(start..start + row_bytes, start + dy * row_bytes);
+            }
+        }
+
+        canvas.set_width(width as u32);
+        canvas.set_height(height as u32);
+        let context = canvas
+            .get_context("2d")?
+            .unwrap()
+            .dyn_into::<CanvasRenderingContext2d>()?;
+        let image = ImageData::new_with_u8_clamped_array_and_sh(Clamped(&self.pixels), width as u32, height as u32)?;
+        context.put_image_data(&image, 0.0, 0.0)
+    }
+}
+
+/// Returns `steps + 1` rows of `width` cells, row-major. The first row is
+/// a single centred live cell, or random when `seed` is given.
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn wolfram_automaton(rule: u8, width: u32, steps: u32, wrap: bool, seed: Option<u32>) -> js_sys::Uint8Array {
+    let mut initial = vec![0u8; width as usize];
+    match seed {
+        Some(_) => {
+            let mut rng = super::random::seeded_rng(seed);
+            initial.iter_mut().for_each(|c| *c = rng.gen_bool(0.5) as u8);
+        }
+        None if width > 0 => initial[width as usize / 2] = 1,
+        None => {}
+    }
+    let rows = elementary_automaton(rule, &initial, steps as usize, wrap);
+    js_sys::Uint8Array::from(&rows.concat()[..])
+}
+
+#[cfg(all(test, feature = "random"))]
+mod tests {
+    use super::*;
+
+    const GLIDER: &str = "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!";
+    const PULSAR: &str = "x = 13, y = 13\n2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!";
+
+    fn grid_with(rle: &str, size: usize, x: i64, y: i64) -> LifeGrid {
+        let mut grid = LifeGrid::new(size, size, LifeRule::conway(), false);
+        grid.place(&RlePattern::parse(rle).unwrap(), x, y);
+        grid
+    }
+
+    /// Smallest p in 1..=max with the grid back to its starting state.
+    fn period(grid: &mut LifeGrid, max: u32) -> Option<u32> {
+        let start = grid.live_cells();
+        (1..=max).find(|_| {
+            grid.step();
+            grid.live_cells() == start
+        })
+    }
+
+    #[test]
+    fn glider_moves_one_cell_diagonally_every_four_generations() {
+        let mut grid = grid_with(GLIDER, 20, 2, 2);
+        let start = grid.live_cells();
+        grid.step_n(4);
+        let moved: Vec<(usize, usize)> = start.iter().map(|&(x, y)| (x + 1, y + 1)).collect();
+        assert_eq!(grid.live_cells(), moved);
+        assert_eq!(grid.generation, 4);
+    }
+
+    #[test]
+    fn glider_wraps_on_a_torus() {
+        let mut grid = LifeGrid::new(8, 8, LifeRule::conway(), true);
+        grid.place(&RlePattern::parse(GLIDER).unwrap(), 0, 0);
+        let start = grid.live_cells();
+        grid.step_n(32);
+        assert_eq!(grid.live_cells(), start);
+    }
+
+    #[test]
+    fn oscillator_periods() {
+        assert_eq!(period(&mut grid_with("x = 3, y = 1\n3o!", 5, 1, 2), 10), Some(2));
+        let mut pulsar = grid_with(PULSAR, 17, 2, 2);
+        assert_eq!(pulsar.population(), 48);
+        assert_eq!(period(&mut pulsar, 10), Some(3));
+    }
+
+    #[test]
+    fn rle_round_trip() {
+        for rle in [GLIDER, PULSAR, "x = 5, y = 4, rule = B36/S23\n2o2bo$$4bo$o!"] {
+            let pattern = RlePattern::parse(rle).unwrap();
+            assert_eq!(RlePattern::parse(&pattern.to_rle()).unwrap(), pattern);
+        }
+        let grid = grid_with(PULSAR, 17, 2, 2);
+        let exported = grid.to_pattern();
+        assert_eq!((exported.width, exported.height), (13, 13));
+        assert_eq!(exported.cells, RlePattern::parse(PULSAR).unwrap().cells);
+    }
+
+    #[test]
+    fn rle_rejects_runs_past_the_declared_size() {
+        assert!(RlePattern::parse("x = 3, y = 3\n4o!").is_err());
+        assert!(RlePattern::parse("x = 3, y = 3\n1000000000o!").is_err());
+        assert!(RlePattern::parse("x = 3, y = 3\n99999999999999999999999o!").is_err());
+        assert!(RlePattern::parse("x = 3, y = 3\n3$o!").is_err());
+    }
+}
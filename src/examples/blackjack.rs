@@ -0,0 +1,640 @@
+#[cfg(feature = "random")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "random")]
+use rand::prelude::*;
+#[cfg(feature = "random")]
+use serde::Serialize;
+#[cfg(feature = "random")]
+use super::random::{seeded_rng, shuffled_shoe, Card};
+
+// The shoe is reshuffled once fewer than this fraction of its cards remain.
+#[cfg(feature = "random")]
+const CUT_CARD_FRACTION: f64 = 0.25;
+
+#[cfg(feature = "random")]
+const MAX_SPLIT_HANDS: usize = 4;
+
+#[cfg(feature = "random")]
+#[derive(Clone, Debug)]
+pub struct BlackjackRules {
+    pub decks: usize,
+    pub dealer_stands_soft_17: bool,
+    pub blackjack_payout: f64,
+    pub allow_surrender: bool,
+    pub double_after_split: bool,
+}
+
+#[cfg(feature = "random")]
+impl Default for BlackjackRules {
+    fn default() -> Self {
+        BlackjackRules {
+            decks: 6,
+            dealer_stands_soft_17: true,
+            blackjack_payout: 1.5,
+            allow_surrender: true,
+            double_after_split: true,
+        }
+    }
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlackjackAction {
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlackjackPhase {
+    Betting,
+    PlayerTurn,
+    RoundOver,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlackjackOutcome {
+    Blackjack,
+    Win,
+    Push,
+    Lose,
+    Bust,
+    Surrendered,
+}
+
+#[cfg(feature = "random")]
+#[derive(Clone, Debug)]
+struct PlayerHand {
+    cards: Vec<Card>,
+    bet: f64,
+    finished: bool,
+    from_split: bool,
+    surrendered: bool,
+    outcome: Option<BlackjackOutcome>,
+    payout: f64,
+}
+
+#[cfg(feature = "random")]
+impl PlayerHand {
+    fn new(cards: Vec<Card>, bet: f64, from_split: bool) -> Self {
+        PlayerHand { cards, bet, finished: false, from_split, surrendered: false, outcome: None, payout: 0.0 }
+    }
+
+    fn is_blackjack(&self) -> bool {
+        !self.from_split && is_blackjack(&self.cards)
+    }
+}
+
+/// Blackjack value of a single card: faces count 10, aces 11.
+#[cfg(feature = "random")]
+pub fn card_value(card: Card) -> u32 {
+    match card.rank {
+        14 => 11,
+        11..=13 => 10,
+        rank => rank as u32,
+    }
+}
+
+/// Returns the best total for a hand and whether an ace is still counted as 11.
+#[cfg(feature = "random")]
+pub fn hand_value(cards: &[Card]) -> (u32, bool) {
+    let mut total: u32 = cards.iter().map(|&c| card_value(c)).sum();
+    let mut soft_aces = cards.iter().filter(|c| c.rank == 14).count();
+    while total > 21 && soft_aces > 0 {
+        total -= 10;
+        soft_aces -= 1;
+    }
+    (total, soft_aces > 0)
+}
+
+#[cfg(feature = "random")]
+fn is_blackjack(cards: &[Card]) -> bool {
+    cards.len() == 2 && hand_value(cards).0 == 21
+}
+
+/// Basic-strategy recommendation for a multi-deck shoe.
+///
+/// `can_double`, `can_split` and `can_surrender` describe what the table
+/// currently allows; when the chart's first choice is unavailable the usual
+/// fallback (hit, or stand on soft 18) is returned instead.
+#[cfg(feature = "random")]
+pub fn basic_strategy(
+    cards: &[Card],
+    dealer_up: Card,
+    rules: &BlackjackRules,
+    can_double: bool,
+    can_split: bool,
+    can_surrender: bool,
+) -> BlackjackAction {
+    let dealer = card_value(dealer_up);
+    let (total, soft) = hand_value(cards);
+    let h17 = !rules.dealer_stands_soft_17;
+
+    if can_surrender && !soft {
+        let surrender = match total {
+            16 => dealer >= 9,
+            15 => dealer == 10 || (h17 && dealer == 11),
+            _ => false,
+        };
+        let is_pair_of_eights = cards.len() == 2 && cards.iter().all(|c| c.rank == 8);
+        if surrender && !is_pair_of_eights {
+            return BlackjackAction::Surrender;
+        }
+    }
+
+    if can_split && cards.len() == 2 && card_value(cards[0]) == card_value(cards[1]) {
+        let das = rules.double_after_split;
+        let split = match card_value(cards[0]) {
+            11 | 8 => true,
+            10 | 5 => false,
+            9 => !matches!(dealer, 7 | 10 | 11),
+            7 => dealer <= 7,
+            6 => dealer <= 6 && (das || dealer >= 3),
+            4 => das && (5..=6).contains(&dealer),
+            _ => dealer <= 7 && (das || dealer >= 4),
+        };
+        if split {
+            return BlackjackAction::Split;
+        }
+    }
+
+    let double_or = |fallback: BlackjackAction| if can_double { BlackjackAction::Double } else { fallback };
+
+    if soft {
+        return match total {
+            20.. => BlackjackAction::Stand,
+            19 if h17 && dealer == 6 => double_or(BlackjackAction::Stand),
+            19 => BlackjackAction::Stand,
+            18 if dealer <= 6 => double_or(BlackjackAction::Stand),
+            18 if dealer <= 8 => BlackjackAction::Stand,
+            17 if (3..=6).contains(&dealer) => double_or(BlackjackAction::Hit),
+            15 | 16 if (4..=6).contains(&dealer) => double_or(BlackjackAction::Hit),
+            13 | 14 if (5..=6).contains(&dealer) => double_or(BlackjackAction::Hit),
+            _ => BlackjackAction::Hit,
+        };
+    }
+
+    match total {
+        17.. => BlackjackAction::Stand,
+        13..=16 if dealer <= 6 => BlackjackAction::Stand,
+        12 if (4..=6).contains(&dealer) => BlackjackAction::Stand,
+        11 if dealer <= 10 || h17 => double_or(BlackjackAction::Hit),
+        10 if dealer <= 9 => double_or(BlackjackAction::Hit),
+        9 if (3..=6).contains(&dealer) => double_or(BlackjackAction::Hit),
+        _ => BlackjackAction::Hit,
+    }
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct HandState {
+    pub cards: Vec<String>,
+    pub total: u32,
+    pub soft: bool,
+    pub bet: f64,
+    pub finished: bool,
+    pub outcome: Option<BlackjackOutcome>,
+    pub payout: f64,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct TableState {
+    pub phase: BlackjackPhase,
+    pub bankroll: f64,
+    /// Only the up card is shown while the player is still acting.
+    pub dealer_cards: Vec<String>,
+    pub dealer_total: u32,
+    pub hands: Vec<HandState>,
+    pub active_hand: Option<usize>,
+    pub legal_actions: Vec<BlackjackAction>,
+    pub advice: Option<BlackjackAction>,
+    pub cards_remaining: usize,
+    pub message: String,
+}
+
+/// Pure-Rust blackjack table; `BlackjackGame` wraps it for JavaScript.
+#[cfg(feature = "random")]
+pub struct BlackjackEngine {
+    rules: BlackjackRules,
+    rng: StdRng,
+    shoe: Vec<Card>,
+    bankroll: f64,
+    dealer: Vec<Card>,
+    hands: Vec<PlayerHand>,
+    active: usize,
+    phase: BlackjackPhase,
+    message: String,
+}
+
+#[cfg(feature = "random")]
+impl BlackjackEngine {
+    pub fn new(rules: BlackjackRules, bankroll: f64, mut rng: StdRng) -> Result<Self, String> {
+        if !bankroll.is_finite() || bankroll < 0.0 {
+            return Err("Bankroll must be a non-negative number".to_string());
+        }
+        let shoe = shuffled_shoe(rules.decks.max(1), &mut rng);
+        Ok(BlackjackEngine {
+            rules,
+            rng,
+            shoe,
+            bankroll,
+            dealer: Vec::new(),
+            hands: Vec::new(),
+            active: 0,
+            phase: BlackjackPhase::Betting,
+            message: "Place a bet to start the round".to_string(),
+        })
+    }
+
+    fn draw(&mut self) -> Card {
+        if self.shoe.is_empty() {
+            self.shoe = shuffled_shoe(self.rules.decks.max(1), &mut self.rng);
+        }
+        self.shoe.pop().unwrap()
+    }
+
+    pub fn deal(&mut self, bet: f64) -> Result<(), String> {
+        if self.phase == BlackjackPhase::PlayerTurn {
+            return Err("A round is already in progress".to_string());
+        }
+        if bet.is_nan() || bet <= 0.0 || bet > self.bankroll {
+            return Err(format!("Bet must be between 0 and the bankroll ({:.2})", self.bankroll));
+        }
+
+        let shoe_size = self.rules.decks.max(1) * 52;
+        if (self.shoe.len() as f64) < shoe_size as f64 * CUT_CARD_FRACTION {
+            self.shoe = shuffled_shoe(self.rules.decks.max(1), &mut self.rng);
+        }
+
+        self.bankroll -= bet;
+        let first = self.draw();
+        let dealer_up = self.draw();
+        let second = self.draw();
+        let dealer_hole = self.draw();
+        self.dealer = vec![dealer_up, dealer_hole];
+        self.hands = vec![PlayerHand::new(vec![first, second], bet, false)];
+        self.active = 0;
+        self.phase = BlackjackPhase::PlayerTurn;
+        self.message = "Your move".to_string();
+
+        // The dealer peeks for blackjack, and a natural ends the round at once.
+        if is_blackjack(&self.dealer) || self.hands[0].is_blackjack() {
+            self.hands[0].finished = true;
+            self.settle();
+        }
+        Ok(())
+    }
+
+    pub fn legal_actions(&self) -> Vec<BlackjackAction> {
+        if self.phase != BlackjackPhase::PlayerTurn {
+            return Vec::new();
+        }
+        let hand = &self.hands[self.active];
+        let mut actions = vec![BlackjackAction::Hit, BlackjackAction::Stand];
+        let two_cards = hand.cards.len() == 2;
+        let affordable = self.bankroll >= hand.bet;
+
+        if two_cards && affordable && (!hand.from_split || self.rules.double_after_split) {
+            actions.push(BlackjackAction::Double);
+        }
+        if two_cards
+            && affordable
+            && card_value(hand.cards[0]) == card_value(hand.cards[1])
+            && self.hands.len() < MAX_SPLIT_HANDS
+        {
+            actions.push(BlackjackAction::Split);
+        }
+        if two_cards && self.rules.allow_surrender && self.hands.len() == 1 {
+            actions.push(BlackjackAction::Surrender);
+        }
+        actions
+    }
+
+    pub fn advice(&self) -> Option<BlackjackAction> {
+        if self.phase != BlackjackPhase::PlayerTurn {
+            return None;
+        }
+        let legal = self.legal_actions();
+        Some(basic_strategy(
+            &self.hands[self.active].cards,
+            self.dealer[0],
+            &self.rules,
+            legal.contains(&BlackjackAction::Double),
+            legal.contains(&BlackjackAction::Split),
+            legal.contains(&BlackjackAction::Surrender),
+        ))
+    }
+
+    pub fn act(&mut self, action: BlackjackAction) -> Result<(), String> {
+        if !self.legal_actions().contains(&action) {
+            return Err(format!("{:?} is not allowed right now", action));
+        }
+
+        match action {
+            BlackjackAction::Hit => {
+                let card = self.draw();
+                let hand = &mut self.hands[self.active];
+                hand.cards.push(card);
+                if hand_value(&hand.cards).0 >= 21 {
+                    hand.finished = true;
+                }
+            }
+            BlackjackAction::Stand => self.hands[self.active].finished = true,
+            BlackjackAction::Double => {
+                let card = self.draw();
+                let hand = &mut self.hands[self.active];
+                self.bankroll -= hand.bet;
+                hand.bet *= 2.0;
+                hand.cards.push(card);
+                hand.finished = true;
+            }
+            BlackjackAction::Split => {
+                let bet = self.hands[self.active].bet;
+                self.bankroll -= bet;
+                let moved = self.hands[self.active].cards.pop().unwrap();
+                let (left_card, right_card) = (self.draw(), self.draw());
+
+                let split_aces = moved.rank == 14;
+                let left = &mut self.hands[self.active];
+                left.from_split = true;
+                left.cards.push(left_card);
+                left.finished = split_aces;
+
+                let mut right = PlayerHand::new(vec![moved, right_card], bet, true);
+                right.finished = split_aces;
+                self.hands.insert(self.active + 1, right);
+            }
+            BlackjackAction::Surrender => {
+                let hand = &mut self.hands[self.active];
+                hand.surrendered = true;
+                hand.finished = true;
+            }
+        }
+
+        self.advance();
+        Ok(())
+    }
+
+    fn advance(&mut self) {
+        while self.active < self.hands.len() && self.hands[self.active].finished {
+            self.active += 1;
+        }
+        if self.active >= self.hands.len() {
+            self.active = self.hands.len() - 1;
+            self.settle();
+        }
+    }
+
+    fn settle(&mut self) {
+        let any_live = self
+            .hands
+            .iter()
+            .any(|h| !h.surrendered && hand_value(&h.cards).0 <= 21 && !h.is_blackjack());
+        if any_live && !is_blackjack(&self.dealer) {
+            loop {
+                let (total, soft) = hand_value(&self.dealer);
+                let hits_soft_17 = total == 17 && soft && !self.rules.dealer_stands_soft_17;
+                if total >= 18 || (total == 17 && !hits_soft_17) {
+                    break;
+                }
+                let card = self.draw();
+                self.dealer.push(card);
+            }
+        }
+
+        let dealer_total = hand_value(&self.dealer).0;
+        let dealer_blackjack = is_blackjack(&self.dealer);
+        let mut net = 0.0;
+
+        for hand in &mut self.hands {
+            let total = hand_value(&hand.cards).0;
+            let (outcome, payout) = if hand.surrendered {
+                (BlackjackOutcome::Surrendered, hand.bet / 2.0)
+            } else if hand.is_blackjack() && dealer_blackjack {
+                (BlackjackOutcome::Push, hand.bet)
+            } else if hand.is_blackjack() {
+                (BlackjackOutcome::Blackjack, hand.bet * (1.0 + self.rules.blackjack_payout))
+            } else if total > 21 {
+                (BlackjackOutcome::Bust, 0.0)
+            } else if dealer_blackjack {
+                (BlackjackOutcome::Lose, 0.0)
+            } else if dealer_total > 21 || total > dealer_total {
+                (BlackjackOutcome::Win, hand.bet * 2.0)
+            } else if total == dealer_total {
+                (BlackjackOutcome::Push, hand.bet)
+            } else {
+                (BlackjackOutcome::Lose, 0.0)
+            };
+            hand.outcome = Some(outcome);
+            hand.payout = payout;
+            hand.finished = true;
+            net += payout - hand.bet;
+            self.bankroll += payout;
+        }
+
+        self.phase = BlackjackPhase::RoundOver;
+        self.message = match net {
+            n if n > 0.0 => format!("Round over: you won {:.2}", n),
+            n if n < 0.0 => format!("Round over: you lost {:.2}", -n),
+            _ => "Round over: push".to_string(),
+        };
+    }
+
+    pub fn state(&self) -> TableState {
+        let hide_hole = self.phase == BlackjackPhase::PlayerTurn;
+        let shown_dealer: &[Card] = if hide_hole { &self.dealer[..1] } else { &self.dealer };
+
+        TableState {
+            phase: self.phase,
+            bankroll: self.bankroll,
+            dealer_cards: shown_dealer.iter().map(|c| c.to_string()).collect(),
+            dealer_total: hand_value(shown_dealer).0,
+            hands: self
+                .hands
+                .iter()
+                .map(|h| {
+                    let (total, soft) = hand_value(&h.cards);
+                    HandState {
+                        cards: h.cards.iter().map(|c| c.to_string()).collect(),
+                        total,
+                        soft,
+                        bet: h.bet,
+                        finished: h.finished,
+                        outcome: h.outcome,
+                        payout: h.payout,
+                    }
+                })
+                .collect(),
+            active_hand: (self.phase == BlackjackPhase::PlayerTurn).then_some(self.active),
+            legal_actions: self.legal_actions(),
+            advice: self.advice(),
+            cards_remaining: self.shoe.len(),
+            message: self.message.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub struct BlackjackGame {
+    engine: BlackjackEngine,
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+impl BlackjackGame {
+    #[wasm_bindgen(constructor)]
+    pub fn new(decks: u32, dealer_stands_soft_17: bool, bankroll: f64, seed: Option<u32>) -> Result<BlackjackGame, JsValue> {
+        let rules = BlackjackRules {
+            decks: decks.max(1) as usize,
+            dealer_stands_soft_17,
+            ..BlackjackRules::default()
+        };
+        let engine = BlackjackEngine::new(rules, bankroll, seeded_rng(seed)).map_err(|e| JsValue::from_str(&e))?;
+        Ok(BlackjackGame { engine })
+    }
+
+    pub fn deal(&mut self, bet: f64) -> Result<JsValue, JsValue> {
+        self.engine.deal(bet).map_err(|e| JsValue::from_str(&e))?;
+        Ok(self.state())
+    }
+
+    pub fn hit(&mut self) -> Result<JsValue, JsValue> {
+        self.apply(BlackjackAction::Hit)
+    }
+
+    pub fn stand(&mut self) -> Result<JsValue, JsValue> {
+        self.apply(BlackjackAction::Stand)
+    }
+
+    pub fn double_down(&mut self) -> Result<JsValue, JsValue> {
+        self.apply(BlackjackAction::Double)
+    }
+
+    pub fn split(&mut self) -> Result<JsValue, JsValue> {
+        self.apply(BlackjackAction::Split)
+    }
+
+    pub fn surrender(&mut self) -> Result<JsValue, JsValue> {
+        self.apply(BlackjackAction::Surrender)
+    }
+
+    pub fn state(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.engine.state()).unwrap()
+    }
+
+    fn apply(&mut self, action: BlackjackAction) -> Result<JsValue, JsValue> {
+        self.engine.act(action).map_err(|e| JsValue::from_str(&e))?;
+        Ok(self.state())
+    }
+}
+
+#[cfg(all(test, feature = "random"))]
+mod tests {
+    use super::*;
+
+    fn rules(dealer_stands_soft_17: bool, double_after_split: bool) -> BlackjackRules {
+        BlackjackRules { decks: 1, dealer_stands_soft_17, double_after_split, ..BlackjackRules::default() }
+    }
+
+    fn cards(ranks: &[u8]) -> Vec<Card> {
+        ranks.iter().map(|&rank| Card::new(rank, 0)).collect()
+    }
+
+    /// An engine whose next draws are `ranks` in order, on top of enough
+    /// filler that the cut card doesn't trigger a reshuffle.
+    fn stacked(rules: BlackjackRules, ranks: &[u8]) -> BlackjackEngine {
+        let mut engine = BlackjackEngine::new(rules, 100.0, StdRng::seed_from_u64(7)).unwrap();
+        engine.shoe = cards(&[2; 40]);
+        engine.shoe.extend(cards(ranks).into_iter().rev());
+        engine
+    }
+
+    #[test]
+    fn basic_strategy_lookups() {
+        let s17 = rules(true, true);
+        let h17 = rules(false, true);
+        let no_das = rules(true, false);
+        let (ace, six, ten) = (Card::new(14, 0), Card::new(6, 0), Card::new(10, 0));
+
+        assert_eq!(basic_strategy(&cards(&[10, 6]), ten, &s17, true, false, true), BlackjackAction::Surrender);
+        assert_eq!(basic_strategy(&cards(&[10, 6]), ten, &s17, true, false, false), BlackjackAction::Hit);
+        assert_eq!(basic_strategy(&cards(&[8, 8]), ten, &s17, true, true, true), BlackjackAction::Split);
+        assert_eq!(basic_strategy(&cards(&[14, 7]), six, &s17, true, false, false), BlackjackAction::Double);
+        assert_eq!(basic_strategy(&cards(&[14, 7]), six, &s17, false, false, false), BlackjackAction::Stand);
+        assert_eq!(basic_strategy(&cards(&[12, 2]), six, &s17, true, false, false), BlackjackAction::Stand);
+        assert_eq!(basic_strategy(&cards(&[6, 5]), ace, &s17, true, false, false), BlackjackAction::Hit);
+        assert_eq!(basic_strategy(&cards(&[6, 5]), ace, &h17, true, false, false), BlackjackAction::Double);
+        assert_eq!(basic_strategy(&cards(&[4, 4]), Card::new(5, 0), &s17, true, true, false), BlackjackAction::Split);
+        assert_eq!(basic_strategy(&cards(&[4, 4]), Card::new(5, 0), &no_das, false, true, false), BlackjackAction::Hit);
+    }
+
+    #[test]
+    fn dealer_stands_or_hits_soft_17() {
+        // Player 10 + 8, dealer A + 6 with a 3 waiting.
+        let deal = [10, 14, 8, 6, 3];
+
+        let mut stands = stacked(rules(true, true), &deal);
+        stands.deal(10.0).unwrap();
+        stands.act(BlackjackAction::Stand).unwrap();
+        assert_eq!(stands.dealer.len(), 2);
+        assert_eq!(stands.hands[0].outcome, Some(BlackjackOutcome::Win));
+        assert_eq!(stands.bankroll, 110.0);
+
+        let mut hits = stacked(rules(false, true), &deal);
+        hits.deal(10.0).unwrap();
+        hits.act(BlackjackAction::Stand).unwrap();
+        assert_eq!(hand_value(&hits.dealer), (20, true));
+        assert_eq!(hits.hands[0].outcome, Some(BlackjackOutcome::Lose));
+        assert_eq!(hits.bankroll, 90.0);
+    }
+
+    #[test]
+    fn double_after_split_pays_both_doubled_hands() {
+        // Player 8 + 8 against a dealer 6 + 10; the split hands draw 3 and 2,
+        // the doubles draw 10s and the dealer busts on the last 10.
+        let deal = [8, 6, 8, 10, 3, 2, 10, 10, 10];
+
+        let mut engine = stacked(rules(true, true), &deal);
+        engine.deal(10.0).unwrap();
+        engine.act(BlackjackAction::Split).unwrap();
+        engine.act(BlackjackAction::Double).unwrap();
+        engine.act(BlackjackAction::Double).unwrap();
+        assert_eq!(engine.phase, BlackjackPhase::RoundOver);
+        for hand in &engine.hands {
+            assert_eq!((hand.bet, hand.outcome, hand.payout), (20.0, Some(BlackjackOutcome::Win), 40.0));
+        }
+        assert_eq!(engine.bankroll, 140.0);
+
+        let mut no_das = stacked(rules(true, false), &deal);
+        no_das.deal(10.0).unwrap();
+        no_das.act(BlackjackAction::Split).unwrap();
+        assert!(no_das.act(BlackjackAction::Double).is_err());
+    }
+
+    #[test]
+    fn same_seed_deals_the_same_round() {
+        let deal = |seed| {
+            let mut engine = BlackjackEngine::new(BlackjackRules::default(), 100.0, StdRng::seed_from_u64(seed)).unwrap();
+            engine.deal(5.0).unwrap();
+            (engine.hands[0].cards.clone(), engine.dealer.clone())
+        };
+        assert_eq!(deal(42), deal(42));
+        assert_ne!(deal(42), deal(43));
+    }
+
+    #[test]
+    fn rejects_invalid_bankrolls() {
+        for bankroll in [-1.0, f64::NAN, f64::INFINITY] {
+            assert!(BlackjackEngine::new(BlackjackRules::default(), bankroll, StdRng::seed_from_u64(1)).is_err());
+        }
+    }
+}
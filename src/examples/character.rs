@@ -0,0 +1,367 @@
+#[cfg(feature = "random")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "random")]
+use rand::prelude::*;
+#[cfg(feature = "random")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "random")]
+use super::random::seeded_rng;
+
+#[cfg(feature = "random")]
+pub const MAX_LEVEL: u32 = 50;
+
+// Every this many levels the class's primary stat goes up by one.
+#[cfg(feature = "random")]
+const STAT_INCREASE_INTERVAL: u32 = 4;
+
+#[cfg(feature = "random")]
+const POINT_BUY_BUDGET: u32 = 18;
+
+// Point-buy cost of each base score from 8 to 15.
+#[cfg(feature = "random")]
+const POINT_BUY_COSTS: [u32; 8] = [0, 1, 2, 3, 4, 5, 7, 9];
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Stat {
+    Strength,
+    Dexterity,
+    Intelligence,
+    Luck,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CharacterStats {
+    pub strength: u32,
+    pub dexterity: u32,
+    pub intelligence: u32,
+    pub luck: u32,
+}
+
+#[cfg(feature = "random")]
+impl CharacterStats {
+    pub fn from_array([strength, dexterity, intelligence, luck]: [u32; 4]) -> Self {
+        CharacterStats { strength, dexterity, intelligence, luck }
+    }
+
+    pub fn to_array(&self) -> [u32; 4] {
+        [self.strength, self.dexterity, self.intelligence, self.luck]
+    }
+
+    pub fn get_mut(&mut self, stat: Stat) -> &mut u32 {
+        match stat {
+            Stat::Strength => &mut self.strength,
+            Stat::Dexterity => &mut self.dexterity,
+            Stat::Intelligence => &mut self.intelligence,
+            Stat::Luck => &mut self.luck,
+        }
+    }
+}
+
+/// D&D-style ability modifier: +1 for every two points above 10.
+#[cfg(feature = "random")]
+pub fn stat_modifier(score: u32) -> i32 {
+    (score as i32 - 10).div_euclid(2)
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct RaceDef {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Added to strength, dexterity, intelligence and luck in that order.
+    pub modifiers: [i32; 4],
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct ClassDef {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub hit_die: u32,
+    pub mana_per_level: u32,
+    pub primary_stat: Stat,
+}
+
+#[cfg(feature = "random")]
+pub const RACES: &[RaceDef] = &[
+    RaceDef { id: "human", name: "Human", modifiers: [1, 1, 1, 1] },
+    RaceDef { id: "elf", name: "Elf", modifiers: [-1, 2, 2, 0] },
+    RaceDef { id: "dwarf", name: "Dwarf", modifiers: [2, 0, 0, 1] },
+    RaceDef { id: "halfling", name: "Halfling", modifiers: [-1, 2, 0, 2] },
+    RaceDef { id: "orc", name: "Orc", modifiers: [3, 0, -1, 0] },
+];
+
+#[cfg(feature = "random")]
+pub const CLASSES: &[ClassDef] = &[
+    ClassDef { id: "warrior", name: "Warrior", hit_die: 10, mana_per_level: 0, primary_stat: Stat::Strength },
+    ClassDef { id: "rogue", name: "Rogue", hit_die: 8, mana_per_level: 1, primary_stat: Stat::Dexterity },
+    ClassDef { id: "mage", name: "Mage", hit_die: 6, mana_per_level: 6, primary_stat: Stat::Intelligence },
+    ClassDef { id: "cleric", name: "Cleric", hit_die: 8, mana_per_level: 4, primary_stat: Stat::Intelligence },
+    ClassDef { id: "gambler", name: "Gambler", hit_die: 6, mana_per_level: 2, primary_stat: Stat::Luck },
+];
+
+#[cfg(feature = "random")]
+pub fn find_race(id: &str) -> Result<&'static RaceDef, String> {
+    RACES.iter().find(|r| r.id == id).ok_or_else(|| format!("Unknown race: {}", id))
+}
+
+#[cfg(feature = "random")]
+pub fn find_class(id: &str) -> Result<&'static ClassDef, String> {
+    CLASSES.iter().find(|c| c.id == id).ok_or_else(|| format!("Unknown class: {}", id))
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatGeneration {
+    /// 4d6, dropping the lowest die, for each stat.
+    Rolled,
+    PointBuy,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GameCharacter {
+    pub name: String,
+    pub race: String,
+    pub class: String,
+    pub level: u32,
+    pub health: u32,
+    pub mana: u32,
+    pub generation: StatGeneration,
+    /// Scores before racial modifiers and level-up increases.
+    pub base_stats: CharacterStats,
+    pub stats: CharacterStats,
+}
+
+#[cfg(feature = "random")]
+impl GameCharacter {
+    /// Builds a level 1 character and derives its final stats, health and mana.
+    pub fn new(
+        name: &str,
+        race: &str,
+        class: &str,
+        generation: StatGeneration,
+        base_stats: CharacterStats,
+    ) -> Result<Self, String> {
+        let mut character = GameCharacter {
+            name: name.to_string(),
+            race: race.to_string(),
+            class: class.to_string(),
+            level: 1,
+            health: 0,
+            mana: 0,
+            generation,
+            stats: base_stats.clone(),
+            base_stats,
+        };
+        character.validate_base_stats()?;
+        character.recalculate()?;
+        Ok(character)
+    }
+
+    /// Recomputes stats, health and mana from base stats, race, class and level.
+    pub fn recalculate(&mut self) -> Result<(), String> {
+        let race = find_race(&self.race)?;
+        let class = find_class(&self.class)?;
+
+        let mut stats = CharacterStats::from_array(
+            self.base_stats
+                .to_array()
+                .iter()
+                .zip(race.modifiers)
+                .map(|(&base, modifier)| (base as i32 + modifier).max(1) as u32)
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        );
+        *stats.get_mut(class.primary_stat) += self.level / STAT_INCREASE_INTERVAL;
+
+        self.health = max_health(class, self.level, stats.strength);
+        self.mana = max_mana(class, self.level, stats.intelligence);
+        self.stats = stats;
+        Ok(())
+    }
+
+    pub fn level_up(&mut self) -> Result<(), String> {
+        if self.level >= MAX_LEVEL {
+            return Err(format!("{} is already at the maximum level ({})", self.name, MAX_LEVEL));
+        }
+        self.level += 1;
+        self.recalculate()
+    }
+
+    fn validate_base_stats(&self) -> Result<(), String> {
+        let scores = self.base_stats.to_array();
+        match self.generation {
+            StatGeneration::Rolled => {
+                if let Some(score) = scores.iter().find(|s| !(3..=18).contains(*s)) {
+                    return Err(format!("Rolled stats must be between 3 and 18, got {}", score));
+                }
+            }
+            StatGeneration::PointBuy => {
+                point_buy_cost(&self.base_stats)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks an imported character against the rules: known race and class,
+    /// a legal level and base stats, and derived values that match.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Character name cannot be empty".to_string());
+        }
+        if !(1..=MAX_LEVEL).contains(&self.level) {
+            return Err(format!("Level must be between 1 and {}", MAX_LEVEL));
+        }
+        self.validate_base_stats()?;
+
+        let mut expected = self.clone();
+        expected.recalculate()?;
+        if expected.stats != self.stats {
+            return Err("Stats do not match base stats, race and level".to_string());
+        }
+        if expected.health != self.health {
+            return Err(format!("Health should be {} for this character", expected.health));
+        }
+        if expected.mana != self.mana {
+            return Err(format!("Mana should be {} for this character", expected.mana));
+        }
+        Ok(())
+    }
+}
+
+/// Hit points: a full hit die at level 1, then the die's average per level,
+/// each adjusted by the strength modifier and never less than 1 per level.
+#[cfg(feature = "random")]
+pub fn max_health(class: &ClassDef, level: u32, strength: u32) -> u32 {
+    let modifier = stat_modifier(strength);
+    let first = (class.hit_die as i32 + modifier).max(1);
+    let per_level = ((class.hit_die / 2 + 1) as i32 + modifier).max(1);
+    (first + per_level * (level as i32 - 1)) as u32
+}
+
+/// Mana: the class's per-level mana plus the intelligence modifier, per level.
+#[cfg(feature = "random")]
+pub fn max_mana(class: &ClassDef, level: u32, intelligence: u32) -> u32 {
+    if class.mana_per_level == 0 {
+        return 0;
+    }
+    ((class.mana_per_level as i32 + stat_modifier(intelligence)).max(0) as u32) * level
+}
+
+/// Total point-buy cost of a set of base stats, or an error if they are illegal.
+#[cfg(feature = "random")]
+pub fn point_buy_cost(stats: &CharacterStats) -> Result<u32, String> {
+    let mut total = 0;
+    for score in stats.to_array() {
+        if !(8..=15).contains(&score) {
+            return Err(format!("Point-buy stats must be between 8 and 15, got {}", score));
+        }
+        total += POINT_BUY_COSTS[(score - 8) as usize];
+    }
+    if total > POINT_BUY_BUDGET {
+        return Err(format!("Point-buy costs {} points, the budget is {}", total, POINT_BUY_BUDGET));
+    }
+    Ok(total)
+}
+
+/// Rolls 4d6 and drops the lowest die for each stat.
+#[cfg(feature = "random")]
+pub fn roll_stats<R: Rng + ?Sized>(rng: &mut R) -> CharacterStats {
+    let mut roll = || {
+        let mut dice: Vec<u32> = (0..4).map(|_| rng.gen_range(1..=6)).collect();
+        dice.sort_unstable();
+        dice[1..].iter().sum()
+    };
+    CharacterStats::from_array([roll(), roll(), roll(), roll()])
+}
+
+#[cfg(feature = "random")]
+fn to_js<T: Serialize>(result: Result<T, String>) -> Result<JsValue, JsValue> {
+    result
+        .map(|value| serde_wasm_bindgen::to_value(&value).unwrap())
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn create_random_character(name: &str) -> JsValue {
+    let mut rng = thread_rng();
+    let race = RACES.choose(&mut rng).unwrap();
+    let class = CLASSES.choose(&mut rng).unwrap();
+
+    let mut character =
+        GameCharacter::new(name, race.id, class.id, StatGeneration::Rolled, roll_stats(&mut rng)).unwrap();
+    let target_level = rng.gen_range(1..=MAX_LEVEL);
+    while character.level < target_level {
+        character.level_up().unwrap();
+    }
+    
+    serde_wasm_bindgen::to_value(&character).unwrap()
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn roll_character(name: &str, race: &str, class: &str, seed: Option<u32>) -> Result<JsValue, JsValue> {
+    let mut rng = seeded_rng(seed);
+    to_js(GameCharacter::new(name, race, class, StatGeneration::Rolled, roll_stats(&mut rng)))
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn point_buy_character(
+    name: &str,
+    race: &str,
+    class: &str,
+    strength: u32,
+    dexterity: u32,
+    intelligence: u32,
+    luck: u32,
+) -> Result<JsValue, JsValue> {
+    let base = CharacterStats { strength, dexterity, intelligence, luck };
+    to_js(GameCharacter::new(name, race, class, StatGeneration::PointBuy, base))
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn level_up_character(character: JsValue) -> Result<JsValue, JsValue> {
+    let mut character: GameCharacter =
+        serde_wasm_bindgen::from_value(character).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    character.validate().map_err(|e| JsValue::from_str(&e))?;
+    to_js(character.level_up().map(|_| character))
+}
+
+/// Deserializes a character object and rejects it unless it follows the rules.
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn import_character(character: JsValue) -> Result<JsValue, JsValue> {
+    let character: GameCharacter =
+        serde_wasm_bindgen::from_value(character).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    to_js(character.validate().map(|_| character))
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize)]
+struct CharacterOptions {
+    races: &'static [RaceDef],
+    classes: &'static [ClassDef],
+    point_buy_budget: u32,
+    max_level: u32,
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn character_options() -> JsValue {
+    let options = CharacterOptions {
+        races: RACES,
+        classes: CLASSES,
+        point_buy_budget: POINT_BUY_BUDGET,
+        max_level: MAX_LEVEL,
+    };
+    serde_wasm_bindgen::to_value(&options).unwrap()
+}
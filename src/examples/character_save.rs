@@ -0,0 +1,161 @@
+#[cfg(feature = "random")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "random")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "random")]
+use serde_json::{json, Value};
+#[cfg(feature = "random")]
+use super::character::{GameCharacter, MAX_LEVEL};
+
+/// Version written by `save_character`. Bump it, and add a migration to
+/// `MIGRATIONS`, whenever the saved shape of `GameCharacter` changes.
+#[cfg(feature = "random")]
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+#[cfg(feature = "random")]
+type Migration = fn(Value) -> Result<Value, String>;
+
+/// `MIGRATIONS[i]` upgrades a save from version `i + 1` to version `i + 2`.
+#[cfg(feature = "random")]
+const MIGRATIONS: &[Migration] = &[migrate_v1_to_v2];
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    schema_version: u32,
+    saved_at: f64,
+    checksum: String,
+    character: Value,
+}
+
+/// Version 1 was the bare object returned by the original
+/// `create_random_character`: name, level, health, mana and stats only.
+/// Old characters become human warriors whose rolled base stats are their
+/// old stats; health and mana are re-derived under the current rules.
+#[cfg(feature = "random")]
+fn migrate_v1_to_v2(mut character: Value) -> Result<Value, String> {
+    let object = character.as_object_mut().ok_or("Version 1 save is not an object")?;
+    let stats = object.get("stats").cloned().ok_or("Version 1 save has no stats")?;
+
+    let mut base_stats = serde_json::Map::new();
+    for key in ["strength", "dexterity", "intelligence", "luck"] {
+        let score = stats
+            .get(key)
+            .and_then(Value::as_u64)
+            .ok_or_else(|| format!("Version 1 save has no valid {} stat", key))?;
+        base_stats.insert(key.to_string(), json!(score.clamp(3, 18)));
+    }
+    let level = object.get("level").and_then(Value::as_u64).unwrap_or(1).clamp(1, MAX_LEVEL as u64);
+
+    object.insert("race".to_string(), json!("human"));
+    object.insert("class".to_string(), json!("warrior"));
+    object.insert("generation".to_string(), json!("rolled"));
+    object.insert("level".to_string(), json!(level));
+    object.insert("base_stats".to_string(), Value::Object(base_stats));
+
+    let mut migrated: GameCharacter = serde_json::from_value(character).map_err(|e| e.to_string())?;
+    migrated.recalculate()?;
+    serde_json::to_value(&migrated).map_err(|e| e.to_string())
+}
+
+/// FNV-1a hash of the character's canonical JSON. This catches hand-edited
+/// or corrupted saves; it is not meant to stop a determined cheater.
+#[cfg(feature = "random")]
+fn checksum(character: &Value) -> String {
+    let hash = character.to_string().bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Serializes a character into a versioned, checksummed save blob.
+#[cfg(feature = "random")]
+pub fn save_character(character: &GameCharacter, saved_at: f64) -> Result<String, String> {
+    character.validate()?;
+    let character = serde_json::to_value(character).map_err(|e| e.to_string())?;
+    let save = SaveFile {
+        schema_version: CURRENT_SCHEMA_VERSION,
+        saved_at,
+        checksum: checksum(&character),
+        character,
+    };
+    serde_json::to_string_pretty(&save).map_err(|e| e.to_string())
+}
+
+/// Loads a save blob of any known version, migrating it to the current schema.
+///
+/// A JSON object without `schema_version` is treated as a version 1 character.
+#[cfg(feature = "random")]
+pub fn load_character(json: &str) -> Result<GameCharacter, String> {
+    let value: Value = serde_json::from_str(json).map_err(|e| format!("Save is not valid JSON: {}", e))?;
+
+    let (version, mut character) = match value.get("schema_version") {
+        None => (1, value),
+        Some(version) => {
+            let save: SaveFile =
+                serde_json::from_value(value.clone()).map_err(|e| format!("Malformed save file: {}", e))?;
+            if version.as_u64() != Some(save.schema_version as u64) {
+                return Err("Malformed save file: invalid schema_version".to_string());
+            }
+            if checksum(&save.character) != save.checksum {
+                return Err("Save checksum mismatch: the file was modified or corrupted".to_string());
+            }
+            (save.schema_version, save.character)
+        }
+    };
+
+    if version == 0 || version > CURRENT_SCHEMA_VERSION {
+        return Err(format!(
+            "Unsupported save version {} (this build reads versions 1 to {})",
+            version, CURRENT_SCHEMA_VERSION
+        ));
+    }
+    for migrate in &MIGRATIONS[(version - 1) as usize..] {
+        character = migrate(character)?;
+    }
+
+    let character: GameCharacter =
+        serde_json::from_value(character).map_err(|e| format!("Invalid character data: {}", e))?;
+    character.validate().map_err(|e| format!("Invalid character: {}", e))?;
+    Ok(character)
+}
+
+#[cfg(feature = "random")]
+fn local_storage() -> Result<web_sys::Storage, JsValue> {
+    web_sys::window()
+        .ok_or_else(|| JsValue::from_str("No window: localStorage is only available on the main thread"))?
+        .local_storage()?
+        .ok_or_else(|| JsValue::from_str("localStorage is not available"))
+}
+
+/// Returns a JSON save blob suitable for downloading.
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn save_character_json(character: JsValue) -> Result<String, JsValue> {
+    let character: GameCharacter =
+        serde_wasm_bindgen::from_value(character).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    save_character(&character, js_sys::Date::now()).map_err(|e| JsValue::from_str(&e))
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn load_character_json(json: &str) -> Result<JsValue, JsValue> {
+    let character = load_character(json).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&character).unwrap())
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn save_character_to_storage(key: &str, character: JsValue) -> Result<(), JsValue> {
+    let json = save_character_json(character)?;
+    local_storage()?.set_item(key, &json)
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn load_character_from_storage(key: &str) -> Result<JsValue, JsValue> {
+    let json = local_storage()?
+        .get_item(key)?
+        .ok_or_else(|| JsValue::from_str(&format!("No saved character under '{}'", key)))?;
+    load_character_json(&json)
+}
@@ -0,0 +1,169 @@
+#[cfg(feature = "math")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "math")]
+use nalgebra as na;
+#[cfg(feature = "math")]
+use serde::Serialize;
+#[cfg(feature = "math")]
+use super::matrix::{matrix_from_array, MatrixData};
+
+/// Relative tolerance for the symmetry check before Cholesky.
+#[cfg(feature = "math")]
+const SYMMETRY_TOLERANCE: f64 = 1e-10;
+
+#[cfg(feature = "math")]
+const SCHUR_MAX_ITERATIONS: usize = 10_000;
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct Factor {
+    pub name: String,
+    #[serde(flatten)]
+    pub matrix: MatrixData,
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct Decomposition {
+    pub kind: String,
+    /// The identity the factors satisfy, e.g. "PA = LU".
+    pub identity: String,
+    pub factors: Vec<Factor>,
+    /// Frobenius norm of the difference between both sides of `identity`.
+    pub reconstruction_error: f64,
+    /// `reconstruction_error` divided by the Frobenius norm of A.
+    pub relative_error: f64,
+}
+
+#[cfg(feature = "math")]
+fn factor(name: &str, matrix: &na::DMatrix<f64>) -> Factor {
+    Factor { name: name.to_string(), matrix: MatrixData::from(matrix) }
+}
+
+#[cfg(feature = "math")]
+fn decomposition(
+    kind: &str,
+    identity: &str,
+    factors: Vec<Factor>,
+    original: &na::DMatrix<f64>,
+    reconstructed: &na::DMatrix<f64>,
+) -> Decomposition {
+    let error = (original - reconstructed).norm();
+    let scale = original.norm();
+    Decomposition {
+        kind: kind.to_string(),
+        identity: identity.to_string(),
+        factors,
+        reconstruction_error: error,
+        relative_error: if scale > 0.0 { error / scale } else { error },
+    }
+}
+
+/// LU with partial (row) pivoting: PA = LU, with L unit lower triangular.
+#[cfg(feature = "math")]
+pub fn lu_decomposition(a: &na::DMatrix<f64>) -> Decomposition {
+    let lu = a.clone().lu();
+    let mut p = na::DMatrix::identity(a.nrows(), a.nrows());
+    lu.p().permute_rows(&mut p);
+    let (l, u) = (lu.l(), lu.u());
+    decomposition(
+        "lu",
+        "PA = LU",
+        vec![factor("P", &p), factor("L", &l), factor("U", &u)],
+        &(&p * a),
+        &(&l * &u),
+    )
+}
+
+/// Thin QR: A = QR with orthonormal columns in Q and upper triangular R.
+#[cfg(feature = "math")]
+pub fn qr_decomposition(a: &na::DMatrix<f64>) -> Decomposition {
+    let qr = a.clone().qr();
+    let (q, r) = (qr.q(), qr.r());
+    decomposition("qr", "A = QR", vec![factor("Q", &q), factor("R", &r)], a, &(&q * &r))
+}
+
+/// Thin SVD: A = U Σ Vᵀ with singular values in descending order.
+#[cfg(feature = "math")]
+pub fn svd_decomposition(a: &na::DMatrix<f64>) -> Decomposition {
+    // `svd` (unlike `svd_unordered`) already sorts the triplets.
+    let svd = a.clone().svd(true, true);
+    let (u, v_t) = (svd.u.unwrap(), svd.v_t.unwrap());
+    let sigma = na::DMatrix::from_diagonal(&svd.singular_values);
+
+    decomposition(
+        "svd",
+        "A = UΣVᵀ",
+        vec![factor("U", &u), factor("Σ", &sigma), factor("Vᵀ", &v_t)],
+        a,
+        &(&u * &sigma * &v_t),
+    )
+}
+
+/// A = LLᵀ for symmetric positive-definite A.
+#[cfg(feature = "math")]
+pub fn cholesky_decomposition(a: &na::DMatrix<f64>) -> Result<Decomposition, String> {
+    if !a.is_square() {
+        return Err("Cholesky decomposition needs a square matrix".to_string());
+    }
+    if (a - a.transpose()).norm() > SYMMETRY_TOLERANCE * a.norm().max(1.0) {
+        return Err("Cholesky decomposition needs a symmetric matrix".to_string());
+    }
+    let l = a
+        .clone()
+        .cholesky()
+        .ok_or("Matrix is not positive definite")?
+        .unpack();
+    Ok(decomposition("cholesky", "A = LLᵀ", vec![factor("L", &l)], a, &(&l * l.transpose())))
+}
+
+/// Real Schur form: A = Q T Qᵀ with orthogonal Q and quasi-upper-triangular
+/// T, whose 2x2 diagonal blocks hold complex-conjugate eigenvalue pairs.
+#[cfg(feature = "math")]
+pub fn schur_decomposition(a: &na::DMatrix<f64>) -> Result<Decomposition, String> {
+    if !a.is_square() {
+        return Err("Schur decomposition needs a square matrix".to_string());
+    }
+    let (q, t) = a
+        .clone()
+        .try_schur(f64::EPSILON, SCHUR_MAX_ITERATIONS)
+        .ok_or("Schur decomposition did not converge")?
+        .unpack();
+    Ok(decomposition("schur", "A = QTQᵀ", vec![factor("Q", &q), factor("T", &t)], a, &(&q * &t * q.transpose())))
+}
+
+/// `kind` is "lu", "qr", "svd", "cholesky" or "schur".
+#[cfg(feature = "math")]
+pub fn decompose(a: &na::DMatrix<f64>, kind: &str) -> Result<Decomposition, String> {
+    match kind {
+        "lu" => Ok(lu_decomposition(a)),
+        "qr" => Ok(qr_decomposition(a)),
+        "svd" => Ok(svd_decomposition(a)),
+        "cholesky" => cholesky_decomposition(a),
+        "schur" => schur_decomposition(a),
+        other => Err(format!("Unknown decomposition: {}", other)),
+    }
+}
+
+/// `data` holds `rows * cols` values in row-major order.
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub fn matrix_decomposition(data: &[f64], rows: u32, cols: u32, kind: &str) -> Result<JsValue, JsValue> {
+    let matrix = matrix_from_array(data, rows as usize, cols as usize).map_err(|e| JsValue::from_str(&e))?;
+    let result = decompose(&matrix, kind).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result).unwrap())
+}
+
+#[cfg(all(test, feature = "math"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn svd_factors_are_sorted_and_reconstruct() {
+        let a = na::DMatrix::from_row_slice(3, 2, &[1.0, 0.0, 0.0, 5.0, 0.0, 0.0]);
+        let svd = svd_decomposition(&a);
+        let sigma = &svd.factors[1].matrix.data;
+        assert!((sigma[0] - 5.0).abs() < 1e-12 && (sigma[3] - 1.0).abs() < 1e-12);
+        assert!(svd.reconstruction_error < 1e-12);
+    }
+}
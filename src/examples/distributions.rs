@@ -0,0 +1,305 @@
+#[cfg(feature = "random")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "random")]
+use rand::prelude::*;
+#[cfg(feature = "random")]
+use rand_distr::{Beta, Binomial, Exp, Gamma, LogNormal, Normal, Poisson, Zipf};
+#[cfg(feature = "random")]
+use serde::Serialize;
+#[cfg(feature = "random")]
+use super::random::seeded_rng;
+
+/// A configured sampler. Parameters, in order, for `from_params`:
+/// normal (mean, std_dev), log_normal (mu, sigma), exponential (lambda),
+/// poisson (lambda), binomial (n, p), beta (alpha, beta),
+/// gamma (shape, scale) and zipf (n, exponent s).
+#[cfg(feature = "random")]
+#[derive(Clone, Debug)]
+pub enum ProbabilityDistribution {
+    Normal(Normal<f64>, f64, f64),
+    LogNormal(LogNormal<f64>, f64, f64),
+    Exponential(Exp<f64>, f64),
+    Poisson(Poisson<f64>, f64),
+    Binomial(Binomial, u64, f64),
+    Beta(Beta<f64>, f64, f64),
+    Gamma(Gamma<f64>, f64, f64),
+    Zipf(Zipf<f64>, u64, f64),
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Copy, Debug, Default)]
+pub struct Moments {
+    pub mean: f64,
+    pub variance: f64,
+    pub skewness: f64,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct HistogramBin {
+    pub start: f64,
+    pub end: f64,
+    pub count: u32,
+    /// Count scaled so that the histogram integrates to one.
+    pub density: f64,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct SampleSummary {
+    pub distribution: String,
+    pub samples: usize,
+    pub min: f64,
+    pub max: f64,
+    pub empirical: Moments,
+    pub theoretical: Moments,
+    pub bins: Vec<HistogramBin>,
+}
+
+/// Above this many terms, H(n, s) switches from direct summation to an
+/// Euler–Maclaurin tail so huge Zipf supports stay cheap.
+#[cfg(feature = "random")]
+const HARMONIC_DIRECT_TERMS: u64 = 1_000_000;
+
+/// Upper bound on histogram bins accepted from JS.
+#[cfg(feature = "random")]
+pub const MAX_HISTOGRAM_BINS: u32 = 10_000;
+
+/// H(n, s) = Σ k^-s for k = 1..=n.
+#[cfg(feature = "random")]
+fn generalized_harmonic(n: u64, s: f64) -> f64 {
+    if n <= HARMONIC_DIRECT_TERMS {
+        return (1..=n).map(|k| (k as f64).powf(-s)).sum();
+    }
+    // Sum the first terms exactly, then approximate Σ f(k) for k = m..=n by
+    // ∫ f + (f(m) + f(n)) / 2 + Σ B₂ⱼ/(2j)! (f⁽²ʲ⁻¹⁾(n) - f⁽²ʲ⁻¹⁾(m)), with
+    // f(x) = x^-s. The neglected terms are far below f64 precision at m = 1000.
+    let m = 1000u64;
+    let head: f64 = (1..m).map(|k| (k as f64).powf(-s)).sum();
+    let (a, b) = (m as f64, n as f64);
+    let f = |x: f64| x.powf(-s);
+    let one_minus_s = 1.0 - s;
+    let log_ratio = (b / a).ln();
+    let integral = if one_minus_s.abs() < 1e-12 {
+        log_ratio
+    } else {
+        a.powf(one_minus_s) * (one_minus_s * log_ratio).exp_m1() / one_minus_s
+    };
+    // Odd derivatives f⁽ʲ⁾(x) = -s(s+1)...(s+j-1) x^(-s-j).
+    let derivative = |order: i32, x: f64| -(0..order).map(|i| s + i as f64).product::<f64>() * x.powf(-s - order as f64);
+    let corrections: f64 = [(1, 1.0 / 12.0), (3, -1.0 / 720.0), (5, 1.0 / 30240.0)]
+        .iter()
+        .map(|&(order, weight)| weight * (derivative(order, b) - derivative(order, a)))
+        .sum();
+    head + integral + (f(a) + f(b)) / 2.0 + corrections
+}
+
+#[cfg(feature = "random")]
+fn count_param(value: f64, name: &str) -> Result<u64, String> {
+    if value >= 0.0 && value.fract() == 0.0 && value <= u64::MAX as f64 {
+        Ok(value as u64)
+    } else {
+        Err(format!("{} must be a non-negative integer", name))
+    }
+}
+
+#[cfg(feature = "random")]
+impl ProbabilityDistribution {
+    pub fn from_params(kind: &str, a: f64, b: f64) -> Result<Self, String> {
+        let invalid = |e: &dyn std::fmt::Display| format!("Invalid {} parameters: {}", kind, e);
+        Ok(match kind {
+            "normal" => ProbabilityDistribution::Normal(Normal::new(a, b).map_err(|e| invalid(&e))?, a, b),
+            "log_normal" => {
+                ProbabilityDistribution::LogNormal(LogNormal::new(a, b).map_err(|e| invalid(&e))?, a, b)
+            }
+            "exponential" => ProbabilityDistribution::Exponential(Exp::new(a).map_err(|e| invalid(&e))?, a),
+            "poisson" => ProbabilityDistribution::Poisson(Poisson::new(a).map_err(|e| invalid(&e))?, a),
+            "binomial" => {
+                let n = count_param(a, "n")?;
+                ProbabilityDistribution::Binomial(Binomial::new(n, b).map_err(|e| invalid(&e))?, n, b)
+            }
+            "beta" => ProbabilityDistribution::Beta(Beta::new(a, b).map_err(|e| invalid(&e))?, a, b),
+            "gamma" => ProbabilityDistribution::Gamma(Gamma::new(a, b).map_err(|e| invalid(&e))?, a, b),
+            "zipf" => {
+                let n = count_param(a, "n")?;
+                ProbabilityDistribution::Zipf(Zipf::new(n, b).map_err(|e| invalid(&e))?, n, b)
+            }
+            other => return Err(format!("Unknown distribution: {}", other)),
+        })
+    }
+
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        match self {
+            ProbabilityDistribution::Normal(d, ..) => d.sample(rng),
+            ProbabilityDistribution::LogNormal(d, ..) => d.sample(rng),
+            ProbabilityDistribution::Exponential(d, _) => d.sample(rng),
+            ProbabilityDistribution::Poisson(d, _) => d.sample(rng),
+            ProbabilityDistribution::Binomial(d, ..) => d.sample(rng) as f64,
+            ProbabilityDistribution::Beta(d, ..) => d.sample(rng),
+            ProbabilityDistribution::Gamma(d, ..) => d.sample(rng),
+            ProbabilityDistribution::Zipf(d, ..) => d.sample(rng),
+        }
+    }
+
+    pub fn sample_n<R: Rng + ?Sized>(&self, count: usize, rng: &mut R) -> Vec<f64> {
+        (0..count).map(|_| self.sample(rng)).collect()
+    }
+
+    /// Closed-form mean, variance and skewness.
+    pub fn theoretical_moments(&self) -> Moments {
+        let (mean, variance, skewness) = match *self {
+            ProbabilityDistribution::Normal(_, mean, sd) => (mean, sd * sd, 0.0),
+            ProbabilityDistribution::LogNormal(_, mu, sigma) => {
+                let s2 = sigma * sigma;
+                (
+                    (mu + s2 / 2.0).exp(),
+                    (s2.exp() - 1.0) * (2.0 * mu + s2).exp(),
+                    (s2.exp() + 2.0) * (s2.exp() - 1.0).sqrt(),
+                )
+            }
+            ProbabilityDistribution::Exponential(_, lambda) => (1.0 / lambda, 1.0 / (lambda * lambda), 2.0),
+            ProbabilityDistribution::Poisson(_, lambda) => (lambda, lambda, 1.0 / lambda.sqrt()),
+            ProbabilityDistribution::Binomial(_, n, p) => {
+                let n = n as f64;
+                let variance = n * p * (1.0 - p);
+                (n * p, variance, (1.0 - 2.0 * p) / variance.sqrt())
+            }
+            ProbabilityDistribution::Beta(_, a, b) => (
+                a / (a + b),
+                a * b / ((a + b).powi(2) * (a + b + 1.0)),
+                2.0 * (b - a) * (a + b + 1.0).sqrt() / ((a + b + 2.0) * (a * b).sqrt()),
+            ),
+            ProbabilityDistribution::Gamma(_, shape, scale) => (shape * scale, shape * scale * scale, 2.0 / shape.sqrt()),
+            ProbabilityDistribution::Zipf(_, n, s) => {
+                // Raw moments E[X^k] = H(n, s - k) / H(n, s).
+                let h = generalized_harmonic(n, s);
+                let raw = |k: f64| generalized_harmonic(n, s - k) / h;
+                let (m1, m2, m3) = (raw(1.0), raw(2.0), raw(3.0));
+                let variance = m2 - m1 * m1;
+                (m1, variance, (m3 - 3.0 * m1 * variance - m1.powi(3)) / variance.powf(1.5))
+            }
+        };
+        Moments { mean, variance, skewness }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProbabilityDistribution::Normal(..) => "normal",
+            ProbabilityDistribution::LogNormal(..) => "log_normal",
+            ProbabilityDistribution::Exponential(..) => "exponential",
+            ProbabilityDistribution::Poisson(..) => "poisson",
+            ProbabilityDistribution::Binomial(..) => "binomial",
+            ProbabilityDistribution::Beta(..) => "beta",
+            ProbabilityDistribution::Gamma(..) => "gamma",
+            ProbabilityDistribution::Zipf(..) => "zipf",
+        }
+    }
+}
+
+/// Sample mean, (unbiased) variance and skewness.
+#[cfg(feature = "random")]
+pub fn sample_moments(samples: &[f64]) -> Moments {
+    let n = samples.len() as f64;
+    if samples.len() < 2 {
+        return Moments { mean: samples.first().copied().unwrap_or(f64::NAN), variance: f64::NAN, skewness: f64::NAN };
+    }
+    let mean = samples.iter().sum::<f64>() / n;
+    let (m2, m3) = samples.iter().fold((0.0, 0.0), |(m2, m3), &x| {
+        let d = x - mean;
+        (m2 + d * d, m3 + d * d * d)
+    });
+    let population_variance = m2 / n;
+    Moments {
+        mean,
+        variance: m2 / (n - 1.0),
+        skewness: (m3 / n) / population_variance.powf(1.5),
+    }
+}
+
+/// Equal-width histogram spanning the sample range.
+#[cfg(feature = "random")]
+pub fn histogram(samples: &[f64], bins: usize) -> Vec<HistogramBin> {
+    if samples.is_empty() || bins == 0 {
+        return Vec::new();
+    }
+    let min = samples.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = samples.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let width = if max > min { (max - min) / bins as f64 } else { 1.0 };
+
+    let mut counts = vec![0u32; bins];
+    for &x in samples {
+        let index = (((x - min) / width) as usize).min(bins - 1);
+        counts[index] += 1;
+    }
+
+    let total = samples.len() as f64;
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| HistogramBin {
+            start: min + i as f64 * width,
+            end: min + (i + 1) as f64 * width,
+            count,
+            density: count as f64 / (total * width),
+        })
+        .collect()
+}
+
+#[cfg(feature = "random")]
+pub fn summarize_samples(distribution: &ProbabilityDistribution, samples: &[f64], bins: usize) -> SampleSummary {
+    SampleSummary {
+        distribution: distribution.name().to_string(),
+        samples: samples.len(),
+        min: samples.iter().copied().fold(f64::INFINITY, f64::min),
+        max: samples.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        empirical: sample_moments(samples),
+        theoretical: distribution.theoretical_moments(),
+        bins: histogram(samples, bins),
+    }
+}
+
+/// Draws `count` samples into a `Float64Array`.
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn sample_distribution(kind: &str, a: f64, b: f64, count: u32, seed: Option<u32>) -> Result<js_sys::Float64Array, JsValue> {
+    let distribution = ProbabilityDistribution::from_params(kind, a, b).map_err(|e| JsValue::from_str(&e))?;
+    let mut rng = seeded_rng(seed);
+    let samples = distribution.sample_n(count as usize, &mut rng);
+    Ok(js_sys::Float64Array::from(&samples[..]))
+}
+
+/// Draws `count` samples and returns a histogram with empirical and
+/// theoretical moments side by side.
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn distribution_summary(kind: &str, a: f64, b: f64, count: u32, bins: u32, seed: Option<u32>) -> Result<JsValue, JsValue> {
+    if bins > MAX_HISTOGRAM_BINS {
+        return Err(JsValue::from_str(&format!("Use at most {} bins", MAX_HISTOGRAM_BINS)));
+    }
+    let distribution = ProbabilityDistribution::from_params(kind, a, b).map_err(|e| JsValue::from_str(&e))?;
+    let mut rng = seeded_rng(seed);
+    let samples = distribution.sample_n(count as usize, &mut rng);
+    let summary = summarize_samples(&distribution, &samples, bins as usize);
+    Ok(serde_wasm_bindgen::to_value(&summary).unwrap())
+}
+
+#[cfg(all(test, feature = "random"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn harmonic_tail_matches_direct_sum() {
+        let n = 3 * HARMONIC_DIRECT_TERMS;
+        for s in [0.5, 1.0, 1.2, 2.0, -1.0, -2.5] {
+            let direct: f64 = (1..=n).map(|k| (k as f64).powf(-s)).sum();
+            let approx = generalized_harmonic(n, s);
+            assert!(((approx - direct) / direct).abs() < 1e-12, "s = {}: {} vs {}", s, approx, direct);
+        }
+    }
+
+    #[test]
+    fn huge_zipf_support_has_finite_moments() {
+        let moments = ProbabilityDistribution::from_params("zipf", 1e15, 1.5).unwrap().theoretical_moments();
+        assert!(moments.mean.is_finite() && moments.variance > 0.0);
+    }
+}
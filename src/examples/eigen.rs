@@ -0,0 +1,219 @@
+#[cfg(feature = "math")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "math")]
+use nalgebra as na;
+#[cfg(feature = "math")]
+use na::Complex;
+#[cfg(feature = "math")]
+use serde::Serialize;
+#[cfg(feature = "math")]
+use super::matrix::matrix_from_array;
+
+#[cfg(feature = "math")]
+const SYMMETRY_TOLERANCE: f64 = 1e-10;
+
+/// Eigenvalues closer than this (relative to the matrix scale) are treated
+/// as one repeated eigenvalue. Defective eigenvalues are only computed to
+/// about sqrt(epsilon), so this has to be far looser than machine precision.
+#[cfg(feature = "math")]
+const CLUSTER_TOLERANCE: f64 = 1e-6;
+
+#[cfg(feature = "math")]
+const SCHUR_MAX_ITERATIONS: usize = 10_000;
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct ComplexValue {
+    pub re: f64,
+    pub im: f64,
+}
+
+#[cfg(feature = "math")]
+impl From<Complex<f64>> for ComplexValue {
+    fn from(c: Complex<f64>) -> Self {
+        ComplexValue { re: c.re, im: c.im }
+    }
+}
+
+#[cfg(feature = "math")]
+impl ComplexValue {
+    /// Formats as "a", "bi" or "a ± bi" with the given precision.
+    pub fn format(&self, precision: usize) -> String {
+        let threshold = 0.5 * 10f64.powi(-(precision as i32));
+        if self.im.abs() < threshold {
+            format!("{:.*}", precision, self.re)
+        } else if self.re.abs() < threshold {
+            format!("{:.*}i", precision, self.im)
+        } else {
+            let sign = if self.im < 0.0 { '-' } else { '+' };
+            format!("{:.*} {} {:.*}i", precision, self.re, sign, precision, self.im.abs())
+        }
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct Eigenspace {
+    pub eigenvalue: ComplexValue,
+    pub algebraic_multiplicity: usize,
+    pub geometric_multiplicity: usize,
+    /// A basis of unit eigenvectors, each scaled so its largest entry is real.
+    pub eigenvectors: Vec<Vec<ComplexValue>>,
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct EigenAnalysis {
+    pub size: usize,
+    /// True when the input was symmetric and `symmetric_eigen` was used.
+    pub symmetric_path: bool,
+    /// All eigenvalues, repeated by multiplicity.
+    pub eigenvalues: Vec<ComplexValue>,
+    pub eigenspaces: Vec<Eigenspace>,
+    pub has_complex_eigenvalues: bool,
+    /// Whether every geometric multiplicity matches its algebraic one.
+    pub diagonalizable: bool,
+}
+
+#[cfg(feature = "math")]
+pub fn is_symmetric(a: &na::DMatrix<f64>) -> bool {
+    a.is_square() && (a - a.transpose()).norm() <= SYMMETRY_TOLERANCE * a.norm().max(1.0)
+}
+
+/// Sorts eigenvalues by descending real part, then descending imaginary part.
+#[cfg(feature = "math")]
+fn sort_eigenvalues(values: &mut [Complex<f64>]) {
+    values.sort_by(|a, b| b.re.total_cmp(&a.re).then(b.im.total_cmp(&a.im)));
+}
+
+/// Groups nearly equal eigenvalues, returning each cluster's mean and size.
+#[cfg(feature = "math")]
+fn cluster_eigenvalues(sorted: &[Complex<f64>], tolerance: f64) -> Vec<(Complex<f64>, usize)> {
+    let mut clusters: Vec<(Complex<f64>, usize)> = Vec::new();
+    for &value in sorted {
+        match clusters.iter_mut().find(|(mean, _)| (mean - value).norm() <= tolerance) {
+            Some((mean, count)) => {
+                *mean = (*mean * *count as f64 + value) / (*count as f64 + 1.0);
+                *count += 1;
+            }
+            None => clusters.push((value, 1)),
+        }
+    }
+    clusters
+}
+
+/// Normalises to unit length and rotates the phase so the largest
+/// component is real and positive.
+#[cfg(feature = "math")]
+fn normalize_eigenvector(v: &na::DVector<Complex<f64>>) -> Vec<ComplexValue> {
+    let pivot = v.iter().copied().max_by(|a, b| a.norm().total_cmp(&b.norm())).unwrap_or(Complex::new(1.0, 0.0));
+    let phase = if pivot.norm() > 0.0 { pivot.conj() / pivot.norm() } else { Complex::new(1.0, 0.0) };
+    let norm = v.norm();
+    v.iter().map(|&c| ComplexValue::from(c * phase / norm)).collect()
+}
+
+/// Basis of the null space of A - λI, from the right singular vectors with
+/// negligible singular values.
+#[cfg(feature = "math")]
+fn eigenspace_basis(a: &na::DMatrix<f64>, lambda: Complex<f64>, tolerance: f64) -> Vec<Vec<ComplexValue>> {
+    let n = a.nrows();
+    let shifted = na::DMatrix::from_fn(n, n, |i, j| {
+        Complex::new(a[(i, j)], 0.0) - if i == j { lambda } else { Complex::new(0.0, 0.0) }
+    });
+    let svd = shifted.svd(false, true);
+    let v_h = svd.v_t.unwrap();
+    (0..n)
+        .filter(|&k| svd.singular_values[k] <= tolerance)
+        .map(|k| normalize_eigenvector(&v_h.row(k).adjoint()))
+        .collect()
+}
+
+#[cfg(feature = "math")]
+fn symmetric_analysis(a: &na::DMatrix<f64>, tolerance: f64) -> EigenAnalysis {
+    let eigen = a.clone().symmetric_eigen();
+    let mut order: Vec<usize> = (0..a.nrows()).collect();
+    order.sort_by(|&i, &j| eigen.eigenvalues[j].total_cmp(&eigen.eigenvalues[i]));
+
+    let mut eigenspaces: Vec<Eigenspace> = Vec::new();
+    for &i in &order {
+        let value = eigen.eigenvalues[i];
+        let vector = eigen.eigenvectors.column(i).map(|x| Complex::new(x, 0.0));
+        let vector = normalize_eigenvector(&vector);
+        match eigenspaces.last_mut() {
+            Some(space) if (space.eigenvalue.re - value).abs() <= tolerance => {
+                space.algebraic_multiplicity += 1;
+                space.geometric_multiplicity += 1;
+                space.eigenvectors.push(vector);
+            }
+            _ => eigenspaces.push(Eigenspace {
+                eigenvalue: ComplexValue { re: value, im: 0.0 },
+                algebraic_multiplicity: 1,
+                geometric_multiplicity: 1,
+                eigenvectors: vec![vector],
+            }),
+        }
+    }
+
+    EigenAnalysis {
+        size: a.nrows(),
+        symmetric_path: true,
+        eigenvalues: order.iter().map(|&i| ComplexValue { re: eigen.eigenvalues[i], im: 0.0 }).collect(),
+        eigenspaces,
+        has_complex_eigenvalues: false,
+        diagonalizable: true,
+    }
+}
+
+#[cfg(feature = "math")]
+pub fn eigen_analysis(a: &na::DMatrix<f64>) -> Result<EigenAnalysis, String> {
+    if !a.is_square() {
+        return Err("Eigen-analysis needs a square matrix".to_string());
+    }
+    if a.iter().any(|v| !v.is_finite()) {
+        return Err("Matrix entries must be finite numbers".to_string());
+    }
+    let tolerance = CLUSTER_TOLERANCE * a.norm().max(1.0);
+    if is_symmetric(a) {
+        return Ok(symmetric_analysis(a, tolerance));
+    }
+
+    let schur = a
+        .clone()
+        .try_schur(f64::EPSILON, SCHUR_MAX_ITERATIONS)
+        .ok_or("Eigenvalue iteration did not converge")?;
+    let mut values: Vec<Complex<f64>> = schur.complex_eigenvalues().iter().copied().collect();
+    sort_eigenvalues(&mut values);
+
+    let eigenspaces: Vec<Eigenspace> = cluster_eigenvalues(&values, tolerance)
+        .into_iter()
+        .map(|(lambda, algebraic)| {
+            // Snap conjugate noise so real eigenvalues get real eigenvectors.
+            let lambda = if lambda.im.abs() <= tolerance { Complex::new(lambda.re, 0.0) } else { lambda };
+            let eigenvectors = eigenspace_basis(a, lambda, tolerance);
+            Eigenspace {
+                eigenvalue: ComplexValue::from(lambda),
+                algebraic_multiplicity: algebraic,
+                geometric_multiplicity: eigenvectors.len().min(algebraic),
+                eigenvectors: eigenvectors.into_iter().take(algebraic).collect(),
+            }
+        })
+        .collect();
+
+    Ok(EigenAnalysis {
+        size: a.nrows(),
+        symmetric_path: false,
+        has_complex_eigenvalues: eigenspaces.iter().any(|s| s.eigenvalue.im != 0.0),
+        diagonalizable: eigenspaces.iter().all(|s| s.geometric_multiplicity == s.algebraic_multiplicity),
+        eigenvalues: values.into_iter().map(ComplexValue::from).collect(),
+        eigenspaces,
+    })
+}
+
+/// `data` holds `size * size` values in row-major order.
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub fn matrix_eigen_analysis(data: &[f64], size: u32) -> Result<JsValue, JsValue> {
+    let matrix = matrix_from_array(data, size as usize, size as usize).map_err(|e| JsValue::from_str(&e))?;
+    let analysis = eigen_analysis(&matrix).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&analysis).unwrap())
+}
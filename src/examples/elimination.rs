@@ -0,0 +1,410 @@
+#[cfg(feature = "math")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "math")]
+use num_rational::BigRational;
+#[cfg(feature = "math")]
+use num_traits::{One, Signed, Zero};
+#[cfg(feature = "math")]
+use serde::Serialize;
+#[cfg(feature = "math")]
+use std::str::FromStr;
+
+/// Parses "3", "-3/7", "0.25" or "-1.5" into an exact rational. Decimals
+/// are read digit for digit, so "0.1" is exactly 1/10.
+#[cfg(feature = "math")]
+pub fn parse_rational(text: &str) -> Result<BigRational, String> {
+    let text = text.trim();
+    let invalid = || format!("Invalid number: {}", text);
+    if let Some((whole, fraction)) = text.split_once('.') {
+        if fraction.is_empty() || !fraction.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let digits = format!("{}{}", whole, fraction);
+        let denominator = format!("1{}", "0".repeat(fraction.len()));
+        return BigRational::from_str(&format!("{}/{}", digits, denominator)).map_err(|_| invalid());
+    }
+    BigRational::from_str(text).map_err(|_| invalid())
+}
+
+/// An augmented matrix [A | B] of exact rationals.
+#[cfg(feature = "math")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AugmentedMatrix {
+    pub rows: Vec<Vec<BigRational>>,
+    /// Columns left of the bar; the rest are right-hand sides.
+    pub coefficient_columns: usize,
+}
+
+#[cfg(feature = "math")]
+impl AugmentedMatrix {
+    /// Rows are separated by newlines or ';', entries by spaces or commas.
+    /// A '|' marks where the right-hand side starts; without one the last
+    /// column is taken as the right-hand side.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut rows = Vec::new();
+        let mut bar: Option<usize> = None;
+
+        for line in text.split([';', '\n']).map(str::trim).filter(|l| !l.is_empty()) {
+            let (left, right) = match line.split_once('|') {
+                Some((left, right)) => (left, Some(right)),
+                None => (line, None),
+            };
+            let split = |part: &str| -> Result<Vec<BigRational>, String> {
+                part.split([' ', ',', '\t']).filter(|s| !s.is_empty()).map(parse_rational).collect()
+            };
+            let mut row = split(left)?;
+            let row_bar = right.map(|_| row.len());
+            if let Some(right) = right {
+                row.extend(split(right)?);
+            }
+            match (rows.is_empty(), bar, row_bar) {
+                (true, _, _) => bar = row_bar,
+                (false, a, b) if a != b => return Err("Every row needs the '|' in the same column".to_string()),
+                _ => {}
+            }
+            rows.push(row);
+        }
+
+        let width = rows.first().map(|r| r.len()).ok_or("The matrix is empty")?;
+        if rows.iter().any(|r| r.len() != width) {
+            return Err("Every row needs the same number of entries".to_string());
+        }
+        let coefficient_columns = bar.unwrap_or(width.saturating_sub(1));
+        if coefficient_columns == 0 || coefficient_columns >= width {
+            return Err("An augmented matrix needs coefficient and right-hand-side columns".to_string());
+        }
+        Ok(AugmentedMatrix { rows, coefficient_columns })
+    }
+
+    pub fn to_strings(&self) -> Vec<Vec<String>> {
+        self.rows.iter().map(|row| row.iter().map(|v| v.to_string()).collect()).collect()
+    }
+
+    pub fn to_latex(&self) -> String {
+        let width = self.rows[0].len();
+        let spec = format!("{}|{}", "r".repeat(self.coefficient_columns), "r".repeat(width - self.coefficient_columns));
+        let body: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(rational_to_latex).collect::<Vec<_>>().join(" & "))
+            .collect();
+        format!("\\left[\\begin{{array}}{{{}}} {} \\end{{array}}\\right]", spec, body.join(" \\\\ "))
+    }
+}
+
+#[cfg(feature = "math")]
+pub fn rational_to_latex(value: &BigRational) -> String {
+    if value.is_integer() {
+        value.to_string()
+    } else {
+        let sign = if value.is_negative() { "-" } else { "" };
+        format!("{}\\frac{{{}}}{{{}}}", sign, value.numer().abs(), value.denom())
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RowOperation {
+    Swap { first: usize, second: usize },
+    Scale { row: usize, factor: String },
+    AddMultiple { target: usize, source: usize, factor: String },
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct EliminationStep {
+    /// "forward" while reaching row echelon form, "backward" afterwards.
+    pub phase: String,
+    pub operation: RowOperation,
+    /// Human-readable form, rows numbered from 1, e.g. "R2 → R2 - 3/2·R1".
+    pub description: String,
+    pub latex: String,
+    pub matrix: Vec<Vec<String>>,
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct EliminationResult {
+    pub initial: Vec<Vec<String>>,
+    pub steps: Vec<EliminationStep>,
+    pub rref: Vec<Vec<String>>,
+    /// Zero-based pivot columns of the RREF.
+    pub pivot_columns: Vec<usize>,
+    pub rank: usize,
+    pub consistent: bool,
+    /// For a single right-hand side: one line per variable, with free
+    /// variables written as themselves. Empty when inconsistent.
+    pub solution: Vec<String>,
+    pub free_variables: Vec<String>,
+    /// The whole elimination as an `aligned` block of arrows and matrices.
+    pub latex: String,
+}
+
+#[cfg(feature = "math")]
+fn coefficient_text(factor: &BigRational) -> String {
+    if factor.is_integer() {
+        factor.to_string()
+    } else {
+        format!("({})", factor)
+    }
+}
+
+/// "3/2·R1" and friends for descriptions: `+ R1`, `- 3/2·R1`.
+#[cfg(feature = "math")]
+fn signed_multiple(factor: &BigRational, row: &str, separator: &str) -> String {
+    let magnitude = factor.abs();
+    let sign = if factor.is_negative() { "-" } else { "+" };
+    if magnitude.is_one() {
+        format!("{} {}", sign, row)
+    } else {
+        format!("{} {}{}{}", sign, magnitude, separator, row)
+    }
+}
+
+#[cfg(feature = "math")]
+struct Eliminator {
+    matrix: AugmentedMatrix,
+    steps: Vec<EliminationStep>,
+    latex_matrices: Vec<String>,
+}
+
+#[cfg(feature = "math")]
+impl Eliminator {
+    fn record(&mut self, phase: &str, operation: RowOperation, description: String, latex: String) {
+        self.steps.push(EliminationStep {
+            phase: phase.to_string(),
+            operation,
+            description,
+            latex,
+            matrix: self.matrix.to_strings(),
+        });
+        self.latex_matrices.push(self.matrix.to_latex());
+    }
+
+    fn swap(&mut self, phase: &str, a: usize, b: usize) {
+        self.matrix.rows.swap(a, b);
+        self.record(
+            phase,
+            RowOperation::Swap { first: a, second: b },
+            format!("R{} ↔ R{}", a + 1, b + 1),
+            format!("R_{{{}}} \\leftrightarrow R_{{{}}}", a + 1, b + 1),
+        );
+    }
+
+    fn scale(&mut self, phase: &str, row: usize, factor: BigRational) {
+        for value in &mut self.matrix.rows[row] {
+            *value = &*value * &factor;
+        }
+        let latex = format!("R_{{{}}} \\to {} R_{{{}}}", row + 1, rational_to_latex(&factor), row + 1);
+        self.record(
+            phase,
+            RowOperation::Scale { row, factor: factor.to_string() },
+            format!("R{} → {}·R{}", row + 1, coefficient_text(&factor), row + 1),
+            latex,
+        );
+    }
+
+    fn add_multiple(&mut self, phase: &str, target: usize, source: usize, factor: BigRational) {
+        let source_row = self.matrix.rows[source].clone();
+        for (value, s) in self.matrix.rows[target].iter_mut().zip(&source_row) {
+            *value = &*value + &factor * s;
+        }
+        let magnitude = factor.abs();
+        let latex_multiple = if magnitude.is_one() { String::new() } else { format!("{} ", rational_to_latex(&magnitude)) };
+        let latex = format!(
+            "R_{{{}}} \\to R_{{{}}} {} {}R_{{{}}}",
+            target + 1,
+            target + 1,
+            if factor.is_negative() { "-" } else { "+" },
+            latex_multiple,
+            source + 1
+        );
+        self.record(
+            phase,
+            RowOperation::AddMultiple { target, source, factor: factor.to_string() },
+            format!("R{} → R{} {}", target + 1, target + 1, signed_multiple(&factor, &format!("R{}", source + 1), "·")),
+            latex,
+        );
+    }
+}
+
+/// Reduces to RREF in two phases: forward elimination to row echelon form
+/// (swaps and eliminating below pivots), then backward (scaling pivots to 1
+/// and eliminating above them). Every operation is recorded.
+#[cfg(feature = "math")]
+pub fn gaussian_elimination(matrix: &AugmentedMatrix) -> EliminationResult {
+    let mut e = Eliminator { matrix: matrix.clone(), steps: Vec::new(), latex_matrices: Vec::new() };
+    let (rows, width) = (matrix.rows.len(), matrix.rows[0].len());
+
+    let mut pivots: Vec<(usize, usize)> = Vec::new();
+    let mut pivot_row = 0;
+    for column in 0..width {
+        if pivot_row == rows {
+            break;
+        }
+        let Some(found) = (pivot_row..rows).find(|&r| !e.matrix.rows[r][column].is_zero()) else {
+            continue;
+        };
+        if found != pivot_row {
+            e.swap("forward", pivot_row, found);
+        }
+        for r in pivot_row + 1..rows {
+            if !e.matrix.rows[r][column].is_zero() {
+                let factor = -(&e.matrix.rows[r][column] / &e.matrix.rows[pivot_row][column]);
+                e.add_multiple("forward", r, pivot_row, factor);
+            }
+        }
+        pivots.push((pivot_row, column));
+        pivot_row += 1;
+    }
+
+    for &(row, column) in pivots.iter().rev() {
+        let pivot = e.matrix.rows[row][column].clone();
+        if !pivot.is_one() {
+            e.scale("backward", row, pivot.recip());
+        }
+        for r in 0..row {
+            if !e.matrix.rows[r][column].is_zero() {
+                let factor = -e.matrix.rows[r][column].clone();
+                e.add_multiple("backward", r, row, factor);
+            }
+        }
+    }
+
+    let n = matrix.coefficient_columns;
+    let pivot_columns: Vec<usize> = pivots.iter().map(|&(_, c)| c).collect();
+    let consistent = pivot_columns.iter().all(|&c| c < n);
+    let free: Vec<usize> = (0..n).filter(|c| !pivot_columns.contains(c)).collect();
+
+    let solution = if consistent && width == n + 1 {
+        (0..n)
+            .map(|var| match pivots.iter().find(|&&(_, c)| c == var) {
+                Some(&(row, _)) => {
+                    let values = &e.matrix.rows[row];
+                    let mut text = values[n].to_string();
+                    let mut has_constant = !values[n].is_zero();
+                    for &f in &free {
+                        if values[f].is_zero() {
+                            continue;
+                        }
+                        // x_var = rhs - Σ a_f x_f
+                        let term = signed_multiple(&-values[f].clone(), &format!("x{}", f + 1), "·");
+                        text = if has_constant {
+                            format!("{} {}", text, term)
+                        } else {
+                            term.trim_start_matches("+ ").replacen("- ", "-", 1)
+                        };
+                        has_constant = true;
+                    }
+                    format!("x{} = {}", var + 1, text)
+                }
+                None => format!("x{} is free", var + 1),
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut latex = format!("\\begin{{aligned}}\n& {}", matrix.to_latex());
+    for (step, matrix_latex) in e.steps.iter().zip(&e.latex_matrices) {
+        latex.push_str(&format!(" \\\\\n\\xrightarrow{{{}}} & {}", step.latex, matrix_latex));
+    }
+    latex.push_str("\n\\end{aligned}");
+
+    EliminationResult {
+        initial: matrix.to_strings(),
+        rref: e.matrix.to_strings(),
+        steps: e.steps,
+        rank: pivot_columns.iter().filter(|&&c| c < n).count(),
+        pivot_columns,
+        consistent,
+        solution,
+        free_variables: free.iter().map(|f| format!("x{}", f + 1)).collect(),
+        latex,
+    }
+}
+
+/// `augmented` is text such as "2 1 -1 | 8; -3 -1 2 | -11; -2 1 2 | -3".
+/// Entries may be integers, fractions ("3/7") or decimals.
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub fn gaussian_elimination_steps(augmented: &str) -> Result<JsValue, JsValue> {
+    let matrix = AugmentedMatrix::parse(augmented).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&gaussian_elimination(&matrix)).unwrap())
+}
+
+#[cfg(all(test, feature = "math"))]
+mod tests {
+    use super::*;
+
+    fn ratio(numerator: i64, denominator: i64) -> BigRational {
+        BigRational::new(numerator.into(), denominator.into())
+    }
+
+    fn eliminate(text: &str) -> EliminationResult {
+        gaussian_elimination(&AugmentedMatrix::parse(text).unwrap())
+    }
+
+    #[test]
+    fn parses_exact_rationals() {
+        assert_eq!(parse_rational("0.1").unwrap(), ratio(1, 10));
+        assert_eq!(parse_rational("-3/7").unwrap(), ratio(-3, 7));
+        assert_eq!(parse_rational(" -1.5 ").unwrap(), ratio(-3, 2));
+        assert_eq!(parse_rational("4").unwrap(), ratio(4, 1));
+        for bad in ["1/0", "1.", "1.2.3", "abc", ""] {
+            assert!(parse_rational(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn records_swap_then_backward_steps() {
+        let result = eliminate("0 1 | 2; 1 1 | 3");
+        let operations: Vec<&RowOperation> = result.steps.iter().map(|s| &s.operation).collect();
+        assert_eq!(
+            operations,
+            [
+                &RowOperation::Swap { first: 0, second: 1 },
+                &RowOperation::AddMultiple { target: 0, source: 1, factor: "-1".to_string() },
+            ]
+        );
+        assert_eq!(result.steps[0].description, "R1 ↔ R2");
+        assert_eq!(result.steps[1].description, "R1 → R1 - R2");
+        assert_eq!(result.steps[1].phase, "backward");
+    }
+
+    #[test]
+    fn reduces_a_unique_system_to_rref() {
+        let result = eliminate("2 1 -1 | 8; -3 -1 2 | -11; -2 1 2 | -3");
+        assert_eq!(
+            result.steps[0].operation,
+            RowOperation::AddMultiple { target: 1, source: 0, factor: "3/2".to_string() }
+        );
+        assert_eq!(result.steps[0].description, "R2 → R2 + 3/2·R1");
+        assert!(result.steps.iter().any(|s| matches!(s.operation, RowOperation::Scale { .. })));
+        assert_eq!(result.rref, [["1", "0", "0", "2"], ["0", "1", "0", "3"], ["0", "0", "1", "-1"]]);
+        assert_eq!(result.rank, 3);
+        assert!(result.consistent);
+        assert_eq!(result.solution, ["x1 = 2", "x2 = 3", "x3 = -1"]);
+        assert!(result.free_variables.is_empty());
+    }
+
+    #[test]
+    fn detects_an_inconsistent_system() {
+        let result = eliminate("1 1 | 1; 2 2 | 3");
+        assert!(!result.consistent);
+        assert_eq!(result.rank, 1);
+        assert_eq!(result.pivot_columns, [0, 2]);
+        assert!(result.solution.is_empty());
+    }
+
+    #[test]
+    fn writes_solutions_in_terms_of_free_variables() {
+        let result = eliminate("1 2 -1 | 3; 2 4 -2 | 6");
+        assert_eq!(result.solution, ["x1 = 3 - 2·x2 + x3", "x2 is free", "x3 is free"]);
+        assert_eq!(result.free_variables, ["x2", "x3"]);
+
+        assert_eq!(eliminate("1 -1 | 0").solution, ["x1 = x2", "x2 is free"]);
+        assert_eq!(eliminate("1 1 | 0").solution, ["x1 = -x2", "x2 is free"]);
+    }
+}
@@ -0,0 +1,819 @@
+#[cfg(feature = "math")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "math")]
+use serde::Serialize;
+#[cfg(feature = "math")]
+use std::collections::{BTreeSet, HashMap};
+#[cfg(feature = "math")]
+use std::fmt;
+
+/// Byte offsets into the source text, end exclusive.
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Span,
+}
+
+#[cfg(feature = "math")]
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}..{}", self.message, self.span.start, self.span.end)
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind {
+    Number(f64),
+    Identifier(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    LeftParen,
+    RightParen,
+    Comma,
+    End,
+}
+
+#[cfg(feature = "math")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+#[cfg(feature = "math")]
+pub fn tokenize(source: &str) -> Result<Vec<Token>, ParseError> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        let single = |kind| Token { kind, span: Span { start, end: start + 1 } };
+        match c {
+            b' ' | b'\t' | b'\n' | b'\r' => i += 1,
+            b'0'..=b'9' | b'.' => {
+                while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+                    i += 1;
+                }
+                // An exponent only counts when digits follow, so "2e" is 2·e.
+                if i < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+                    let mut j = i + 1;
+                    if j < bytes.len() && (bytes[j] == b'+' || bytes[j] == b'-') {
+                        j += 1;
+                    }
+                    if j < bytes.len() && bytes[j].is_ascii_digit() {
+                        i = j;
+                        while i < bytes.len() && bytes[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                let text = &source[start..i];
+                let value = text.parse::<f64>().map_err(|_| ParseError {
+                    message: format!("Invalid number '{}'", text),
+                    span: Span { start, end: i },
+                })?;
+                tokens.push(Token { kind: TokenKind::Number(value), span: Span { start, end: i } });
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Identifier(source[start..i].to_string()),
+                    span: Span { start, end: i },
+                });
+            }
+            b'*' if bytes.get(i + 1) == Some(&b'*') => {
+                tokens.push(Token { kind: TokenKind::Caret, span: Span { start, end: start + 2 } });
+                i += 2;
+            }
+            b'+' | b'-' | b'*' | b'/' | b'^' | b'(' | b')' | b',' => {
+                tokens.push(single(match c {
+                    b'+' => TokenKind::Plus,
+                    b'-' => TokenKind::Minus,
+                    b'*' => TokenKind::Star,
+                    b'/' => TokenKind::Slash,
+                    b'^' => TokenKind::Caret,
+                    b'(' => TokenKind::LeftParen,
+                    b')' => TokenKind::RightParen,
+                    _ => TokenKind::Comma,
+                }));
+                i += 1;
+            }
+            _ => {
+                let ch = source[start..].chars().next().unwrap();
+                return Err(ParseError {
+                    message: format!("Unexpected character '{}'", ch),
+                    span: Span { start, end: start + ch.len_utf8() },
+                });
+            }
+        }
+    }
+
+    tokens.push(Token { kind: TokenKind::End, span: Span { start: source.len(), end: source.len() } });
+    Ok(tokens)
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MathConstant {
+    Pi,
+    E,
+    Tau,
+}
+
+#[cfg(feature = "math")]
+impl MathConstant {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pi" => Some(MathConstant::Pi),
+            "e" => Some(MathConstant::E),
+            "tau" => Some(MathConstant::Tau),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MathConstant::Pi => "pi",
+            MathConstant::E => "e",
+            MathConstant::Tau => "tau",
+        }
+    }
+
+    pub fn value(self) -> f64 {
+        match self {
+            MathConstant::Pi => std::f64::consts::PI,
+            MathConstant::E => std::f64::consts::E,
+            MathConstant::Tau => std::f64::consts::TAU,
+        }
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MathFunction {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Exp,
+    Ln,
+    Log10,
+    Log2,
+    Sqrt,
+    Cbrt,
+    Abs,
+    Floor,
+    Ceil,
+    Round,
+    Sign,
+    Min,
+    Max,
+    Atan2,
+}
+
+#[cfg(feature = "math")]
+impl MathFunction {
+    /// Looks up a function by name; "log" is base 10.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "sin" => MathFunction::Sin,
+            "cos" => MathFunction::Cos,
+            "tan" => MathFunction::Tan,
+            "asin" | "arcsin" => MathFunction::Asin,
+            "acos" | "arccos" => MathFunction::Acos,
+            "atan" | "arctan" => MathFunction::Atan,
+            "sinh" => MathFunction::Sinh,
+            "cosh" => MathFunction::Cosh,
+            "tanh" => MathFunction::Tanh,
+            "exp" => MathFunction::Exp,
+            "ln" => MathFunction::Ln,
+            "log" | "log10" => MathFunction::Log10,
+            "log2" => MathFunction::Log2,
+            "sqrt" => MathFunction::Sqrt,
+            "cbrt" => MathFunction::Cbrt,
+            "abs" => MathFunction::Abs,
+            "floor" => MathFunction::Floor,
+            "ceil" => MathFunction::Ceil,
+            "round" => MathFunction::Round,
+            "sign" | "sgn" => MathFunction::Sign,
+            "min" => MathFunction::Min,
+            "max" => MathFunction::Max,
+            "atan2" => MathFunction::Atan2,
+            _ => return None,
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MathFunction::Sin => "sin",
+            MathFunction::Cos => "cos",
+            MathFunction::Tan => "tan",
+            MathFunction::Asin => "asin",
+            MathFunction::Acos => "acos",
+            MathFunction::Atan => "atan",
+            MathFunction::Sinh => "sinh",
+            MathFunction::Cosh => "cosh",
+            MathFunction::Tanh => "tanh",
+            MathFunction::Exp => "exp",
+            MathFunction::Ln => "ln",
+            MathFunction::Log10 => "log10",
+            MathFunction::Log2 => "log2",
+            MathFunction::Sqrt => "sqrt",
+            MathFunction::Cbrt => "cbrt",
+            MathFunction::Abs => "abs",
+            MathFunction::Floor => "floor",
+            MathFunction::Ceil => "ceil",
+            MathFunction::Round => "round",
+            MathFunction::Sign => "sign",
+            MathFunction::Min => "min",
+            MathFunction::Max => "max",
+            MathFunction::Atan2 => "atan2",
+        }
+    }
+
+    pub fn arity(self) -> usize {
+        match self {
+            MathFunction::Min | MathFunction::Max | MathFunction::Atan2 => 2,
+            _ => 1,
+        }
+    }
+
+    pub fn apply(self, args: &[f64]) -> f64 {
+        let x = args[0];
+        match self {
+            MathFunction::Sin => x.sin(),
+            MathFunction::Cos => x.cos(),
+            MathFunction::Tan => x.tan(),
+            MathFunction::Asin => x.asin(),
+            MathFunction::Acos => x.acos(),
+            MathFunction::Atan => x.atan(),
+            MathFunction::Sinh => x.sinh(),
+            MathFunction::Cosh => x.cosh(),
+            MathFunction::Tanh => x.tanh(),
+            MathFunction::Exp => x.exp(),
+            MathFunction::Ln => x.ln(),
+            MathFunction::Log10 => x.log10(),
+            MathFunction::Log2 => x.log2(),
+            MathFunction::Sqrt => x.sqrt(),
+            MathFunction::Cbrt => x.cbrt(),
+            MathFunction::Abs => x.abs(),
+            MathFunction::Floor => x.floor(),
+            MathFunction::Ceil => x.ceil(),
+            MathFunction::Round => x.round(),
+            MathFunction::Sign => {
+                if x == 0.0 || x.is_nan() {
+                    x
+                } else {
+                    x.signum()
+                }
+            }
+            MathFunction::Min => x.min(args[1]),
+            MathFunction::Max => x.max(args[1]),
+            MathFunction::Atan2 => x.atan2(args[1]),
+        }
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+#[cfg(feature = "math")]
+impl BinaryOp {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Pow => "^",
+        }
+    }
+
+    /// (left, right) binding powers. `^` binds tighter on the left than the
+    /// right, which makes it right-associative.
+    fn binding_power(self) -> (u8, u8) {
+        match self {
+            BinaryOp::Add | BinaryOp::Sub => (10, 11),
+            BinaryOp::Mul | BinaryOp::Div => (20, 21),
+            BinaryOp::Pow => (31, 30),
+        }
+    }
+}
+
+/// Prefix minus binds looser than `^`, so -x^2 is -(x^2).
+#[cfg(feature = "math")]
+const PREFIX_BINDING_POWER: u8 = 25;
+
+/// Deepest tree, and deepest parenthesis or sign nesting, the parser
+/// accepts. Evaluation, printing and the symbolic passes recurse per level.
+#[cfg(feature = "math")]
+pub const MAX_EXPRESSION_DEPTH: usize = 200;
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Expr {
+    Number { value: f64 },
+    Constant { constant: MathConstant },
+    Variable { name: String },
+    Negate { operand: Box<Expr> },
+    Binary { op: BinaryOp, left: Box<Expr>, right: Box<Expr> },
+    Call { function: MathFunction, args: Vec<Expr> },
+}
+
+#[cfg(feature = "math")]
+impl Expr {
+    pub fn number(value: f64) -> Self {
+        Expr::Number { value }
+    }
+
+    pub fn variable(name: &str) -> Self {
+        Expr::Variable { name: name.to_string() }
+    }
+
+    pub fn binary(op: BinaryOp, left: Expr, right: Expr) -> Self {
+        Expr::Binary { op, left: Box::new(left), right: Box::new(right) }
+    }
+
+    pub fn negate(operand: Expr) -> Self {
+        Expr::Negate { operand: Box::new(operand) }
+    }
+
+    pub fn call(function: MathFunction, args: Vec<Expr>) -> Self {
+        Expr::Call { function, args }
+    }
+
+    /// Evaluates with variables supplied by `lookup`.
+    pub fn eval_with(&self, lookup: &dyn Fn(&str) -> Option<f64>) -> Result<f64, String> {
+        Ok(match self {
+            Expr::Number { value } => *value,
+            Expr::Constant { constant } => constant.value(),
+            Expr::Variable { name } => lookup(name).ok_or_else(|| format!("Unbound variable '{}'", name))?,
+            Expr::Negate { operand } => -operand.eval_with(lookup)?,
+            Expr::Binary { op, left, right } => {
+                let (a, b) = (left.eval_with(lookup)?, right.eval_with(lookup)?);
+                match op {
+                    BinaryOp::Add => a + b,
+                    BinaryOp::Sub => a - b,
+                    BinaryOp::Mul => a * b,
+                    BinaryOp::Div => a / b,
+                    BinaryOp::Pow => a.powf(b),
+                }
+            }
+            Expr::Call { function, args } => {
+                let values = args.iter().map(|a| a.eval_with(lookup)).collect::<Result<Vec<_>, _>>()?;
+                function.apply(&values)
+            }
+        })
+    }
+
+    pub fn eval(&self, variables: &HashMap<String, f64>) -> Result<f64, String> {
+        self.eval_with(&|name| variables.get(name).copied())
+    }
+
+    /// Free variables in alphabetical order.
+    pub fn variables(&self) -> BTreeSet<String> {
+        let mut names = BTreeSet::new();
+        self.collect_variables(&mut names);
+        names
+    }
+
+    fn collect_variables(&self, names: &mut BTreeSet<String>) {
+        match self {
+            Expr::Variable { name } => {
+                names.insert(name.clone());
+            }
+            Expr::Negate { operand } => operand.collect_variables(names),
+            Expr::Binary { left, right, .. } => {
+                left.collect_variables(names);
+                right.collect_variables(names);
+            }
+            Expr::Call { args, .. } => args.iter().for_each(|a| a.collect_variables(names)),
+            Expr::Number { .. } | Expr::Constant { .. } => {}
+        }
+    }
+
+    /// Precedence used when printing; atoms and calls bind tightest.
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Binary { op, .. } => op.binding_power().0,
+            Expr::Negate { .. } => PREFIX_BINDING_POWER,
+            Expr::Number { value } if *value < 0.0 => PREFIX_BINDING_POWER,
+            _ => u8::MAX,
+        }
+    }
+}
+
+#[cfg(feature = "math")]
+fn format_number(value: f64) -> String {
+    if value.is_finite() && value == value.trunc() && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+#[cfg(feature = "math")]
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let wrap = |f: &mut fmt::Formatter, e: &Expr, needs: bool| -> fmt::Result {
+            if needs {
+                write!(f, "({})", e)
+            } else {
+                write!(f, "{}", e)
+            }
+        };
+        match self {
+            Expr::Number { value } => write!(f, "{}", format_number(*value)),
+            Expr::Constant { constant } => write!(f, "{}", constant.name()),
+            Expr::Variable { name } => write!(f, "{}", name),
+            Expr::Negate { operand } => {
+                write!(f, "-")?;
+                wrap(f, operand, operand.precedence() <= PREFIX_BINDING_POWER)
+            }
+            Expr::Binary { op, left, right } => {
+                let (lbp, rbp) = op.binding_power();
+                let (left_needs, right_needs) = if *op == BinaryOp::Pow {
+                    (left.precedence() <= lbp, right.precedence() < rbp)
+                } else {
+                    (left.precedence() < lbp, right.precedence() < rbp)
+                };
+                wrap(f, left, left_needs)?;
+                write!(f, " {} ", op.symbol())?;
+                wrap(f, right, right_needs)
+            }
+            Expr::Call { function, args } => {
+                write!(f, "{}(", function.name())?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "math")]
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    /// Current recursion depth of `parse_expression`.
+    nesting: usize,
+}
+
+#[cfg(feature = "math")]
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position]
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.position].clone();
+        if token.kind != TokenKind::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn error<T>(&self, message: &str, span: Span) -> Result<T, ParseError> {
+        Err(ParseError { message: message.to_string(), span })
+    }
+
+    fn expect(&mut self, kind: TokenKind, message: &str) -> Result<Token, ParseError> {
+        if self.peek().kind == kind {
+            Ok(self.next())
+        } else {
+            self.error(message, self.peek().span)
+        }
+    }
+
+    /// Passes `depth` through, or fails at `span` when it is over the limit.
+    fn check_depth(&self, depth: usize, span: Span) -> Result<usize, ParseError> {
+        if depth > MAX_EXPRESSION_DEPTH {
+            self.error("Expression is nested too deeply", span)
+        } else {
+            Ok(depth)
+        }
+    }
+
+    /// Parses an expression and returns it with its tree depth.
+    fn parse_expression(&mut self, min_bp: u8) -> Result<(Expr, usize), ParseError> {
+        self.nesting += 1;
+        self.check_depth(self.nesting, self.peek().span)?;
+        let result = self.parse_binary(min_bp);
+        self.nesting -= 1;
+        result
+    }
+
+    fn parse_binary(&mut self, min_bp: u8) -> Result<(Expr, usize), ParseError> {
+        let (mut left, mut depth) = self.parse_prefix()?;
+
+        loop {
+            let token = self.peek().clone();
+            let op = match token.kind {
+                TokenKind::Plus => BinaryOp::Add,
+                TokenKind::Minus => BinaryOp::Sub,
+                TokenKind::Star => BinaryOp::Mul,
+                TokenKind::Slash => BinaryOp::Div,
+                TokenKind::Caret => BinaryOp::Pow,
+                // Implicit multiplication: "2x", "3(x + 1)", "(a)(b)", "x sin(x)".
+                TokenKind::Number(_) | TokenKind::Identifier(_) | TokenKind::LeftParen => {
+                    let (lbp, rbp) = BinaryOp::Mul.binding_power();
+                    if lbp < min_bp {
+                        break;
+                    }
+                    let (right, right_depth) = self.parse_expression(rbp)?;
+                    depth = self.check_depth(depth.max(right_depth) + 1, token.span)?;
+                    left = Expr::binary(BinaryOp::Mul, left, right);
+                    continue;
+                }
+                _ => break,
+            };
+            let (lbp, rbp) = op.binding_power();
+            if lbp < min_bp {
+                break;
+            }
+            self.next();
+            let (right, right_depth) = self.parse_expression(rbp)?;
+            depth = self.check_depth(depth.max(right_depth) + 1, token.span)?;
+            left = Expr::binary(op, left, right);
+        }
+
+        Ok((left, depth))
+    }
+
+    fn parse_prefix(&mut self) -> Result<(Expr, usize), ParseError> {
+        let token = self.next();
+        match token.kind {
+            TokenKind::Number(value) => Ok((Expr::number(value), 1)),
+            TokenKind::Minus => {
+                let (operand, depth) = self.parse_expression(PREFIX_BINDING_POWER)?;
+                Ok((Expr::negate(operand), self.check_depth(depth + 1, token.span)?))
+            }
+            TokenKind::Plus => self.parse_expression(PREFIX_BINDING_POWER),
+            TokenKind::LeftParen => {
+                let inner = self.parse_expression(0)?;
+                self.expect(TokenKind::RightParen, "Expected ')'")?;
+                Ok(inner)
+            }
+            TokenKind::Identifier(name) => {
+                if let Some(function) = MathFunction::from_name(&name) {
+                    return self.parse_call(function, token.span);
+                }
+                // "x(x + 1)" multiplies, but a longer unknown name before '('
+                // is far more likely a misspelt function than a product.
+                let is_constant = MathConstant::from_name(&name).is_some();
+                if self.peek().kind == TokenKind::LeftParen && name.len() > 1 && !is_constant {
+                    return self.error(&format!("Unknown function '{}'", name), token.span);
+                }
+                let expr = match MathConstant::from_name(&name) {
+                    Some(constant) => Expr::Constant { constant },
+                    None => Expr::Variable { name },
+                };
+                Ok((expr, 1))
+            }
+            TokenKind::End => self.error("Unexpected end of expression", token.span),
+            TokenKind::RightParen => self.error("Unexpected ')'", token.span),
+            _ => self.error("Expected a number, variable or '('", token.span),
+        }
+    }
+
+    fn parse_call(&mut self, function: MathFunction, name_span: Span) -> Result<(Expr, usize), ParseError> {
+        self.expect(TokenKind::LeftParen, &format!("Expected '(' after '{}'", function.name()))?;
+        let mut args = Vec::new();
+        let mut depth = 0;
+        if self.peek().kind != TokenKind::RightParen {
+            loop {
+                let (arg, arg_depth) = self.parse_expression(0)?;
+                args.push(arg);
+                depth = depth.max(arg_depth);
+                if self.peek().kind == TokenKind::Comma {
+                    self.next();
+                } else {
+                    break;
+                }
+            }
+        }
+        let close = self.expect(TokenKind::RightParen, "Expected ')' or ','")?;
+        if args.len() != function.arity() {
+            return self.error(
+                &format!("'{}' takes {} argument(s), got {}", function.name(), function.arity(), args.len()),
+                Span { start: name_span.start, end: close.span.end },
+            );
+        }
+        Ok((Expr::call(function, args), self.check_depth(depth + 1, name_span)?))
+    }
+}
+
+#[cfg(feature = "math")]
+pub fn parse_expression_source(source: &str) -> Result<Expr, ParseError> {
+    let mut parser = Parser { tokens: tokenize(source)?, position: 0, nesting: 0 };
+    let (expr, _) = parser.parse_expression(0)?;
+    let trailing = parser.peek().clone();
+    if trailing.kind != TokenKind::End {
+        let message = if trailing.kind == TokenKind::RightParen { "Unmatched ')'" } else { "Unexpected token" };
+        return parser.error(message, trailing.span);
+    }
+    Ok(expr)
+}
+
+/// Throws parse errors to JS as `{ message, span }` objects.
+#[cfg(feature = "math")]
+pub(crate) fn parse_error_to_js(error: ParseError) -> JsValue {
+    serde_wasm_bindgen::to_value(&error).unwrap()
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize)]
+struct ParsedExpression {
+    ast: Expr,
+    normalized: String,
+    variables: Vec<String>,
+}
+
+/// Returns `{ ast, normalized, variables }`, or throws `{ message, span }`.
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub fn parse_expression(source: &str) -> Result<JsValue, JsValue> {
+    let ast = parse_expression_source(source).map_err(parse_error_to_js)?;
+    let parsed = ParsedExpression { normalized: ast.to_string(), variables: ast.variables().into_iter().collect(), ast };
+    Ok(serde_wasm_bindgen::to_value(&parsed).unwrap())
+}
+
+/// Reads a `{ name: value }` object; `undefined` or `null` means no variables.
+#[cfg(feature = "math")]
+fn variables_from_js(variables: JsValue) -> Result<HashMap<String, f64>, JsValue> {
+    if variables.is_undefined() || variables.is_null() {
+        return Ok(HashMap::new());
+    }
+    serde_wasm_bindgen::from_value(variables).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// `variables` is an object such as `{ x: 2, y: 0.5 }`.
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub fn evaluate_expression(source: &str, variables: JsValue) -> Result<f64, JsValue> {
+    let ast = parse_expression_source(source).map_err(parse_error_to_js)?;
+    ast.eval(&variables_from_js(variables)?).map_err(|e| JsValue::from_str(&e))
+}
+
+/// A parsed expression kept on the Rust side for repeated evaluation.
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub struct Expression {
+    ast: Expr,
+}
+
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+impl Expression {
+    #[wasm_bindgen(constructor)]
+    pub fn new(source: &str) -> Result<Expression, JsValue> {
+        Ok(Expression { ast: parse_expression_source(source).map_err(parse_error_to_js)? })
+    }
+
+    pub fn evaluate(&self, variables: JsValue) -> Result<f64, JsValue> {
+        self.ast.eval(&variables_from_js(variables)?).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Evaluates at each value of `variable`, yielding NaN where undefined.
+    pub fn evaluate_many(&self, variable: &str, values: &[f64]) -> Result<js_sys::Float64Array, JsValue> {
+        let results = values
+            .iter()
+            .map(|&v| self.ast.eval_with(&|name| (name == variable).then_some(v)))
+            .collect::<Result<Vec<f64>, String>>()
+            .map_err(|e| JsValue::from_str(&e))?;
+        Ok(js_sys::Float64Array::from(&results[..]))
+    }
+
+    pub fn variables(&self) -> Vec<String> {
+        self.ast.variables().into_iter().collect()
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_string_js(&self) -> String {
+        self.ast.to_string()
+    }
+}
+
+#[cfg(feature = "math")]
+impl Expression {
+    pub fn ast(&self) -> &Expr {
+        &self.ast
+    }
+}
+
+#[cfg(all(test, feature = "math"))]
+mod tests {
+    use super::*;
+
+    fn eval_at(source: &str, x: f64) -> f64 {
+        parse_expression_source(source).unwrap().eval_with(&|name| (name == "x").then_some(x)).unwrap()
+    }
+
+    fn error_span(source: &str) -> (String, usize, usize) {
+        let error = parse_expression_source(source).unwrap_err();
+        (error.message, error.span.start, error.span.end)
+    }
+
+    #[test]
+    fn respects_precedence_and_associativity() {
+        assert_eq!(eval_at("1 + 2 * 3", 0.0), 7.0);
+        assert_eq!(eval_at("2 * 3 ^ 2", 0.0), 18.0);
+        assert_eq!(eval_at("8 - 3 - 2", 0.0), 3.0);
+        assert_eq!(eval_at("12 / 3 / 2", 0.0), 2.0);
+        assert_eq!(eval_at("2 ^ 3 ^ 2", 0.0), 512.0);
+        assert_eq!(eval_at("2 ** 3", 0.0), 8.0);
+        assert_eq!(eval_at("-x^2", 3.0), -9.0);
+        assert_eq!(eval_at("(-x)^2", 3.0), 9.0);
+        assert_eq!(eval_at("2^-1", 0.0), 0.5);
+    }
+
+    #[test]
+    fn multiplies_implicitly() {
+        assert_eq!(eval_at("2x", 3.0), 6.0);
+        assert_eq!(eval_at("3(x + 1)", 3.0), 12.0);
+        assert_eq!(eval_at("(x)(x + 1)", 3.0), 12.0);
+        assert_eq!(eval_at("2x^2", 3.0), 18.0);
+        assert_eq!(eval_at("x sin(0)", 3.0), 0.0);
+    }
+
+    #[test]
+    fn reads_exponents_only_when_digits_follow() {
+        assert_eq!(eval_at("2e", 0.0), 2.0 * std::f64::consts::E);
+        assert_eq!(eval_at("2e3", 0.0), 2000.0);
+        assert_eq!(eval_at("2E-3", 0.0), 0.002);
+        assert_eq!(eval_at("2e+x", 1.0), 2.0 * std::f64::consts::E + 1.0);
+    }
+
+    #[test]
+    fn prints_with_minimal_parentheses() {
+        let print = |source: &str| parse_expression_source(source).unwrap().to_string();
+        assert_eq!(print("(a - b) - (c - d)"), "a - b - (c - d)");
+        assert_eq!(print("(2^3)^2"), "(2 ^ 3) ^ 2");
+        assert_eq!(print("-(x^2)"), "-x ^ 2");
+    }
+
+    #[test]
+    fn reports_error_spans() {
+        assert_eq!(error_span("1 + * 2"), ("Expected a number, variable or '('".to_string(), 4, 5));
+        assert_eq!(error_span("(1 + 2"), ("Expected ')'".to_string(), 6, 6));
+        assert_eq!(error_span("1 + 2)"), ("Unmatched ')'".to_string(), 5, 6));
+        assert_eq!(error_span("foo(1)"), ("Unknown function 'foo'".to_string(), 0, 3));
+        assert_eq!(error_span("1 + max(1)"), ("'max' takes 2 argument(s), got 1".to_string(), 4, 10));
+        assert_eq!(error_span("2 $ 3"), ("Unexpected character '$'".to_string(), 2, 3));
+        assert_eq!(error_span("1 +").1, 3);
+    }
+
+    #[test]
+    fn limits_nesting_depth() {
+        let limit = MAX_EXPRESSION_DEPTH;
+        let parens = |n: usize| format!("{}x{}", "(".repeat(n), ")".repeat(n));
+        assert!(parse_expression_source(&parens(limit - 1)).is_ok());
+        let (message, start, _) = error_span(&parens(10 * limit));
+        assert_eq!(message, "Expression is nested too deeply");
+        assert!(start < 10 * limit);
+
+        assert!(parse_expression_source(&format!("{}x", "-".repeat(10 * limit))).is_err());
+        assert!(parse_expression_source(&format!("x{}", "^x".repeat(10 * limit))).is_err());
+        // Left-deep chains never recurse in the parser but still build deep trees.
+        let (_, start, end) = error_span(&format!("x{}", " + x".repeat(10 * limit)));
+        assert_eq!(&format!("x{}", " + x".repeat(10 * limit))[start..end], "+");
+        assert!(parse_expression_source(&format!("x{}", " x".repeat(10 * limit))).is_err());
+        assert!(parse_expression_source(&format!("x{}", " + x".repeat(limit / 2))).is_ok());
+    }
+}
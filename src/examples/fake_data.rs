@@ -0,0 +1,403 @@
+#[cfg(feature = "random")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "random")]
+use rand::prelude::*;
+#[cfg(feature = "random")]
+use serde::Deserialize;
+#[cfg(feature = "random")]
+use serde_json::{Map, Value};
+#[cfg(feature = "random")]
+use super::identifiers::uuid_v4;
+#[cfg(feature = "random")]
+use super::random::{seeded_rng, FIRST_NAMES, LAST_NAMES};
+
+#[cfg(feature = "random")]
+const EMAIL_DOMAINS: &[&str] = &["example.com", "example.org", "mail.test", "inbox.test", "corp.example"];
+
+#[cfg(feature = "random")]
+const LOREM_WORDS: &[&str] = &[
+    "lorem", "ipsum", "dolor", "sit", "amet", "consectetur", "adipiscing", "elit", "sed", "do",
+    "eiusmod", "tempor", "incididunt", "ut", "labore", "et", "dolore", "magna", "aliqua", "enim",
+    "ad", "minim", "veniam", "quis", "nostrud", "exercitation", "ullamco", "laboris", "nisi",
+    "aliquip", "ex", "ea", "commodo", "consequat",
+];
+
+// Size limits that keep a schema from asking for unbounded output.
+#[cfg(feature = "random")]
+const MAX_RECORDS: usize = 1_000_000;
+#[cfg(feature = "random")]
+const MAX_LOREM_WORDS: usize = 1_000;
+#[cfg(feature = "random")]
+const MAX_ARRAY_ITEMS: usize = 1_000;
+#[cfg(feature = "random")]
+const MAX_VALUES_PER_RECORD: usize = 10_000;
+#[cfg(feature = "random")]
+const MAX_YEAR: i64 = 9999;
+
+#[cfg(feature = "random")]
+fn default_probability() -> f64 {
+    0.5
+}
+
+/// One column of a mock-data schema, e.g. `{"name": "age", "type": "int", "min": 18, "max": 90}`.
+#[cfg(feature = "random")]
+#[derive(Deserialize, Debug, Clone)]
+pub struct FieldSpec {
+    pub name: String,
+    #[serde(flatten)]
+    pub generator: FieldGenerator,
+}
+
+#[cfg(feature = "random")]
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FieldGenerator {
+    /// Row index plus `start`, handy for primary keys.
+    Sequence {
+        #[serde(default)]
+        start: i64,
+    },
+    Int { min: i64, max: i64 },
+    Float {
+        min: f64,
+        max: f64,
+        #[serde(default)]
+        decimals: Option<u32>,
+    },
+    Bool {
+        #[serde(default = "default_probability")]
+        probability: f64,
+    },
+    Enum {
+        values: Vec<Value>,
+        #[serde(default)]
+        weights: Option<Vec<f64>>,
+    },
+    FirstName,
+    LastName,
+    Name,
+    Email,
+    /// Dates formatted as `YYYY-MM-DD`, inclusive of both ends.
+    Date { start: String, end: String },
+    Uuid,
+    Lorem {
+        #[serde(default = "default_min_words")]
+        min_words: usize,
+        #[serde(default = "default_max_words")]
+        max_words: usize,
+    },
+    Object { fields: Vec<FieldSpec> },
+    Array {
+        items: Box<FieldGenerator>,
+        #[serde(default)]
+        min_items: usize,
+        #[serde(default = "default_max_items")]
+        max_items: usize,
+    },
+}
+
+#[cfg(feature = "random")]
+fn default_min_words() -> usize {
+    3
+}
+
+#[cfg(feature = "random")]
+fn default_max_words() -> usize {
+    12
+}
+
+#[cfg(feature = "random")]
+fn default_max_items() -> usize {
+    3
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+#[cfg(feature = "random")]
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month as i64 + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+#[cfg(feature = "random")]
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(feature = "random")]
+fn parse_date(text: &str) -> Result<i64, String> {
+    let invalid = || format!("Invalid date '{}', expected YYYY-MM-DD", text);
+    let parts: Vec<&str> = text.split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        return Err(invalid());
+    };
+    let year: i64 = year.parse().map_err(|_| invalid())?;
+    let month: u32 = month.parse().map_err(|_| invalid())?;
+    let day: u32 = day.parse().map_err(|_| invalid())?;
+    if year.abs() > MAX_YEAR {
+        return Err(format!("Date '{}' is outside years -{} to {}", text, MAX_YEAR, MAX_YEAR));
+    }
+
+    let days = days_from_civil(year, month, day);
+    if civil_from_days(days) != (year, month, day) {
+        return Err(invalid());
+    }
+    Ok(days)
+}
+
+#[cfg(feature = "random")]
+impl FieldGenerator {
+    /// Checks ranges and nested fields before any rows are generated.
+    pub fn validate(&self, name: &str) -> Result<(), String> {
+        let fail = |message: &str| Err(format!("Field '{}': {}", name, message));
+        match self {
+            FieldGenerator::Int { min, max } if min > max => fail("min cannot exceed max"),
+            FieldGenerator::Float { min, max, .. } if !(max - min).is_finite() => {
+                fail("min and max must be finite and their difference representable")
+            }
+            FieldGenerator::Float { min, max, .. } if min > max => fail("min cannot exceed max"),
+            FieldGenerator::Bool { probability } if !(0.0..=1.0).contains(probability) => {
+                fail("probability must be between 0 and 1")
+            }
+            FieldGenerator::Enum { values, .. } if values.is_empty() => fail("enum needs at least one value"),
+            FieldGenerator::Enum { values, weights: Some(weights) } => {
+                if weights.len() != values.len() {
+                    fail("weights must match values in length")
+                } else if weights.iter().any(|w| !(w.is_finite() && *w >= 0.0))
+                    || !(weights.iter().sum::<f64>() > 0.0 && weights.iter().sum::<f64>().is_finite())
+                {
+                    fail("weights must be finite, non-negative and not all zero")
+                } else {
+                    Ok(())
+                }
+            }
+            FieldGenerator::Date { start, end } => {
+                if parse_date(start)? > parse_date(end)? {
+                    fail("start date is after end date")
+                } else {
+                    Ok(())
+                }
+            }
+            FieldGenerator::Lorem { min_words, max_words } if min_words > max_words => {
+                fail("min_words cannot exceed max_words")
+            }
+            FieldGenerator::Lorem { max_words, .. } if *max_words > MAX_LOREM_WORDS => {
+                fail(&format!("max_words can be at most {}", MAX_LOREM_WORDS))
+            }
+            FieldGenerator::Object { fields } => validate_fields(fields),
+            FieldGenerator::Array { items, min_items, max_items } => {
+                if min_items > max_items {
+                    fail("min_items cannot exceed max_items")
+                } else if *max_items > MAX_ARRAY_ITEMS {
+                    fail(&format!("max_items can be at most {}", MAX_ARRAY_ITEMS))
+                } else {
+                    items.validate(name)
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The most values one record can hold for this field; nested arrays
+    /// multiply.
+    fn max_values(&self) -> usize {
+        match self {
+            FieldGenerator::Object { fields } => max_values(fields),
+            FieldGenerator::Array { items, max_items, .. } => max_items.saturating_mul(items.max_values()),
+            _ => 1,
+        }
+    }
+
+    pub fn generate<R: Rng + ?Sized>(&self, row: usize, rng: &mut R) -> Value {
+        match self {
+            FieldGenerator::Sequence { start } => Value::from(start.saturating_add(row as i64)),
+            FieldGenerator::Int { min, max } => Value::from(rng.gen_range(*min..=*max)),
+            FieldGenerator::Float { min, max, decimals } => {
+                let value = if min == max { *min } else { rng.gen_range(*min..*max) };
+                let value = match decimals {
+                    Some(d) => {
+                        let factor = 10f64.powi(*d as i32);
+                        (value * factor).round() / factor
+                    }
+                    None => value,
+                };
+                Value::from(value)
+            }
+            FieldGenerator::Bool { probability } => Value::from(rng.gen_bool(*probability)),
+            FieldGenerator::Enum { values, weights } => match weights {
+                Some(weights) => {
+                    let total: f64 = weights.iter().sum();
+                    let mut pick = rng.gen_range(0.0..total);
+                    let index = weights
+                        .iter()
+                        .position(|w| {
+                            pick -= w;
+                            pick < 0.0
+                        })
+                        .unwrap_or(values.len() - 1);
+                    values[index].clone()
+                }
+                None => values.choose(rng).unwrap().clone(),
+            },
+            FieldGenerator::FirstName => Value::from(*FIRST_NAMES.choose(rng).unwrap()),
+            FieldGenerator::LastName => Value::from(*LAST_NAMES.choose(rng).unwrap()),
+            FieldGenerator::Name => Value::from(format!(
+                "{} {}",
+                FIRST_NAMES.choose(rng).unwrap(),
+                LAST_NAMES.choose(rng).unwrap()
+            )),
+            FieldGenerator::Email => Value::from(format!(
+                "{}.{}{}@{}",
+                FIRST_NAMES.choose(rng).unwrap().to_lowercase(),
+                LAST_NAMES.choose(rng).unwrap().to_lowercase(),
+                rng.gen_range(1..1000),
+                EMAIL_DOMAINS.choose(rng).unwrap()
+            )),
+            FieldGenerator::Date { start, end } => {
+                // Both dates were checked by `validate`.
+                let days = rng.gen_range(parse_date(start).unwrap()..=parse_date(end).unwrap());
+                let (year, month, day) = civil_from_days(days);
+                Value::from(format!("{:04}-{:02}-{:02}", year, month, day))
+            }
+            FieldGenerator::Uuid => Value::from(uuid_v4(rng)),
+            FieldGenerator::Lorem { min_words, max_words } => {
+                let words = rng.gen_range(*min_words..=*max_words);
+                let text: Vec<&str> = (0..words).map(|_| *LOREM_WORDS.choose(rng).unwrap()).collect();
+                Value::from(text.join(" "))
+            }
+            FieldGenerator::Object { fields } => Value::Object(generate_record(fields, row, rng)),
+            FieldGenerator::Array { items, min_items, max_items } => {
+                let len = rng.gen_range(*min_items..=*max_items);
+                Value::Array((0..len).map(|_| items.generate(row, rng)).collect())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "random")]
+pub fn validate_fields(fields: &[FieldSpec]) -> Result<(), String> {
+    if fields.is_empty() {
+        return Err("Schema needs at least one field".to_string());
+    }
+    for (i, field) in fields.iter().enumerate() {
+        if fields[..i].iter().any(|f| f.name == field.name) {
+            return Err(format!("Duplicate field name '{}'", field.name));
+        }
+        field.generator.validate(&field.name)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "random")]
+fn max_values(fields: &[FieldSpec]) -> usize {
+    fields.iter().fold(0, |total, field| total.saturating_add(field.generator.max_values()))
+}
+
+#[cfg(feature = "random")]
+pub fn generate_record<R: Rng + ?Sized>(fields: &[FieldSpec], row: usize, rng: &mut R) -> Map<String, Value> {
+    fields
+        .iter()
+        .map(|field| (field.name.clone(), field.generator.generate(row, rng)))
+        .collect()
+}
+
+#[cfg(feature = "random")]
+fn csv_escape(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Column names for CSV output; nested objects become `parent.child` columns.
+#[cfg(feature = "random")]
+fn csv_columns(fields: &[FieldSpec], prefix: &str, columns: &mut Vec<String>) {
+    for field in fields {
+        let name = format!("{}{}", prefix, field.name);
+        match &field.generator {
+            FieldGenerator::Object { fields } => csv_columns(fields, &format!("{}.", name), columns),
+            _ => columns.push(name),
+        }
+    }
+}
+
+/// Generates one CSV row straight into `cells`, skipping the intermediate JSON map.
+#[cfg(feature = "random")]
+fn csv_cells<R: Rng + ?Sized>(fields: &[FieldSpec], row: usize, rng: &mut R, cells: &mut Vec<String>) {
+    for field in fields {
+        match &field.generator {
+            FieldGenerator::Object { fields } => csv_cells(fields, row, rng, cells),
+            generator => match generator.generate(row, rng) {
+                Value::String(text) => cells.push(csv_escape(&text)),
+                // Arrays and other values are written as JSON.
+                value => cells.push(csv_escape(&value.to_string())),
+            },
+        }
+    }
+}
+
+/// Generates `count` records for the schema as a JSON array or as CSV.
+#[cfg(feature = "random")]
+pub fn generate_dataset<R: Rng + ?Sized>(
+    fields: &[FieldSpec],
+    count: usize,
+    format: &str,
+    rng: &mut R,
+) -> Result<String, String> {
+    validate_fields(fields)?;
+    if count > MAX_RECORDS {
+        return Err(format!("Generate at most {} records at a time", MAX_RECORDS));
+    }
+    if max_values(fields) > MAX_VALUES_PER_RECORD {
+        return Err(format!("A record could hold more than {} values; shrink the arrays", MAX_VALUES_PER_RECORD));
+    }
+
+    match format {
+        "json" => {
+            let records: Vec<Value> = (0..count)
+                .map(|row| Value::Object(generate_record(fields, row, rng)))
+                .collect();
+            serde_json::to_string(&records).map_err(|e| e.to_string())
+        }
+        "csv" => {
+            let mut columns = Vec::new();
+            csv_columns(fields, "", &mut columns);
+            let mut output = columns.iter().map(|c| csv_escape(c)).collect::<Vec<_>>().join(",");
+            output.push('\n');
+
+            let mut cells = Vec::with_capacity(columns.len());
+            for row in 0..count {
+                cells.clear();
+                csv_cells(fields, row, rng, &mut cells);
+                output.push_str(&cells.join(","));
+                output.push('\n');
+            }
+            Ok(output)
+        }
+        other => Err(format!("Unknown output format '{}', expected json or csv", other)),
+    }
+}
+
+/// `schema` is a JSON array of field specs; `format` is "json" or "csv".
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn generate_fake_data(schema: &str, count: u32, format: &str, seed: Option<u32>) -> Result<String, JsValue> {
+    let fields: Vec<FieldSpec> =
+        serde_json::from_str(schema).map_err(|e| JsValue::from_str(&format!("Invalid schema: {}", e)))?;
+    let mut rng = seeded_rng(seed);
+    generate_dataset(&fields, count as usize, format, &mut rng).map_err(|e| JsValue::from_str(&e))
+}
@@ -0,0 +1,295 @@
+#[cfg(feature = "random")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "random")]
+use rand::prelude::*;
+#[cfg(feature = "random")]
+use rand::rngs::OsRng;
+#[cfg(feature = "random")]
+use serde::Serialize;
+
+#[cfg(feature = "random")]
+const CROCKFORD_BASE32: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// URL-safe default alphabet used by nanoid.
+#[cfg(feature = "random")]
+pub const NANOID_ALPHABET: &str = "_-0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// Offset between the Gregorian epoch used by UUID v1/v6 (1582-10-15) and
+// the Unix epoch, in 100-nanosecond intervals.
+#[cfg(feature = "random")]
+const GREGORIAN_OFFSET: u64 = 0x01B2_1DD2_1381_4000;
+
+/// OS randomness by default, or a seeded generator for reproducible output.
+#[cfg(feature = "random")]
+pub fn id_rng(seed: Option<u32>) -> Box<dyn RngCore> {
+    match seed {
+        Some(seed) => Box::new(StdRng::seed_from_u64(seed as u64)),
+        None => Box::new(OsRng),
+    }
+}
+
+#[cfg(feature = "random")]
+fn format_uuid(bytes: &[u8; 16]) -> String {
+    let mut uuid = String::with_capacity(36);
+    for (i, byte) in bytes.iter().enumerate() {
+        if matches!(i, 4 | 6 | 8 | 10) {
+            uuid.push('-');
+        }
+        uuid.push_str(&format!("{:02x}", byte));
+    }
+    uuid
+}
+
+/// Sets the version nibble and the RFC 4122 variant bits.
+#[cfg(feature = "random")]
+fn stamp_version(bytes: &mut [u8; 16], version: u8) {
+    bytes[6] = (bytes[6] & 0x0f) | (version << 4);
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+}
+
+#[cfg(feature = "random")]
+pub fn uuid_v4<R: Rng + ?Sized>(rng: &mut R) -> String {
+    let mut bytes: [u8; 16] = rng.gen();
+    stamp_version(&mut bytes, 4);
+    format_uuid(&bytes)
+}
+
+/// Clock for time-ordered IDs. Within one millisecond the random part is
+/// incremented rather than redrawn, so a batch of IDs sorts in order.
+#[cfg(feature = "random")]
+struct MonotonicClock {
+    timestamp_ms: u64,
+    random: u128,
+    random_bits: u32,
+}
+
+#[cfg(feature = "random")]
+impl MonotonicClock {
+    fn new<R: Rng + ?Sized>(timestamp_ms: u64, random_bits: u32, rng: &mut R) -> Self {
+        let mut clock = MonotonicClock { timestamp_ms, random: 0, random_bits };
+        clock.reseed(rng);
+        clock
+    }
+
+    fn reseed<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        // Leave headroom in the top bit so increments rarely overflow.
+        self.random = rng.gen::<u128>() & ((1u128 << (self.random_bits - 1)) - 1);
+    }
+
+    fn next<R: Rng + ?Sized>(&mut self, rng: &mut R) -> (u64, u128) {
+        let current = (self.timestamp_ms, self.random);
+        self.random += 1;
+        if self.random >> self.random_bits != 0 {
+            self.timestamp_ms += 1;
+            self.reseed(rng);
+        }
+        current
+    }
+}
+
+#[cfg(feature = "random")]
+fn uuid_v7_from(timestamp_ms: u64, random: u128) -> String {
+    // 48-bit timestamp, then 74 random bits split around the version and variant.
+    let rand_a = (random >> 62) as u16 & 0x0fff;
+    let rand_b = random as u64 & ((1 << 62) - 1);
+    let mut bytes = [0u8; 16];
+    bytes[..6].copy_from_slice(&timestamp_ms.to_be_bytes()[2..]);
+    bytes[6..8].copy_from_slice(&rand_a.to_be_bytes());
+    bytes[8..].copy_from_slice(&rand_b.to_be_bytes());
+    stamp_version(&mut bytes, 7);
+    format_uuid(&bytes)
+}
+
+#[cfg(feature = "random")]
+fn ulid_from(timestamp_ms: u64, random: u128) -> String {
+    let value = ((timestamp_ms as u128 & ((1 << 48) - 1)) << 80) | (random & ((1 << 80) - 1));
+    (0..26)
+        .rev()
+        .map(|i| CROCKFORD_BASE32[((value >> (i * 5)) & 31) as usize] as char)
+        .collect()
+}
+
+#[cfg(feature = "random")]
+pub fn uuid_v7_batch<R: Rng + ?Sized>(count: usize, timestamp_ms: u64, rng: &mut R) -> Vec<String> {
+    let mut clock = MonotonicClock::new(timestamp_ms, 74, rng);
+    (0..count)
+        .map(|_| {
+            let (ms, random) = clock.next(rng);
+            uuid_v7_from(ms, random)
+        })
+        .collect()
+}
+
+#[cfg(feature = "random")]
+pub fn ulid_batch<R: Rng + ?Sized>(count: usize, timestamp_ms: u64, rng: &mut R) -> Vec<String> {
+    let mut clock = MonotonicClock::new(timestamp_ms, 80, rng);
+    (0..count)
+        .map(|_| {
+            let (ms, random) = clock.next(rng);
+            ulid_from(ms, random)
+        })
+        .collect()
+}
+
+/// Generates a nanoid, masking random bytes and rejecting out-of-range
+/// values so every alphabet character is equally likely.
+#[cfg(feature = "random")]
+pub fn nanoid<R: Rng + ?Sized>(alphabet: &[char], size: usize, rng: &mut R) -> Result<String, String> {
+    if alphabet.len() < 2 || alphabet.len() > 256 {
+        return Err("Alphabet must contain between 2 and 256 characters".to_string());
+    }
+    let mask = alphabet.len().next_power_of_two() - 1;
+
+    let mut id = String::with_capacity(size);
+    let mut count = 0;
+    while count < size {
+        let index = (rng.gen::<u8>() as usize) & mask;
+        if let Some(&c) = alphabet.get(index) {
+            id.push(c);
+            count += 1;
+        }
+    }
+    Ok(id)
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct IdentifierInfo {
+    pub valid: bool,
+    /// "uuid", "ulid", or empty when the input is not recognised.
+    pub kind: String,
+    pub version: Option<u8>,
+    pub variant: Option<String>,
+    /// Unix milliseconds embedded in time-based IDs (UUID v1, v6, v7 and ULID).
+    pub timestamp_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+#[cfg(feature = "random")]
+fn invalid(kind: &str, error: &str) -> IdentifierInfo {
+    IdentifierInfo { kind: kind.to_string(), error: Some(error.to_string()), ..IdentifierInfo::default() }
+}
+
+#[cfg(feature = "random")]
+pub fn parse_uuid(text: &str) -> IdentifierInfo {
+    let text = text.trim();
+    let dashes_ok = text.len() == 36 && [8, 13, 18, 23].iter().all(|&i| text.as_bytes()[i] == b'-');
+    let hex: String = text.chars().filter(|&c| c != '-').collect();
+    if !dashes_ok || hex.len() != 32 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return invalid("uuid", "Expected 32 hex digits in 8-4-4-4-12 form");
+    }
+
+    let value = u128::from_str_radix(&hex, 16).unwrap();
+    let bytes = value.to_be_bytes();
+    let version = bytes[6] >> 4;
+    let variant = match bytes[8] {
+        b if b & 0x80 == 0 => "ncs",
+        b if b & 0xc0 == 0x80 => "rfc4122",
+        b if b & 0xe0 == 0xc0 => "microsoft",
+        _ => "future",
+    };
+
+    let gregorian_to_unix_ms = |ticks: u64| (ticks as f64 - GREGORIAN_OFFSET as f64) / 10_000.0;
+    let timestamp_ms = match (variant, version) {
+        ("rfc4122", 1) => {
+            let low = u32::from_be_bytes(bytes[0..4].try_into().unwrap()) as u64;
+            let mid = u16::from_be_bytes(bytes[4..6].try_into().unwrap()) as u64;
+            let high = (u16::from_be_bytes(bytes[6..8].try_into().unwrap()) & 0x0fff) as u64;
+            Some(gregorian_to_unix_ms((high << 48) | (mid << 32) | low))
+        }
+        ("rfc4122", 6) => {
+            let high = u32::from_be_bytes(bytes[0..4].try_into().unwrap()) as u64;
+            let mid = u16::from_be_bytes(bytes[4..6].try_into().unwrap()) as u64;
+            let low = (u16::from_be_bytes(bytes[6..8].try_into().unwrap()) & 0x0fff) as u64;
+            Some(gregorian_to_unix_ms((high << 28) | (mid << 12) | low))
+        }
+        ("rfc4122", 7) => Some((value >> 80) as f64),
+        _ => None,
+    };
+
+    IdentifierInfo {
+        valid: true,
+        kind: "uuid".to_string(),
+        version: Some(version),
+        variant: Some(variant.to_string()),
+        timestamp_ms,
+        error: None,
+    }
+}
+
+#[cfg(feature = "random")]
+pub fn parse_ulid(text: &str) -> IdentifierInfo {
+    let text = text.trim().to_ascii_uppercase();
+    if text.len() != 26 {
+        return invalid("ulid", "Expected 26 Crockford base32 characters");
+    }
+    let mut value: u128 = 0;
+    for c in text.bytes() {
+        // Crockford base32 reads I and L as 1 and O as 0.
+        let c = match c {
+            b'I' | b'L' => b'1',
+            b'O' => b'0',
+            c => c,
+        };
+        let Some(digit) = CROCKFORD_BASE32.iter().position(|&d| d == c) else {
+            return invalid("ulid", "Invalid Crockford base32 character");
+        };
+        if value >> 123 != 0 {
+            return invalid("ulid", "ULID value exceeds 128 bits");
+        }
+        value = (value << 5) | digit as u128;
+    }
+
+    IdentifierInfo {
+        valid: true,
+        kind: "ulid".to_string(),
+        timestamp_ms: Some((value >> 80) as f64),
+        ..IdentifierInfo::default()
+    }
+}
+
+/// Recognises UUIDs (36 characters) and ULIDs (26 characters).
+#[cfg(feature = "random")]
+pub fn parse_identifier_text(text: &str) -> IdentifierInfo {
+    match text.trim().len() {
+        36 => parse_uuid(text),
+        26 => parse_ulid(text),
+        _ => invalid("", "Not a UUID or ULID"),
+    }
+}
+
+/// `kind` is "uuid_v4", "uuid_v7" or "ulid". Without a seed, OS randomness
+/// and the current time are used; `timestamp_ms` overrides the clock.
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn generate_ids(kind: &str, count: u32, seed: Option<u32>, timestamp_ms: Option<f64>) -> Result<JsValue, JsValue> {
+    let mut rng = id_rng(seed);
+    let timestamp_ms = timestamp_ms.unwrap_or_else(js_sys::Date::now) as u64;
+    let ids = match kind {
+        "uuid_v4" => (0..count).map(|_| uuid_v4(&mut rng)).collect(),
+        "uuid_v7" => uuid_v7_batch(count as usize, timestamp_ms, &mut rng),
+        "ulid" => ulid_batch(count as usize, timestamp_ms, &mut rng),
+        other => return Err(JsValue::from_str(&format!("Unknown identifier kind: {}", other))),
+    };
+    Ok(serde_wasm_bindgen::to_value(&ids).unwrap())
+}
+
+/// Generates `count` nanoids of `size` characters; the default alphabet is URL-safe.
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn generate_nanoids(count: u32, size: u32, alphabet: Option<String>, seed: Option<u32>) -> Result<JsValue, JsValue> {
+    let alphabet: Vec<char> = alphabet.as_deref().unwrap_or(NANOID_ALPHABET).chars().collect();
+    let mut rng = id_rng(seed);
+    let ids = (0..count)
+        .map(|_| nanoid(&alphabet, size as usize, &mut rng))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&ids).unwrap())
+}
+
+/// Reports validity, version, variant and embedded timestamp of a UUID or ULID.
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn parse_identifier(text: &str) -> JsValue {
+    serde_wasm_bindgen::to_value(&parse_identifier_text(text)).unwrap()
+}
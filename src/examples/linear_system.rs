@@ -0,0 +1,218 @@
+#[cfg(feature = "math")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "math")]
+use nalgebra as na;
+#[cfg(feature = "math")]
+use serde::Serialize;
+#[cfg(feature = "math")]
+use super::matrix::{matrix_from_array, rank_tolerance};
+
+/// Entries smaller than this in solution and basis vectors are reported as
+/// exact zeros, which keeps parametric forms readable.
+#[cfg(feature = "math")]
+const DISPLAY_EPSILON: f64 = 1e-12;
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemVerdict {
+    /// Exactly one solution.
+    Unique,
+    /// Consistent with free variables; the solution set is an affine subspace.
+    Infinite,
+    /// No exact solution; `solution` holds the least-squares fit instead.
+    Inconsistent,
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct LinearSolution {
+    pub rows: usize,
+    pub cols: usize,
+    pub rank: usize,
+    /// Rank of the augmented matrix [A | b]: `rank` when b lies in the
+    /// column space of A, `rank + 1` otherwise.
+    pub augmented_rank: usize,
+    pub verdict: SystemVerdict,
+    pub consistent: bool,
+    /// "lu" for square full-rank systems, otherwise "svd".
+    pub method: String,
+    /// The unique solution, the minimum-norm particular solution, or the
+    /// minimum-norm least-squares solution, depending on `verdict`.
+    pub solution: Vec<f64>,
+    /// Basis of the null space of A; one free parameter per vector.
+    pub null_space: Vec<Vec<f64>>,
+    /// Ax - b for the reported solution.
+    pub residual: Vec<f64>,
+    pub residual_norm: f64,
+    /// One line per variable, e.g. "x1 = 2 - 0.5·t1".
+    pub parametric_form: Vec<String>,
+}
+
+#[cfg(feature = "math")]
+fn clean(v: f64) -> f64 {
+    if v.abs() < DISPLAY_EPSILON {
+        0.0
+    } else {
+        v
+    }
+}
+
+#[cfg(feature = "math")]
+fn format_number(v: f64) -> String {
+    let text = format!("{:.4}", v);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" { "0".to_string() } else { text.to_string() }
+}
+
+/// Writes x = p + Σ tⱼ nⱼ component by component.
+#[cfg(feature = "math")]
+pub fn format_parametric(particular: &[f64], null_space: &[Vec<f64>]) -> Vec<String> {
+    (0..particular.len())
+        .map(|i| {
+            let mut text = format_number(particular[i]);
+            let mut has_constant = particular[i].abs() >= 0.000_05;
+            for (j, basis) in null_space.iter().enumerate() {
+                let coefficient = basis[i];
+                if coefficient.abs() < 0.000_05 {
+                    continue;
+                }
+                let magnitude = if (coefficient.abs() - 1.0).abs() < 0.000_05 {
+                    String::new()
+                } else {
+                    format!("{}·", format_number(coefficient.abs()))
+                };
+                let term = format!("{}t{}", magnitude, j + 1);
+                text = match (has_constant, coefficient < 0.0) {
+                    (false, false) => term,
+                    (false, true) => format!("-{}", term),
+                    (true, false) => format!("{} + {}", text, term),
+                    (true, true) => format!("{} - {}", text, term),
+                };
+                has_constant = true;
+            }
+            format!("x{} = {}", i + 1, text)
+        })
+        .collect()
+}
+
+/// Solves Ax = b for any shape of A. The system counts as consistent when
+/// the least-squares solution leaves a residual at rounding level, which is
+/// the numerically robust form of rank(A) = rank([A | b]).
+#[cfg(feature = "math")]
+pub fn solve_system(a: &na::DMatrix<f64>, b: &na::DVector<f64>) -> Result<LinearSolution, String> {
+    let (rows, cols) = a.shape();
+    if b.len() != rows {
+        return Err(format!("Right-hand side has {} entries but A has {} rows", b.len(), rows));
+    }
+    if a.iter().chain(b.iter()).any(|v| !v.is_finite()) {
+        return Err("Coefficients must be finite numbers".to_string());
+    }
+
+    let svd = a.clone().svd(true, true);
+    let tolerance = rank_tolerance(&svd.singular_values, rows, cols);
+    let rank = svd.singular_values.iter().filter(|&&s| s > tolerance).count();
+
+    // LU is the natural route for square, nonsingular systems; everything
+    // else goes through the SVD's minimum-norm solution.
+    let lu_solution = if rows == cols && rank == cols { a.clone().lu().solve(b) } else { None };
+    let (method, solution) = match lu_solution {
+        Some(x) => ("lu", x),
+        None => ("svd", svd.solve(b, tolerance)?),
+    };
+
+    // Backward-error test: a consistent system's residual is only the
+    // rounding in forming Ax and b.
+    let residual = a * &solution - b;
+    let scale = svd.singular_values.max() * solution.norm() + b.norm();
+    let consistent = residual.norm() <= 100.0 * rows.max(cols) as f64 * f64::EPSILON * scale;
+    let augmented_rank = if consistent { rank } else { rank + 1 };
+
+    let verdict = match (consistent, rank == cols) {
+        (false, _) => SystemVerdict::Inconsistent,
+        (true, true) => SystemVerdict::Unique,
+        (true, false) => SystemVerdict::Infinite,
+    };
+
+    // Right singular vectors past the rank span the null space.
+    let v_t = svd.v_t.as_ref().unwrap();
+    let null_space: Vec<Vec<f64>> = if verdict == SystemVerdict::Infinite {
+        let mut basis: Vec<Vec<f64>> = (0..v_t.nrows())
+            .filter(|&k| svd.singular_values[k] <= tolerance)
+            .map(|k| v_t.row(k).iter().map(|&v| clean(v)).collect())
+            .collect();
+        // A wide matrix has more unknowns than singular values; complete
+        // the basis from the orthogonal complement of the row space.
+        if basis.len() < cols - rank {
+            let row_space = v_t.rows(0, rank).transpose();
+            let projector = na::DMatrix::identity(cols, cols) - &row_space * row_space.transpose();
+            let complement = projector.svd(true, false);
+            let u = complement.u.unwrap();
+            basis = (0..cols - rank).map(|k| u.column(k).iter().map(|&v| clean(v)).collect()).collect();
+        }
+        basis
+    } else {
+        Vec::new()
+    };
+
+    let solution: Vec<f64> = solution.iter().map(|&v| clean(v)).collect();
+
+    Ok(LinearSolution {
+        rows,
+        cols,
+        rank,
+        augmented_rank,
+        verdict,
+        consistent,
+        method: method.to_string(),
+        parametric_form: format_parametric(&solution, &null_space),
+        solution,
+        null_space,
+        residual_norm: residual.norm(),
+        residual: residual.iter().copied().collect(),
+    })
+}
+
+/// `a` holds `rows * cols` coefficients in row-major order and `b` the
+/// `rows` right-hand-side values.
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub fn solve_matrix_system(a: &[f64], rows: u32, cols: u32, b: &[f64]) -> Result<JsValue, JsValue> {
+    let matrix = matrix_from_array(a, rows as usize, cols as usize).map_err(|e| JsValue::from_str(&e))?;
+    let solution = solve_system(&matrix, &na::DVector::from_column_slice(b)).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&solution).unwrap())
+}
+
+#[cfg(all(test, feature = "math"))]
+mod tests {
+    use super::*;
+
+    fn solve(rows: usize, cols: usize, a: &[f64], b: &[f64]) -> LinearSolution {
+        solve_system(&na::DMatrix::from_row_slice(rows, cols, a), &na::DVector::from_column_slice(b)).unwrap()
+    }
+
+    #[test]
+    fn badly_scaled_right_hand_side_stays_consistent() {
+        let solution = solve(2, 2, &[1.0, 0.0, 0.0, 1e-10], &[1e7, 0.0]);
+        assert_eq!(solution.verdict, SystemVerdict::Unique);
+        assert_eq!((solution.rank, solution.augmented_rank), (2, 2));
+        assert_eq!(solution.residual_norm, 0.0);
+    }
+
+    #[test]
+    fn classifies_dependent_and_inconsistent_systems() {
+        let dependent = solve(2, 2, &[1.0, 2.0, 2.0, 4.0], &[3.0, 6.0]);
+        assert_eq!(dependent.verdict, SystemVerdict::Infinite);
+        assert_eq!(dependent.null_space.len(), 1);
+
+        let inconsistent = solve(2, 2, &[1.0, 2.0, 2.0, 4.0], &[3.0, 7.0]);
+        assert_eq!(inconsistent.verdict, SystemVerdict::Inconsistent);
+        assert_eq!((inconsistent.rank, inconsistent.augmented_rank), (1, 2));
+    }
+
+    #[test]
+    fn large_consistent_right_hand_side_on_singular_matrix() {
+        let solution = solve(3, 3, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0], &[6e7, 15e7, 24e7]);
+        assert_eq!(solution.verdict, SystemVerdict::Infinite);
+    }
+}
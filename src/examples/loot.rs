@@ -0,0 +1,553 @@
+#[cfg(feature = "random")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "random")]
+use rand::prelude::*;
+#[cfg(feature = "random")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "random")]
+use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "random")]
+use super::character::stat_modifier;
+#[cfg(feature = "random")]
+use super::random::seeded_rng;
+
+// Weight bonus per point of luck modifier for items above the lowest tier.
+#[cfg(feature = "random")]
+const LUCK_BONUS_PER_MODIFIER: f64 = 0.05;
+
+// Luck scores above this give no further bonus.
+#[cfg(feature = "random")]
+const MAX_LUCK: u32 = 30;
+
+// Limits that keep rolls cheap and every weight sum finite.
+#[cfg(feature = "random")]
+pub const MAX_TABLE_ROLLS: u32 = 100;
+#[cfg(feature = "random")]
+pub const MAX_DROPS_PER_ROLL: u64 = 10_000;
+#[cfg(feature = "random")]
+pub const MAX_TOTAL_WEIGHT: f64 = 1e12;
+#[cfg(feature = "random")]
+pub const MAX_PITY_MULTIPLIER: f64 = 1e6;
+
+#[cfg(feature = "random")]
+fn default_rolls() -> u32 {
+    1
+}
+
+#[cfg(feature = "random")]
+fn default_quantity() -> [u32; 2] {
+    [1, 1]
+}
+
+/// Raises the odds of rare drops after a dry streak: once `after` rolls of a
+/// table have produced nothing of `rarity` or better, those entries' weights
+/// are multiplied by `1 + boost * (streak - after + 1)`.
+#[cfg(feature = "random")]
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct PitySettings {
+    pub rarity: String,
+    pub after: u32,
+    pub boost: f64,
+}
+
+#[cfg(feature = "random")]
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ItemDrop {
+    pub item: String,
+    pub rarity: Option<String>,
+    /// Inclusive quantity range.
+    #[serde(default = "default_quantity")]
+    pub quantity: [u32; 2],
+}
+
+#[cfg(feature = "random")]
+#[derive(Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum EntryKind {
+    Item(ItemDrop),
+    Table { table: String },
+    Nothing { nothing: bool },
+}
+
+#[cfg(feature = "random")]
+#[derive(Deserialize, Clone, Debug)]
+pub struct LootEntry {
+    pub weight: f64,
+    #[serde(flatten)]
+    pub kind: EntryKind,
+}
+
+#[cfg(feature = "random")]
+#[derive(Deserialize, Clone, Debug)]
+pub struct LootTable {
+    #[serde(default = "default_rolls")]
+    pub rolls: u32,
+    #[serde(default)]
+    pub guaranteed: Vec<ItemDrop>,
+    #[serde(default)]
+    pub entries: Vec<LootEntry>,
+}
+
+/// A full loot configuration: rarity tiers from most to least common,
+/// named tables, and optional pity settings.
+#[cfg(feature = "random")]
+#[derive(Deserialize, Clone, Debug)]
+pub struct LootConfig {
+    pub rarities: Vec<String>,
+    pub tables: BTreeMap<String, LootTable>,
+    #[serde(default)]
+    pub pity: Option<PitySettings>,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct RolledItem {
+    pub item: String,
+    pub rarity: String,
+    pub quantity: u32,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct LootRoll {
+    pub drops: Vec<RolledItem>,
+    /// Rolls of this table in a row without a pity-tier drop, after this roll.
+    pub pity_streak: u32,
+    pub pity_active: bool,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct ItemOdds {
+    pub item: String,
+    pub rarity: String,
+    /// Chance that one roll of the table yields at least one of this item.
+    pub drop_chance: f64,
+    pub expected_quantity: f64,
+}
+
+#[cfg(feature = "random")]
+impl LootConfig {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let config: LootConfig = serde_json::from_str(json).map_err(|e| format!("Invalid loot config: {}", e))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn rarity_rank(&self, rarity: &Option<String>) -> usize {
+        rarity
+            .as_ref()
+            .and_then(|r| self.rarities.iter().position(|known| known == r))
+            .unwrap_or(0)
+    }
+
+    fn rarity_name(&self, rarity: &Option<String>) -> String {
+        self.rarities[self.rarity_rank(rarity)].clone()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rarities.is_empty() {
+            return Err("At least one rarity tier is required".to_string());
+        }
+        if let Some(pity) = &self.pity {
+            if !self.rarities.contains(&pity.rarity) {
+                return Err(format!("Pity rarity '{}' is not a known tier", pity.rarity));
+            }
+            if !(pity.boost.is_finite() && pity.boost >= 0.0) {
+                return Err("Pity boost must be a finite, non-negative number".to_string());
+            }
+        }
+
+        let check_item = |table: &str, drop: &ItemDrop| -> Result<(), String> {
+            if let Some(rarity) = &drop.rarity {
+                if !self.rarities.contains(rarity) {
+                    return Err(format!("Table '{}': unknown rarity '{}'", table, rarity));
+                }
+            }
+            if drop.quantity[0] > drop.quantity[1] {
+                return Err(format!("Table '{}': quantity range for '{}' is reversed", table, drop.item));
+            }
+            Ok(())
+        };
+
+        for (name, table) in &self.tables {
+            for drop in &table.guaranteed {
+                check_item(name, drop)?;
+            }
+            if table.rolls > MAX_TABLE_ROLLS {
+                return Err(format!("Table '{}': at most {} rolls are allowed", name, MAX_TABLE_ROLLS));
+            }
+            if table.entries.iter().any(|e| !(e.weight.is_finite() && e.weight >= 0.0)) {
+                return Err(format!("Table '{}': weights must be finite and non-negative", name));
+            }
+            let total: f64 = table.entries.iter().map(|e| e.weight).sum();
+            if !table.entries.is_empty() && total <= 0.0 {
+                return Err(format!("Table '{}': weights must not all be zero", name));
+            }
+            if total > MAX_TOTAL_WEIGHT {
+                return Err(format!("Table '{}': weights may add up to at most {}", name, MAX_TOTAL_WEIGHT));
+            }
+            for entry in &table.entries {
+                match &entry.kind {
+                    EntryKind::Item(drop) => check_item(name, drop)?,
+                    EntryKind::Table { table } if !self.tables.contains_key(table) => {
+                        return Err(format!("Table '{}' refers to unknown table '{}'", name, table));
+                    }
+                    _ => {}
+                }
+            }
+            self.check_cycles(name, &mut Vec::new())?;
+        }
+        for name in self.tables.keys() {
+            if self.max_drops(name) > MAX_DROPS_PER_ROLL {
+                return Err(format!("Table '{}' can drop more than {} items in one roll", name, MAX_DROPS_PER_ROLL));
+            }
+        }
+        Ok(())
+    }
+
+    /// Upper bound on the drops from one roll of an acyclic table.
+    fn max_drops(&self, name: &str) -> u64 {
+        let table = &self.tables[name];
+        let per_roll = table
+            .entries
+            .iter()
+            .map(|entry| match &entry.kind {
+                EntryKind::Item(_) => 1,
+                EntryKind::Table { table } => self.max_drops(table),
+                EntryKind::Nothing { .. } => 0,
+            })
+            .max()
+            .unwrap_or(0);
+        (table.guaranteed.len() as u64).saturating_add(per_roll.saturating_mul(table.rolls as u64))
+    }
+
+    fn check_cycles<'a>(&'a self, name: &'a str, path: &mut Vec<&'a str>) -> Result<(), String> {
+        if path.contains(&name) {
+            return Err(format!("Loot tables form a cycle: {} -> {}", path.join(" -> "), name));
+        }
+        path.push(name);
+        for entry in &self.tables[name].entries {
+            if let EntryKind::Table { table } = &entry.kind {
+                self.check_cycles(table, path)?;
+            }
+        }
+        path.pop();
+        Ok(())
+    }
+
+    /// Whether rolling the entry can produce an item of at least `rank`.
+    fn can_drop_rank(&self, kind: &EntryKind, rank: usize) -> bool {
+        match kind {
+            EntryKind::Item(drop) => self.rarity_rank(&drop.rarity) >= rank,
+            EntryKind::Table { table } => {
+                let table = &self.tables[table];
+                table.guaranteed.iter().any(|d| self.rarity_rank(&d.rarity) >= rank)
+                    || table.entries.iter().any(|e| self.can_drop_rank(&e.kind, rank))
+            }
+            EntryKind::Nothing { .. } => false,
+        }
+    }
+
+    /// Entry weights after luck and pity adjustments.
+    fn effective_weights(&self, table: &LootTable, luck: u32, pity_multiplier: f64) -> Vec<f64> {
+        let luck_bonus = (1.0 + LUCK_BONUS_PER_MODIFIER * stat_modifier(luck.min(MAX_LUCK)) as f64).max(0.0);
+        let pity_rank = self.pity.as_ref().map(|p| self.rarities.iter().position(|r| *r == p.rarity).unwrap());
+
+        table
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut weight = entry.weight;
+                if self.can_drop_rank(&entry.kind, 1) {
+                    weight *= luck_bonus;
+                }
+                if let Some(rank) = pity_rank {
+                    if self.can_drop_rank(&entry.kind, rank) {
+                        weight *= pity_multiplier;
+                    }
+                }
+                weight
+            })
+            .collect()
+    }
+
+    fn roll_drop<R: Rng + ?Sized>(&self, drop: &ItemDrop, rng: &mut R, drops: &mut Vec<RolledItem>) {
+        let quantity = rng.gen_range(drop.quantity[0]..=drop.quantity[1]);
+        if quantity > 0 {
+            drops.push(RolledItem { item: drop.item.clone(), rarity: self.rarity_name(&drop.rarity), quantity });
+        }
+    }
+
+    fn roll_table<R: Rng + ?Sized>(
+        &self,
+        name: &str,
+        luck: u32,
+        pity_multiplier: f64,
+        rng: &mut R,
+        drops: &mut Vec<RolledItem>,
+    ) {
+        let table = &self.tables[name];
+        for drop in &table.guaranteed {
+            self.roll_drop(drop, rng, drops);
+        }
+        if table.entries.is_empty() {
+            return;
+        }
+
+        let weights = self.effective_weights(table, luck, pity_multiplier);
+        let total: f64 = weights.iter().sum();
+        for _ in 0..table.rolls {
+            let mut pick = rng.gen_range(0.0..total);
+            let index = weights
+                .iter()
+                .position(|w| {
+                    pick -= w;
+                    pick < 0.0
+                })
+                .unwrap_or(weights.len() - 1);
+
+            match &table.entries[index].kind {
+                EntryKind::Item(drop) => self.roll_drop(drop, rng, drops),
+                EntryKind::Table { table } => self.roll_table(table, luck, pity_multiplier, rng, drops),
+                EntryKind::Nothing { .. } => {}
+            }
+        }
+    }
+
+    /// Exact chance of getting none of `item`, and its expected count,
+    /// from one roll of the named table.
+    fn item_odds(&self, name: &str, item: &str, luck: u32) -> (f64, f64) {
+        let table = &self.tables[name];
+        let mut none = 1.0;
+        let mut expected = 0.0;
+
+        for drop in table.guaranteed.iter().filter(|d| d.item == item) {
+            if drop.quantity[0] > 0 {
+                none = 0.0;
+            } else {
+                none *= 1.0 / (drop.quantity[1] as f64 + 1.0);
+            }
+            expected += (drop.quantity[0] + drop.quantity[1]) as f64 / 2.0;
+        }
+        if table.entries.is_empty() {
+            return (none, expected);
+        }
+
+        let weights = self.effective_weights(table, luck, 1.0);
+        let total: f64 = weights.iter().sum();
+        let (mut none_per_roll, mut expected_per_roll) = (0.0, 0.0);
+        for (entry, weight) in table.entries.iter().zip(&weights) {
+            let p = weight / total;
+            let (entry_none, entry_expected) = match &entry.kind {
+                EntryKind::Item(drop) if drop.item == item => {
+                    let (lo, hi) = (drop.quantity[0] as f64, drop.quantity[1] as f64);
+                    let zero_chance = if lo == 0.0 { 1.0 / (hi + 1.0) } else { 0.0 };
+                    (zero_chance, (lo + hi) / 2.0)
+                }
+                EntryKind::Table { table } => self.item_odds(table, item, luck),
+                _ => (1.0, 0.0),
+            };
+            none_per_roll += p * entry_none;
+            expected_per_roll += p * entry_expected;
+        }
+
+        none *= none_per_roll.powf(table.rolls as f64);
+        expected += expected_per_roll * table.rolls as f64;
+        (none, expected)
+    }
+
+    fn collect_items(&self, name: &str, items: &mut BTreeMap<String, String>) {
+        let table = &self.tables[name];
+        for drop in &table.guaranteed {
+            items.entry(drop.item.clone()).or_insert_with(|| self.rarity_name(&drop.rarity));
+        }
+        for entry in &table.entries {
+            match &entry.kind {
+                EntryKind::Item(drop) => {
+                    items.entry(drop.item.clone()).or_insert_with(|| self.rarity_name(&drop.rarity));
+                }
+                EntryKind::Table { table } => self.collect_items(table, items),
+                EntryKind::Nothing { .. } => {}
+            }
+        }
+    }
+
+    /// Exact per-item drop chances and expected quantities for one roll of a
+    /// table with no active pity boost.
+    pub fn expected_value(&self, name: &str, luck: u32) -> Result<Vec<ItemOdds>, String> {
+        if !self.tables.contains_key(name) {
+            return Err(format!("Unknown loot table: {}", name));
+        }
+        let mut items = BTreeMap::new();
+        self.collect_items(name, &mut items);
+
+        let mut odds: Vec<ItemOdds> = items
+            .into_iter()
+            .map(|(item, rarity)| {
+                let (none, expected_quantity) = self.item_odds(name, &item, luck);
+                ItemOdds { item, rarity, drop_chance: 1.0 - none, expected_quantity }
+            })
+            .collect();
+        odds.sort_by(|a, b| b.drop_chance.total_cmp(&a.drop_chance));
+        Ok(odds)
+    }
+}
+
+/// Loot tables plus per-table pity counters and an RNG.
+#[cfg(feature = "random")]
+pub struct LootRoller {
+    config: LootConfig,
+    rng: StdRng,
+    pity_streaks: HashMap<String, u32>,
+}
+
+#[cfg(feature = "random")]
+impl LootRoller {
+    pub fn new(config: LootConfig, rng: StdRng) -> Self {
+        LootRoller { config, rng, pity_streaks: HashMap::new() }
+    }
+
+    pub fn roll(&mut self, table: &str, luck: u32) -> Result<LootRoll, String> {
+        if !self.config.tables.contains_key(table) {
+            return Err(format!("Unknown loot table: {}", table));
+        }
+
+        let streak = self.pity_streaks.get(table).copied().unwrap_or(0);
+        let (pity_multiplier, pity_rank) = match &self.config.pity {
+            Some(pity) => {
+                let rank = self.config.rarities.iter().position(|r| *r == pity.rarity).unwrap();
+                let boosted_rolls = streak.saturating_add(1).saturating_sub(pity.after);
+                ((1.0 + pity.boost * boosted_rolls as f64).min(MAX_PITY_MULTIPLIER), Some(rank))
+            }
+            None => (1.0, None),
+        };
+
+        let mut drops = Vec::new();
+        self.config.roll_table(table, luck, pity_multiplier, &mut self.rng, &mut drops);
+
+        let got_rare = pity_rank.is_some_and(|rank| {
+            drops.iter().any(|d| self.config.rarities.iter().position(|r| *r == d.rarity).unwrap() >= rank)
+        });
+        let streak = if got_rare || pity_rank.is_none() { 0 } else { streak.saturating_add(1) };
+        self.pity_streaks.insert(table.to_string(), streak);
+
+        Ok(LootRoll { drops, pity_streak: streak, pity_active: pity_multiplier > 1.0 })
+    }
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub struct LootSystem {
+    roller: LootRoller,
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+impl LootSystem {
+    /// `config` is a JSON loot configuration; see `LootConfig`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: &str, seed: Option<u32>) -> Result<LootSystem, JsValue> {
+        let config = LootConfig::from_json(config).map_err(|e| JsValue::from_str(&e))?;
+        Ok(LootSystem { roller: LootRoller::new(config, seeded_rng(seed)) })
+    }
+
+    /// Rolls a table for a character with the given luck stat.
+    pub fn roll(&mut self, table: &str, luck: u32) -> Result<JsValue, JsValue> {
+        let roll = self.roller.roll(table, luck).map_err(|e| JsValue::from_str(&e))?;
+        Ok(serde_wasm_bindgen::to_value(&roll).unwrap())
+    }
+
+    pub fn expected_value(&self, table: &str, luck: u32) -> Result<JsValue, JsValue> {
+        let odds = self.roller.config.expected_value(table, luck).map_err(|e| JsValue::from_str(&e))?;
+        Ok(serde_wasm_bindgen::to_value(&odds).unwrap())
+    }
+
+    pub fn pity_streak(&self, table: &str) -> u32 {
+        self.roller.pity_streaks.get(table).copied().unwrap_or(0)
+    }
+
+    pub fn reset_pity(&mut self) {
+        self.roller.pity_streaks.clear();
+    }
+}
+
+#[cfg(all(test, feature = "random"))]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"{
+        "rarities": ["common", "rare"],
+        "tables": {
+            "chest": {
+                "rolls": 2,
+                "guaranteed": [{ "item": "coin", "quantity": [0, 3] }],
+                "entries": [
+                    { "weight": 3, "item": "coin", "quantity": [1, 2] },
+                    { "weight": 1, "table": "gems" },
+                    { "weight": 2, "nothing": true }
+                ]
+            },
+            "gems": {
+                "entries": [
+                    { "weight": 1, "item": "ruby", "rarity": "rare" },
+                    { "weight": 4, "item": "quartz" }
+                ]
+            }
+        }
+    }"#;
+
+    #[test]
+    fn expected_drop_chances_match_seeded_rolls() {
+        let config = LootConfig::from_json(CONFIG).unwrap();
+        let odds = config.expected_value("chest", 10).unwrap();
+        let mut roller = LootRoller::new(config, StdRng::seed_from_u64(11));
+
+        let trials = 40_000;
+        let mut seen: HashMap<String, u32> = HashMap::new();
+        for _ in 0..trials {
+            let roll = roller.roll("chest", 10).unwrap();
+            let mut items: Vec<&str> = roll.drops.iter().map(|d| d.item.as_str()).collect();
+            items.sort_unstable();
+            items.dedup();
+            for item in items {
+                *seen.entry(item.to_string()).or_default() += 1;
+            }
+        }
+
+        // ruby: 1 - (1 - 1/6 * 1/5)^2.
+        let ruby = odds.iter().find(|o| o.item == "ruby").unwrap();
+        assert!((ruby.drop_chance - (1.0 - (29.0f64 / 30.0).powi(2))).abs() < 1e-12);
+        for item in &odds {
+            let observed = seen.get(&item.item).copied().unwrap_or(0) as f64 / trials as f64;
+            assert!((observed - item.drop_chance).abs() < 0.01, "{}: {} vs {}", item.item, observed, item.drop_chance);
+        }
+    }
+
+    #[test]
+    fn luck_beyond_the_cap_is_clamped() {
+        let config = LootConfig::from_json(CONFIG).unwrap();
+        let capped = config.expected_value("chest", MAX_LUCK).unwrap();
+        let huge = config.expected_value("chest", u32::MAX).unwrap();
+        for (a, b) in capped.iter().zip(&huge) {
+            assert_eq!(a.drop_chance, b.drop_chance);
+        }
+    }
+
+    #[test]
+    fn rejects_unbounded_configs() {
+        let table = |body: &str| format!(r#"{{ "rarities": ["common"], "tables": {{ "t": {} }} }}"#, body);
+        assert!(LootConfig::from_json(&table(r#"{ "rolls": 101, "entries": [{ "weight": 1, "nothing": true }] }"#)).is_err());
+        assert!(LootConfig::from_json(&table(r#"{ "entries": [{ "weight": 1e300, "nothing": true }] }"#)).is_err());
+        assert!(LootConfig::from_json(&table(r#"{ "entries": [{ "weight": -1, "nothing": true }] }"#)).is_err());
+        let pity = r#"{ "rarities": ["common"], "tables": {}, "pity": { "rarity": "common", "after": 1, "boost": -1 } }"#;
+        assert!(LootConfig::from_json(pity).is_err());
+
+        let nested = r#"{ "rarities": ["common"], "tables": {
+            "a": { "rolls": 100, "entries": [{ "weight": 1, "table": "b" }] },
+            "b": { "rolls": 100, "entries": [{ "weight": 1, "table": "c" }] },
+            "c": { "rolls": 100, "entries": [{ "weight": 1, "item": "x" }] }
+        } }"#;
+        assert!(LootConfig::from_json(nested).unwrap_err().contains("more than"));
+    }
+}
@@ -2,6 +2,12 @@
 use wasm_bindgen::prelude::*;
 #[cfg(feature = "math")]
 use nalgebra as na;
+#[cfg(feature = "math")]
+use super::linear_system::{solve_system, SystemVerdict};
+#[cfg(feature = "math")]
+use super::matrix::MatrixData;
+#[cfg(feature = "math")]
+use super::transform::{transform_report, TransformOp};
 
 #[cfg(feature = "math")]
 #[wasm_bindgen]
@@ -12,10 +18,15 @@ pub fn matrix_operations(a11: f64, a12: f64, a21: f64, a22: f64) -> String {
     let trace = matrix.trace();
     let transpose = matrix.transpose();
     
-    let symmetric = na::Matrix2::from_fn(|i, j| {
-        if i == j { matrix[(i, j)] } else { (matrix[(i, j)] + matrix[(j, i)]) / 2.0 }
-    });
-    let eigen = symmetric.symmetric_eigen();
+    let eigen_result = match super::eigen::eigen_analysis(&na::DMatrix::from_row_slice(2, 2, &[a11, a12, a21, a22])) {
+        Ok(eigen) => format!(
+            "Eigenvalues: [{}] ({} solver)\nDiagonalizable: {}",
+            eigen.eigenvalues.iter().map(|v| v.format(4)).collect::<Vec<_>>().join(", "),
+            if eigen.symmetric_path { "symmetric" } else { "general" },
+            eigen.diagonalizable
+        ),
+        Err(e) => format!("Eigenvalues: {}", e),
+    };
     
     let inverse_result = if determinant.abs() > 1e-10 {
         match matrix.try_inverse() {
@@ -28,46 +39,51 @@ pub fn matrix_operations(a11: f64, a12: f64, a21: f64, a22: f64) -> String {
     };
     
     format!(
-        "Matrix Analysis\n\nOriginal Matrix:\n[{:.3}, {:.3}]\n[{:.3}, {:.3}]\n\nDeterminant: {:.6}\nTrace: {:.6}\n\nTranspose:\n[{:.3}, {:.3}]\n[{:.3}, {:.3}]\n\n{}\n\nEigenvalues: [{:.4}, {:.4}]",
+        "Matrix Analysis\n\nOriginal Matrix:\n[{:.3}, {:.3}]\n[{:.3}, {:.3}]\n\nDeterminant: {:.6}\nTrace: {:.6}\n\nTranspose:\n[{:.3}, {:.3}]\n[{:.3}, {:.3}]\n\n{}\n\n{}",
         a11, a12, a21, a22,
         determinant, trace,
         transpose[(0,0)], transpose[(0,1)], transpose[(1,0)], transpose[(1,1)],
         inverse_result,
-        eigen.eigenvalues[0], eigen.eigenvalues[1]
+        eigen_result
     )
 }
 
 #[cfg(feature = "math")]
 #[wasm_bindgen]
 pub fn geometric_transformation(x: f64, y: f64, z: f64, angle_deg: f64) -> String {
-    let point = na::Point3::new(x, y, z);
-    let angle = angle_deg.to_radians();
-    
-    let rot_x = na::Rotation3::from_axis_angle(&na::Vector3::x_axis(), angle);
-    let rot_y = na::Rotation3::from_axis_angle(&na::Vector3::y_axis(), angle);
-    let rot_z = na::Rotation3::from_axis_angle(&na::Vector3::z_axis(), angle);
-    
-    let rotated_x = rot_x * point;
-    let rotated_y = rot_y * point;
-    let rotated_z = rot_z * point;
-    
-    let combined = rot_z * rot_y * rot_x * point;
-    
-    let scale = na::Scale3::new(1.5, 0.75, 2.0);
-    let scaled = scale * point;
-    
-    let translation = na::Translation3::new(1.0, -2.0, 3.0);
-    let translated = translation * point;
-    
+    let chain = [
+        TransformOp::Euler { degrees: [angle_deg; 3], order: "xyz".to_string() },
+        TransformOp::Scale { factors: [1.5, 0.75, 2.0] },
+        TransformOp::Translate { offset: [1.0, -2.0, 3.0] },
+    ];
+    let report = match transform_report(&chain, [x, y, z]) {
+        Ok(report) => report,
+        Err(e) => return format!("Error: {}", e),
+    };
+
+    let format_matrix = |m: &MatrixData| {
+        m.data
+            .chunks(m.cols)
+            .map(|row| format!("  [{}]", row.iter().map(|v| format!("{:8.3}", v)).collect::<Vec<_>>().join(" ")))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let steps = report
+        .steps
+        .iter()
+        .map(|step| format!("  {} → ({:.3}, {:.3}, {:.3})", step.operation, step.point[0], step.point[1], step.point[2]))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let inverse = report.inverse.as_ref().map_or_else(|| "  (singular)".to_string(), format_matrix);
+
     format!(
-        "3D Geometric Transformations\n\nOriginal point: ({:.2}, {:.2}, {:.2})\nAngle: {:.1}°\n\nRotations:\n  Around X-axis: ({:.3}, {:.3}, {:.3})\n  Around Y-axis: ({:.3}, {:.3}, {:.3})\n  Around Z-axis: ({:.3}, {:.3}, {:.3})\n  Combined (X→Y→Z): ({:.3}, {:.3}, {:.3})\n\nScaling (1.5, 0.75, 2.0): ({:.3}, {:.3}, {:.3})\nTranslation (1, -2, 3): ({:.3}, {:.3}, {:.3})",
+        "3D Geometric Transformations\n\nOriginal point: ({:.2}, {:.2}, {:.2})\nAngle: {:.1}°\n\nSteps:\n{}\n\nComposed matrix (det {:.3}):\n{}\n\nInverse:\n{}\n\nTransformed point: ({:.3}, {:.3}, {:.3})",
         x, y, z, angle_deg,
-        rotated_x.x, rotated_x.y, rotated_x.z,
-        rotated_y.x, rotated_y.y, rotated_y.z,
-        rotated_z.x, rotated_z.y, rotated_z.z,
-        combined.x, combined.y, combined.z,
-        scaled.x, scaled.y, scaled.z,
-        translated.x, translated.y, translated.z
+        steps,
+        report.determinant,
+        format_matrix(&report.matrix),
+        inverse,
+        report.transformed[0], report.transformed[1], report.transformed[2]
     )
 }
 
@@ -113,38 +129,36 @@ pub fn vector_operations(x1: f64, y1: f64, z1: f64, x2: f64, y2: f64, z2: f64) -
 #[cfg(feature = "math")]
 #[wasm_bindgen]
 pub fn solve_linear_system(a11: f64, a12: f64, b1: f64, a21: f64, a22: f64, b2: f64) -> String {
-    let a = na::Matrix2::new(a11, a12, a21, a22);
-    let b = na::Vector2::new(b1, b2);
+    let a = na::DMatrix::from_row_slice(2, 2, &[a11, a12, a21, a22]);
+    let b = na::DVector::from_column_slice(&[b1, b2]);
     
     let det = a.determinant();
     
-    let solution = if det.abs() > 1e-10 {
-        match a.lu().solve(&b) {
-            Some(x) => format!("Solution: x1 = {:.6}, x2 = {:.6}\n\nVerification:\n  {:.3}*{:.6} + {:.3}*{:.6} = {:.6} (expected: {:.3})\n  {:.3}*{:.6} + {:.3}*{:.6} = {:.6} (expected: {:.3})",
-                x[0], x[1],
-                a11, x[0], a12, x[1], a11*x[0] + a12*x[1], b1,
-                a21, x[0], a22, x[1], a21*x[0] + a22*x[1], b2),
-            None => "No unique solution (system is singular)".to_string(),
-        }
-    } else {
-        let rank_a = if det.abs() < 1e-10 { 1 } else { 2 };
-        
-        if rank_a == 1 {
-            let ratio1 = if a11.abs() > 1e-10 { b1 / a11 } else { 0.0 };
-            let ratio2 = if a21.abs() > 1e-10 { b2 / a21 } else { 0.0 };
-            
-            if (ratio1 - ratio2).abs() < 1e-10 {
-                "Infinite solutions (dependent system)".to_string()
-            } else {
-                "No solution (inconsistent system)".to_string()
+    let system = solve_system(&a, &b);
+    let solution = match system {
+        Ok(s) => match s.verdict {
+            SystemVerdict::Unique => {
+                let (x1, x2) = (s.solution[0], s.solution[1]);
+                format!("Solution: x1 = {:.6}, x2 = {:.6}\n\nVerification:\n  {:.3}*{:.6} + {:.3}*{:.6} = {:.6} (expected: {:.3})\n  {:.3}*{:.6} + {:.3}*{:.6} = {:.6} (expected: {:.3})",
+                    x1, x2,
+                    a11, x1, a12, x2, a11*x1 + a12*x2, b1,
+                    a21, x1, a22, x2, a21*x1 + a22*x2, b2)
             }
-        } else {
-            "System analysis failed".to_string()
-        }
+            SystemVerdict::Infinite => format!(
+                "Infinite solutions (dependent system, rank {})\n\nSolution set:\n  {}",
+                s.rank,
+                s.parametric_form.join("\n  ")
+            ),
+            SystemVerdict::Inconsistent => format!(
+                "No solution (inconsistent system: rank {} vs augmented rank {})\n\nLeast-squares fit: x1 = {:.6}, x2 = {:.6} (residual {:.6})",
+                s.rank, s.augmented_rank, s.solution[0], s.solution[1], s.residual_norm
+            ),
+        },
+        Err(e) => format!("System analysis failed: {}", e),
     };
     
     format!(
         "Linear System Solver\n\nSystem of equations:\n  {:.3}x₁ + {:.3}x₂ = {:.3}\n  {:.3}x₁ + {:.3}x₂ = {:.3}\n\nMatrix determinant: {:.6}\n\n{}",
         a11, a12, b1, a21, a22, b2, det, solution
     )
-}
\ No newline at end of file
+}
@@ -0,0 +1,145 @@
+#[cfg(feature = "math")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "math")]
+use nalgebra as na;
+#[cfg(feature = "math")]
+use serde::Serialize;
+
+/// A matrix as it crosses the wasm boundary: row-major values plus shape,
+/// the same layout `matrix_from_array` accepts.
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct MatrixData {
+    pub rows: usize,
+    pub cols: usize,
+    pub data: Vec<f64>,
+}
+
+#[cfg(feature = "math")]
+impl From<&na::DMatrix<f64>> for MatrixData {
+    fn from(matrix: &na::DMatrix<f64>) -> Self {
+        MatrixData {
+            rows: matrix.nrows(),
+            cols: matrix.ncols(),
+            data: matrix.transpose().as_slice().to_vec(),
+        }
+    }
+}
+
+/// Builds a matrix from row-major `data`.
+#[cfg(feature = "math")]
+pub fn matrix_from_array(data: &[f64], rows: usize, cols: usize) -> Result<na::DMatrix<f64>, String> {
+    if rows == 0 || cols == 0 {
+        return Err("Matrix dimensions must be positive".to_string());
+    }
+    let size = rows.checked_mul(cols).ok_or_else(|| format!("A {}x{} matrix is too large", rows, cols))?;
+    if data.len() != size {
+        return Err(format!("Expected {} values for a {}x{} matrix, got {}", size, rows, cols, data.len()));
+    }
+    if data.iter().any(|v| !v.is_finite()) {
+        return Err("Matrix entries must be finite numbers".to_string());
+    }
+    Ok(na::DMatrix::from_row_slice(rows, cols, data))
+}
+
+/// Singular values below this are treated as zero, following the usual
+/// `max(m, n) * sigma_max * epsilon` convention.
+#[cfg(feature = "math")]
+pub fn rank_tolerance(singular_values: &na::DVector<f64>, rows: usize, cols: usize) -> f64 {
+    rows.max(cols) as f64 * singular_values.max() * f64::EPSILON
+}
+
+#[cfg(feature = "math")]
+pub fn numerical_rank(matrix: &na::DMatrix<f64>) -> usize {
+    let singular_values = matrix.singular_values();
+    let tolerance = rank_tolerance(&singular_values, matrix.nrows(), matrix.ncols());
+    singular_values.iter().filter(|&&s| s > tolerance).count()
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct MatrixNorms {
+    pub frobenius: f64,
+    /// Maximum absolute column sum.
+    pub one: f64,
+    /// Maximum absolute row sum.
+    pub infinity: f64,
+    /// Largest singular value.
+    pub spectral: f64,
+    /// Sum of singular values.
+    pub nuclear: f64,
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct MatrixAnalysis {
+    pub matrix: MatrixData,
+    pub square: bool,
+    pub determinant: Option<f64>,
+    pub trace: Option<f64>,
+    pub rank: usize,
+    pub full_rank: bool,
+    pub transpose: MatrixData,
+    /// Present only for square, full-rank matrices.
+    pub inverse: Option<MatrixData>,
+    /// Moore–Penrose pseudo-inverse, always available.
+    pub pseudo_inverse: MatrixData,
+    pub singular_values: Vec<f64>,
+    pub norms: MatrixNorms,
+    /// 2-norm condition number; infinite for rank-deficient matrices.
+    pub condition_number: f64,
+}
+
+#[cfg(feature = "math")]
+pub fn analyze_matrix(matrix: &na::DMatrix<f64>) -> MatrixAnalysis {
+    let (rows, cols) = matrix.shape();
+    let square = rows == cols;
+
+    let svd = matrix.clone().svd(true, true);
+    let singular_values = svd.singular_values.clone();
+    let tolerance = rank_tolerance(&singular_values, rows, cols);
+    let rank = singular_values.iter().filter(|&&s| s > tolerance).count();
+    let full_rank = rank == rows.min(cols);
+
+    let sigma_max = singular_values.max();
+    let sigma_min = singular_values.min();
+    let condition_number = if full_rank && sigma_min > 0.0 { sigma_max / sigma_min } else { f64::INFINITY };
+
+    let inverse = if square && full_rank {
+        matrix.clone().try_inverse().map(|inv| MatrixData::from(&inv))
+    } else {
+        None
+    };
+    let pseudo_inverse = svd.pseudo_inverse(tolerance).unwrap();
+
+    let norms = MatrixNorms {
+        frobenius: matrix.norm(),
+        one: matrix.column_iter().map(|c| c.abs().sum()).fold(0.0, f64::max),
+        infinity: matrix.row_iter().map(|r| r.abs().sum()).fold(0.0, f64::max),
+        spectral: sigma_max,
+        nuclear: singular_values.sum(),
+    };
+
+    MatrixAnalysis {
+        matrix: MatrixData::from(matrix),
+        square,
+        determinant: square.then(|| matrix.determinant()),
+        trace: square.then(|| matrix.trace()),
+        rank,
+        full_rank,
+        transpose: MatrixData::from(&matrix.transpose()),
+        inverse,
+        pseudo_inverse: MatrixData::from(&pseudo_inverse),
+        singular_values: singular_values.iter().copied().collect(),
+        norms,
+        condition_number,
+    }
+}
+
+/// `data` holds `rows * cols` values in row-major order.
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub fn matrix_analysis(data: &[f64], rows: u32, cols: u32) -> Result<JsValue, JsValue> {
+    let matrix = matrix_from_array(data, rows as usize, cols as usize).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&analyze_matrix(&matrix)).unwrap())
+}
@@ -0,0 +1,381 @@
+#[cfg(feature = "random")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "random")]
+use rand::prelude::*;
+#[cfg(feature = "random")]
+use serde::Serialize;
+#[cfg(feature = "random")]
+use std::cmp::Reverse;
+#[cfg(feature = "random")]
+use std::collections::{BinaryHeap, VecDeque};
+#[cfg(feature = "random")]
+use super::random::seeded_rng;
+
+// Wall bits stored per cell.
+#[cfg(feature = "random")]
+pub const WALL_NORTH: u8 = 1;
+#[cfg(feature = "random")]
+pub const WALL_EAST: u8 = 2;
+#[cfg(feature = "random")]
+pub const WALL_SOUTH: u8 = 4;
+#[cfg(feature = "random")]
+pub const WALL_WEST: u8 = 8;
+
+/// Largest maze accepted, in cells; traces grow with the cell count.
+#[cfg(feature = "random")]
+pub const MAX_MAZE_CELLS: u32 = 1_000_000;
+
+/// `width * height`, rejecting empty mazes and ones over `MAX_MAZE_CELLS`.
+#[cfg(feature = "random")]
+fn checked_cell_count(width: u32, height: u32) -> Result<u32, String> {
+    if width == 0 || height == 0 {
+        return Err("Maze dimensions must be at least 1x1".to_string());
+    }
+    width
+        .checked_mul(height)
+        .filter(|&cells| cells <= MAX_MAZE_CELLS)
+        .ok_or_else(|| format!("Mazes can have at most {} cells", MAX_MAZE_CELLS))
+}
+
+/// One event in a generation or solving trace. Cells are indexed `y * width + x`.
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MazeStep {
+    /// The wall between two neighbouring cells was removed.
+    Carve { from: u32, to: u32 },
+    /// A cell became part of the maze, or was expanded by a solver.
+    Visit { cell: u32 },
+    /// The recursive backtracker returned from a dead end.
+    Backtrack { cell: u32 },
+    /// Prim's algorithm added a cell to its frontier.
+    Frontier { cell: u32 },
+    /// Wilson's algorithm moved its random walk onto a cell.
+    Walk { cell: u32 },
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct Maze {
+    pub width: u32,
+    pub height: u32,
+    /// Wall bitmask per cell: north 1, east 2, south 4, west 8.
+    pub walls: Vec<u8>,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct GeneratedMaze {
+    #[serde(flatten)]
+    pub maze: Maze,
+    pub steps: Vec<MazeStep>,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct MazeSolution {
+    pub found: bool,
+    /// Cells from start to goal, inclusive; empty when no path exists.
+    pub path: Vec<u32>,
+    pub visited: usize,
+    pub steps: Vec<MazeStep>,
+}
+
+#[cfg(feature = "random")]
+impl Maze {
+    /// A maze with every wall standing.
+    pub fn closed(width: u32, height: u32) -> Result<Self, String> {
+        let cells = checked_cell_count(width, height)?;
+        Ok(Maze { width, height, walls: vec![WALL_NORTH | WALL_EAST | WALL_SOUTH | WALL_WEST; cells as usize] })
+    }
+
+    pub fn from_walls(width: u32, height: u32, walls: Vec<u8>) -> Result<Self, String> {
+        let cells = checked_cell_count(width, height)?;
+        if walls.len() != cells as usize {
+            return Err(format!("Expected {} wall entries for a {}x{} maze", cells, width, height));
+        }
+        Ok(Maze { width, height, walls })
+    }
+
+    fn cell_count(&self) -> usize {
+        (self.width * self.height) as usize
+    }
+
+    /// Neighbouring cells paired with the wall bit that separates them.
+    fn neighbours(&self, cell: u32) -> impl Iterator<Item = (u32, u8)> {
+        let (x, y, w, h) = (cell % self.width, cell / self.width, self.width, self.height);
+        [
+            (y > 0).then(|| (cell - w, WALL_NORTH)),
+            (x + 1 < w).then(|| (cell + 1, WALL_EAST)),
+            (y + 1 < h).then(|| (cell + w, WALL_SOUTH)),
+            (x > 0).then(|| (cell - 1, WALL_WEST)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Neighbours reachable without crossing a wall.
+    fn open_neighbours(&self, cell: u32) -> impl Iterator<Item = u32> + '_ {
+        self.neighbours(cell)
+            .filter(move |&(_, wall)| self.walls[cell as usize] & wall == 0)
+            .map(|(next, _)| next)
+    }
+
+    fn carve(&mut self, from: u32, to: u32) {
+        let wall = self.neighbours(from).find(|&(n, _)| n == to).map(|(_, wall)| wall).unwrap();
+        let opposite = match wall {
+            WALL_NORTH => WALL_SOUTH,
+            WALL_EAST => WALL_WEST,
+            WALL_SOUTH => WALL_NORTH,
+            _ => WALL_EAST,
+        };
+        self.walls[from as usize] &= !wall;
+        self.walls[to as usize] &= !opposite;
+    }
+}
+
+#[cfg(feature = "random")]
+fn recursive_backtracker<R: Rng + ?Sized>(maze: &mut Maze, rng: &mut R, steps: &mut Vec<MazeStep>) {
+    let mut visited = vec![false; maze.cell_count()];
+    let start = rng.gen_range(0..maze.cell_count() as u32);
+    let mut stack = vec![start];
+    visited[start as usize] = true;
+    steps.push(MazeStep::Visit { cell: start });
+
+    while let Some(&cell) = stack.last() {
+        let unvisited: Vec<u32> = maze.neighbours(cell).map(|(n, _)| n).filter(|&n| !visited[n as usize]).collect();
+        match unvisited.choose(rng) {
+            Some(&next) => {
+                maze.carve(cell, next);
+                visited[next as usize] = true;
+                steps.push(MazeStep::Carve { from: cell, to: next });
+                stack.push(next);
+            }
+            None => {
+                stack.pop();
+                steps.push(MazeStep::Backtrack { cell });
+            }
+        }
+    }
+}
+
+/// Marks `cell` as part of the maze and moves its outside neighbours onto the frontier.
+#[cfg(feature = "random")]
+fn prim_add(maze: &Maze, cell: u32, in_maze: &mut [bool], in_frontier: &mut [bool], frontier: &mut Vec<u32>, steps: &mut Vec<MazeStep>) {
+    in_maze[cell as usize] = true;
+    for (n, _) in maze.neighbours(cell) {
+        if !in_maze[n as usize] && !in_frontier[n as usize] {
+            in_frontier[n as usize] = true;
+            frontier.push(n);
+            steps.push(MazeStep::Frontier { cell: n });
+        }
+    }
+}
+
+#[cfg(feature = "random")]
+fn prim<R: Rng + ?Sized>(maze: &mut Maze, rng: &mut R, steps: &mut Vec<MazeStep>) {
+    let mut in_maze = vec![false; maze.cell_count()];
+    let mut in_frontier = vec![false; maze.cell_count()];
+    let mut frontier = Vec::new();
+
+    let start = rng.gen_range(0..maze.cell_count() as u32);
+    steps.push(MazeStep::Visit { cell: start });
+    prim_add(maze, start, &mut in_maze, &mut in_frontier, &mut frontier, steps);
+
+    while !frontier.is_empty() {
+        let cell = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+        let joined: Vec<u32> = maze.neighbours(cell).map(|(n, _)| n).filter(|&n| in_maze[n as usize]).collect();
+        let &from = joined.choose(rng).unwrap();
+        maze.carve(from, cell);
+        steps.push(MazeStep::Carve { from, to: cell });
+        prim_add(maze, cell, &mut in_maze, &mut in_frontier, &mut frontier, steps);
+    }
+}
+
+#[cfg(feature = "random")]
+fn find_root(parents: &mut [u32], mut cell: u32) -> u32 {
+    while parents[cell as usize] != cell {
+        parents[cell as usize] = parents[parents[cell as usize] as usize];
+        cell = parents[cell as usize];
+    }
+    cell
+}
+
+#[cfg(feature = "random")]
+fn kruskal<R: Rng + ?Sized>(maze: &mut Maze, rng: &mut R, steps: &mut Vec<MazeStep>) {
+    let mut edges: Vec<(u32, u32)> = (0..maze.cell_count() as u32)
+        .flat_map(|cell| {
+            maze.neighbours(cell)
+                .filter(|&(_, wall)| wall == WALL_EAST || wall == WALL_SOUTH)
+                .map(move |(n, _)| (cell, n))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    edges.shuffle(rng);
+
+    let mut parents: Vec<u32> = (0..maze.cell_count() as u32).collect();
+    for (a, b) in edges {
+        let (root_a, root_b) = (find_root(&mut parents, a), find_root(&mut parents, b));
+        if root_a != root_b {
+            parents[root_a as usize] = root_b;
+            maze.carve(a, b);
+            steps.push(MazeStep::Carve { from: a, to: b });
+        }
+    }
+}
+
+#[cfg(feature = "random")]
+fn wilson<R: Rng + ?Sized>(maze: &mut Maze, rng: &mut R, steps: &mut Vec<MazeStep>) {
+    let count = maze.cell_count();
+    let mut in_maze = vec![false; count];
+    let first = rng.gen_range(0..count as u32);
+    in_maze[first as usize] = true;
+    steps.push(MazeStep::Visit { cell: first });
+    // For each cell on the current walk, the cell the walk last moved on to.
+    let mut next_on_walk = vec![u32::MAX; count];
+
+    for start in 0..count as u32 {
+        if in_maze[start as usize] {
+            continue;
+        }
+        // Random walk until the maze is hit; overwriting exits erases loops.
+        let mut cell = start;
+        steps.push(MazeStep::Walk { cell });
+        while !in_maze[cell as usize] {
+            let options: Vec<u32> = maze.neighbours(cell).map(|(n, _)| n).collect();
+            let &next = options.choose(rng).unwrap();
+            next_on_walk[cell as usize] = next;
+            steps.push(MazeStep::Walk { cell: next });
+            cell = next;
+        }
+
+        let mut cell = start;
+        while !in_maze[cell as usize] {
+            let next = next_on_walk[cell as usize];
+            in_maze[cell as usize] = true;
+            maze.carve(cell, next);
+            steps.push(MazeStep::Carve { from: cell, to: next });
+            cell = next;
+        }
+    }
+}
+
+/// Generates a perfect maze. `algorithm` is "backtracker", "prim", "kruskal" or "wilson".
+#[cfg(feature = "random")]
+pub fn generate_maze_with<R: Rng + ?Sized>(
+    width: u32,
+    height: u32,
+    algorithm: &str,
+    rng: &mut R,
+) -> Result<GeneratedMaze, String> {
+    let mut maze = Maze::closed(width, height)?;
+    let mut steps = Vec::new();
+    match algorithm {
+        "backtracker" => recursive_backtracker(&mut maze, rng, &mut steps),
+        "prim" => prim(&mut maze, rng, &mut steps),
+        "kruskal" => kruskal(&mut maze, rng, &mut steps),
+        "wilson" => wilson(&mut maze, rng, &mut steps),
+        other => return Err(format!("Unknown maze algorithm: {}", other)),
+    }
+    Ok(GeneratedMaze { maze, steps })
+}
+
+#[cfg(feature = "random")]
+fn rebuild_path(came_from: &[u32], start: u32, goal: u32) -> Vec<u32> {
+    let mut path = vec![goal];
+    let mut cell = goal;
+    while cell != start {
+        cell = came_from[cell as usize];
+        path.push(cell);
+    }
+    path.reverse();
+    path
+}
+
+/// Finds a path with "bfs", "dfs" or "astar" (Manhattan heuristic).
+#[cfg(feature = "random")]
+pub fn solve_maze_with(maze: &Maze, start: u32, goal: u32, algorithm: &str) -> Result<MazeSolution, String> {
+    let count = maze.cell_count() as u32;
+    if start >= count || goal >= count {
+        return Err(format!("Start and goal must be cells between 0 and {}", count - 1));
+    }
+
+    let mut came_from = vec![u32::MAX; count as usize];
+    let mut steps = Vec::new();
+    came_from[start as usize] = start;
+
+    let found = match algorithm {
+        "bfs" | "dfs" => {
+            let mut pending = VecDeque::from([start]);
+            let mut found = false;
+            while let Some(cell) = if algorithm == "bfs" { pending.pop_front() } else { pending.pop_back() } {
+                steps.push(MazeStep::Visit { cell });
+                if cell == goal {
+                    found = true;
+                    break;
+                }
+                for next in maze.open_neighbours(cell) {
+                    if came_from[next as usize] == u32::MAX {
+                        came_from[next as usize] = cell;
+                        pending.push_back(next);
+                    }
+                }
+            }
+            found
+        }
+        "astar" => {
+            let (gx, gy) = (goal % maze.width, goal / maze.width);
+            let heuristic = |cell: u32| (cell % maze.width).abs_diff(gx) + (cell / maze.width).abs_diff(gy);
+            let mut cost = vec![u32::MAX; count as usize];
+            cost[start as usize] = 0;
+            let mut open = BinaryHeap::from([Reverse((heuristic(start), 0, start))]);
+            let mut found = false;
+
+            while let Some(Reverse((_, g, cell))) = open.pop() {
+                if g > cost[cell as usize] {
+                    continue;
+                }
+                steps.push(MazeStep::Visit { cell });
+                if cell == goal {
+                    found = true;
+                    break;
+                }
+                for next in maze.open_neighbours(cell) {
+                    if g + 1 < cost[next as usize] {
+                        cost[next as usize] = g + 1;
+                        came_from[next as usize] = cell;
+                        open.push(Reverse((g + 1 + heuristic(next), g + 1, next)));
+                    }
+                }
+            }
+            found
+        }
+        other => return Err(format!("Unknown maze solver: {}", other)),
+    };
+
+    Ok(MazeSolution {
+        found,
+        path: if found { rebuild_path(&came_from, start, goal) } else { Vec::new() },
+        visited: steps.len(),
+        steps,
+    })
+}
+
+/// Returns `{ width, height, walls, steps }`; see `MazeStep` for the trace format.
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn generate_maze(width: u32, height: u32, algorithm: &str, seed: Option<u32>) -> Result<JsValue, JsValue> {
+    let mut rng = seeded_rng(seed);
+    let maze = generate_maze_with(width, height, algorithm, &mut rng).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&maze).unwrap())
+}
+
+/// Solves from the top-left to the bottom-right cell of a maze returned by `generate_maze`.
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn solve_maze(width: u32, height: u32, walls: Vec<u8>, algorithm: &str) -> Result<JsValue, JsValue> {
+    let maze = Maze::from_walls(width, height, walls).map_err(|e| JsValue::from_str(&e))?;
+    let goal = (maze.cell_count() - 1) as u32;
+    let solution = solve_maze_with(&maze, 0, goal, algorithm).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&solution).unwrap())
+}
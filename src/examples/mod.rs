@@ -3,6 +3,81 @@ pub mod basic;
 #[cfg(feature = "random")]
 pub mod random;
 
+#[cfg(feature = "random")]
+pub mod poker;
+
+#[cfg(feature = "random")]
+pub mod blackjack;
+
+#[cfg(feature = "random")]
+pub mod character;
+
+#[cfg(feature = "random")]
+pub mod character_save;
+
+#[cfg(feature = "random")]
+pub mod names;
+
+#[cfg(feature = "random")]
+pub mod fake_data;
+
+#[cfg(feature = "random")]
+pub mod distributions;
+
+#[cfg(feature = "random")]
+pub mod noise;
+
+#[cfg(feature = "random")]
+pub mod maze;
+
+#[cfg(feature = "random")]
+pub mod identifiers;
+
+#[cfg(feature = "random")]
+pub mod loot;
+
+#[cfg(feature = "random")]
+pub mod rng_quality;
+
+#[cfg(feature = "random")]
+pub mod palette;
+
+#[cfg(feature = "random")]
+pub mod automata;
+
+#[cfg(feature = "math")]
+pub mod matrix;
+
+#[cfg(feature = "math")]
+pub mod decompositions;
+
+#[cfg(feature = "math")]
+pub mod eigen;
+
+#[cfg(feature = "math")]
+pub mod linear_system;
+
+#[cfg(feature = "math")]
+pub mod elimination;
+
+#[cfg(feature = "math")]
+pub mod expression;
+
+#[cfg(feature = "math")]
+pub mod symbolic;
+
+#[cfg(feature = "math")]
+pub mod plot;
+
+#[cfg(feature = "math")]
+pub mod numerics;
+
+#[cfg(feature = "math")]
+pub mod ode;
+
+#[cfg(feature = "math")]
+pub mod transform;
+
 #[cfg(feature = "math")]
 pub mod math;
 
@@ -15,6 +90,81 @@ pub use basic::*;
 #[cfg(feature = "random")]
 pub use random::*;
 
+#[cfg(feature = "random")]
+pub use poker::*;
+
+#[cfg(feature = "random")]
+pub use blackjack::*;
+
+#[cfg(feature = "random")]
+pub use character::*;
+
+#[cfg(feature = "random")]
+pub use character_save::*;
+
+#[cfg(feature = "random")]
+pub use names::*;
+
+#[cfg(feature = "random")]
+pub use fake_data::*;
+
+#[cfg(feature = "random")]
+pub use distributions::*;
+
+#[cfg(feature = "random")]
+pub use noise::*;
+
+#[cfg(feature = "random")]
+pub use maze::*;
+
+#[cfg(feature = "random")]
+pub use identifiers::*;
+
+#[cfg(feature = "random")]
+pub use loot::*;
+
+#[cfg(feature = "random")]
+pub use rng_quality::*;
+
+#[cfg(feature = "random")]
+pub use palette::*;
+
+#[cfg(feature = "random")]
+pub use automata::*;
+
+#[cfg(feature = "math")]
+pub use matrix::*;
+
+#[cfg(feature = "math")]
+pub use decompositions::*;
+
+#[cfg(feature = "math")]
+pub use eigen::*;
+
+#[cfg(feature = "math")]
+pub use linear_system::*;
+
+#[cfg(feature = "math")]
+pub use elimination::*;
+
+#[cfg(feature = "math")]
+pub use expression::*;
+
+#[cfg(feature = "math")]
+pub use symbolic::*;
+
+#[cfg(feature = "math")]
+pub use plot::*;
+
+#[cfg(feature = "math")]
+pub use numerics::*;
+
+#[cfg(feature = "math")]
+pub use ode::*;
+
+#[cfg(feature = "math")]
+pub use transform::*;
+
 #[cfg(feature = "math")]
 pub use math::*;
 
@@ -0,0 +1,345 @@
+#[cfg(feature = "random")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "random")]
+use rand::prelude::*;
+#[cfg(feature = "random")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "random")]
+use super::random::seeded_rng;
+
+// Markers for the start and end of a name inside the chain.
+#[cfg(feature = "random")]
+const START: char = '^';
+#[cfg(feature = "random")]
+const END: char = '$';
+
+// How many candidates may be rejected per requested name before giving up.
+#[cfg(feature = "random")]
+const ATTEMPTS_PER_NAME: usize = 200;
+
+// Upper bounds on the sizes callers may ask for.
+#[cfg(feature = "random")]
+pub const MAX_NAME_COUNT: usize = 10_000;
+#[cfg(feature = "random")]
+pub const MAX_MARKOV_ORDER: usize = 8;
+#[cfg(feature = "random")]
+pub const MAX_NAME_LENGTH: usize = 64;
+#[cfg(feature = "random")]
+pub const MAX_MIDDLE_SYLLABLES: usize = 16;
+
+#[cfg(feature = "random")]
+fn check_count(count: usize) -> Result<(), String> {
+    if count > MAX_NAME_COUNT {
+        return Err(format!("Ask for at most {} names at a time", MAX_NAME_COUNT));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "random")]
+pub struct NameTheme {
+    pub id: &'static str,
+    pub corpus: &'static [&'static str],
+    /// Syllables for the beginning, middle and end of a name.
+    pub syllables: [&'static [&'static str]; 3],
+}
+
+#[cfg(feature = "random")]
+pub const NAME_THEMES: &[NameTheme] = &[
+    NameTheme {
+        id: "fantasy",
+        corpus: &[
+            "Aelar", "Aerendil", "Alaric", "Arannis", "Belanor", "Caelynn", "Celebrin", "Dorian",
+            "Elandra", "Elowen", "Eryndor", "Faelar", "Galinndan", "Gwendolyn", "Ilyana", "Isolde",
+            "Kethryllia", "Larethian", "Lirael", "Mialee", "Morwen", "Naivara", "Quarion", "Rhiannon",
+            "Riardon", "Sariel", "Silvyr", "Thalion", "Theren", "Valanthe", "Varis", "Ysolde",
+        ],
+        syllables: [
+            &["Ae", "Al", "Ar", "Bel", "Cae", "El", "Fae", "Gal", "Il", "Lir", "Mor", "Syl", "Tha", "Val"],
+            &["a", "an", "dri", "e", "la", "li", "ma", "re", "ri", "tha", "va", "wy"],
+            &["dil", "dor", "el", "ion", "lynn", "nor", "ra", "ren", "riel", "wen", "wyn", "ys"],
+        ],
+    },
+    NameTheme {
+        id: "scifi",
+        corpus: &[
+            "Anakor", "Axon", "Caelix", "Cygnar", "Daxen", "Deckard", "Eztli", "Halcyon", "Ixion",
+            "Juno", "Kaelen", "Korvex", "Lyra", "Maxor", "Nexa", "Novak", "Orin", "Praxis", "Quill",
+            "Rasczak", "Ripley", "Sarkon", "Solus", "Talos", "Tycho", "Vanta", "Vexis", "Xandar",
+            "Xeno", "Zarek", "Zenith", "Zorin",
+        ],
+        syllables: [
+            &["Ax", "Cy", "Dax", "Ix", "Kor", "Lyr", "Nex", "Or", "Prax", "Sol", "Tal", "Vex", "Xan", "Zar"],
+            &["a", "e", "i", "o", "on", "ra", "te", "u", "ve", "xa", "y"],
+            &["ax", "ek", "ex", "in", "is", "ix", "on", "or", "os", "us", "yx", "zar"],
+        ],
+    },
+    NameTheme {
+        id: "norse",
+        corpus: &[
+            "Arnbjorn", "Asgeir", "Astrid", "Bjorn", "Brynhild", "Dagny", "Egil", "Eirik", "Frida",
+            "Gunnar", "Gudrun", "Halfdan", "Hallgerd", "Helga", "Ingrid", "Ivar", "Kjartan", "Leif",
+            "Ragnar", "Ragnhild", "Sigrid", "Sigurd", "Solveig", "Steinar", "Svala", "Thorfinn",
+            "Thorunn", "Torvald", "Ulf", "Valdis", "Vigdis", "Yngvar",
+        ],
+        syllables: [
+            &["Arn", "As", "Bjor", "Bryn", "Eg", "Gun", "Hal", "Ing", "Ra", "Sig", "Stei", "Thor", "Ul", "Yng"],
+            &["a", "ar", "e", "gn", "i", "ol", "un", "vi"],
+            &["bjorn", "dis", "finn", "frid", "geir", "hild", "nar", "rid", "run", "urd", "vald", "var"],
+        ],
+    },
+    NameTheme {
+        id: "classic",
+        corpus: &[
+            "Aiden", "Bella", "Connor", "Diana", "Ethan", "Fiona", "Gabriel", "Hannah", "Isaac",
+            "Jade", "Kyle", "Luna", "Mason", "Nova", "Oscar", "Piper", "Ashford", "Blake", "Cross",
+            "Drake", "Evans", "Gray", "Hunt", "Kane", "Lane", "Moore", "Nash", "Pierce", "Quinn",
+            "Reed", "Stone",
+        ],
+        syllables: [
+            &["Ai", "Bel", "Con", "Di", "E", "Fi", "Ga", "Han", "I", "Ja", "Lu", "Ma", "No", "Pi"],
+            &["a", "be", "e", "la", "ne", "ni", "o", "ri"],
+            &["an", "den", "el", "la", "na", "nah", "nor", "per", "son", "va"],
+        ],
+    },
+];
+
+#[cfg(feature = "random")]
+pub fn find_name_theme(id: &str) -> Result<&'static NameTheme, String> {
+    NAME_THEMES
+        .iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| format!("Unknown name theme: {}", id))
+}
+
+/// Upper-cases the first letter of each word in a generated name.
+#[cfg(feature = "random")]
+fn capitalize(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut at_word_start = true;
+    for c in name.chars() {
+        if at_word_start {
+            result.extend(c.to_uppercase());
+        } else {
+            result.push(c);
+        }
+        at_word_start = matches!(c, ' ' | '-' | '\'');
+    }
+    result
+}
+
+/// Order-N character Markov chain trained on a list of names.
+#[cfg(feature = "random")]
+pub struct MarkovNameModel {
+    order: usize,
+    transitions: HashMap<String, Vec<(char, u32)>>,
+    training: HashSet<String>,
+}
+
+#[cfg(feature = "random")]
+impl MarkovNameModel {
+    pub fn train(corpus: &[&str], order: usize) -> Result<Self, String> {
+        if order == 0 || order > MAX_MARKOV_ORDER {
+            return Err(format!("Markov order must be between 1 and {}", MAX_MARKOV_ORDER));
+        }
+
+        let mut counts: HashMap<String, HashMap<char, u32>> = HashMap::new();
+        let mut training = HashSet::new();
+
+        for name in corpus {
+            let name = name.trim().to_lowercase();
+            if name.is_empty() {
+                continue;
+            }
+            if name.contains([START, END]) {
+                return Err(format!("Names can't contain '{}' or '{}': {}", START, END, name));
+            }
+            let padded: Vec<char> = std::iter::repeat_n(START, order)
+                .chain(name.chars())
+                .chain(std::iter::once(END))
+                .collect();
+            for window in padded.windows(order + 1) {
+                let context: String = window[..order].iter().collect();
+                *counts.entry(context).or_default().entry(window[order]).or_default() += 1;
+            }
+            training.insert(name);
+        }
+
+        if training.is_empty() {
+            return Err("The training corpus contains no names".to_string());
+        }
+
+        let transitions = counts
+            .into_iter()
+            .map(|(context, next)| {
+                let mut next: Vec<(char, u32)> = next.into_iter().collect();
+                // Sort so that seeded generation does not depend on hash order.
+                next.sort_unstable();
+                (context, next)
+            })
+            .collect();
+
+        Ok(MarkovNameModel { order, transitions, training })
+    }
+
+    /// Walks the chain once; returns `None` if the name outgrows `max_length`.
+    fn sample<R: Rng + ?Sized>(&self, max_length: usize, rng: &mut R) -> Option<String> {
+        let mut context: Vec<char> = vec![START; self.order];
+        let mut name = String::new();
+
+        loop {
+            let key: String = context.iter().collect();
+            let next = self.transitions.get(&key)?;
+            let total: u32 = next.iter().map(|(_, count)| count).sum();
+            let mut pick = rng.gen_range(0..total);
+            let &(c, _) = next
+                .iter()
+                .find(|(_, count)| {
+                    if pick < *count {
+                        true
+                    } else {
+                        pick -= count;
+                        false
+                    }
+                })
+                .unwrap();
+
+            if c == END {
+                return Some(name);
+            }
+            name.push(c);
+            if name.chars().count() > max_length {
+                return None;
+            }
+            context.remove(0);
+            context.push(c);
+        }
+    }
+
+    /// Generates up to `count` distinct names with `min_length..=max_length`
+    /// characters, optionally skipping names found in the training data.
+    pub fn generate<R: Rng + ?Sized>(
+        &self,
+        count: usize,
+        min_length: usize,
+        max_length: usize,
+        reject_training_names: bool,
+        rng: &mut R,
+    ) -> Result<Vec<String>, String> {
+        check_count(count)?;
+        if min_length > max_length || max_length > MAX_NAME_LENGTH {
+            return Err(format!("Name lengths must satisfy min <= max <= {}", MAX_NAME_LENGTH));
+        }
+        let attempts = count.checked_mul(ATTEMPTS_PER_NAME).ok_or("Too many names requested")?;
+        let mut names: Vec<String> = Vec::with_capacity(count);
+        let mut seen = HashSet::new();
+
+        for _ in 0..attempts {
+            if names.len() == count {
+                break;
+            }
+            let Some(name) = self.sample(max_length, rng) else {
+                continue;
+            };
+            if name.chars().count() < min_length
+                || (reject_training_names && self.training.contains(&name))
+                || !seen.insert(name.clone())
+            {
+                continue;
+            }
+            names.push(capitalize(&name));
+        }
+        Ok(names)
+    }
+}
+
+/// Builds names by joining a start, zero or more middle, and an end syllable.
+#[cfg(feature = "random")]
+pub fn generate_syllable_names<R: Rng + ?Sized>(
+    theme: &NameTheme,
+    count: usize,
+    max_middle: usize,
+    rng: &mut R,
+) -> Result<Vec<String>, String> {
+    check_count(count)?;
+    if max_middle > MAX_MIDDLE_SYLLABLES {
+        return Err(format!("Use at most {} middle syllables", MAX_MIDDLE_SYLLABLES));
+    }
+    let [starts, middles, ends] = theme.syllables;
+    Ok((0..count)
+        .map(|_| {
+            let mut name = starts.choose(rng).unwrap().to_string();
+            for _ in 0..rng.gen_range(0..=max_middle) {
+                name.push_str(middles.choose(rng).unwrap());
+            }
+            name.push_str(ends.choose(rng).unwrap());
+            name
+        })
+        .collect())
+}
+
+#[cfg(feature = "random")]
+fn markov_names_js(
+    corpus: &[&str],
+    count: u32,
+    order: u32,
+    min_length: u32,
+    max_length: u32,
+    reject_training_names: bool,
+    seed: Option<u32>,
+) -> Result<JsValue, JsValue> {
+    let model = MarkovNameModel::train(corpus, order as usize).map_err(|e| JsValue::from_str(&e))?;
+    let mut rng = seeded_rng(seed);
+    let names = model
+        .generate(count as usize, min_length as usize, max_length as usize, reject_training_names, &mut rng)
+        .map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&names).unwrap())
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn generate_markov_names(
+    theme: &str,
+    count: u32,
+    order: u32,
+    min_length: u32,
+    max_length: u32,
+    reject_training_names: bool,
+    seed: Option<u32>,
+) -> Result<JsValue, JsValue> {
+    let theme = find_name_theme(theme).map_err(|e| JsValue::from_str(&e))?;
+    markov_names_js(theme.corpus, count, order, min_length, max_length, reject_training_names, seed)
+}
+
+/// Like `generate_markov_names`, but trained on a user corpus of names
+/// separated by commas or newlines.
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn generate_markov_names_from_corpus(
+    corpus: &str,
+    count: u32,
+    order: u32,
+    min_length: u32,
+    max_length: u32,
+    reject_training_names: bool,
+    seed: Option<u32>,
+) -> Result<JsValue, JsValue> {
+    let corpus: Vec<&str> = corpus.split([',', '\n']).collect();
+    markov_names_js(&corpus, count, order, min_length, max_length, reject_training_names, seed)
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn generate_syllable_name_list(theme: &str, count: u32, max_middle: u32, seed: Option<u32>) -> Result<JsValue, JsValue> {
+    let theme = find_name_theme(theme).map_err(|e| JsValue::from_str(&e))?;
+    let mut rng = seeded_rng(seed);
+    let names =
+        generate_syllable_names(theme, count as usize, max_middle as usize, &mut rng).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&names).unwrap())
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn name_themes() -> JsValue {
+    let ids: Vec<&str> = NAME_THEMES.iter().map(|t| t.id).collect();
+    serde_wasm_bindgen::to_value(&ids).unwrap()
+}
@@ -0,0 +1,636 @@
+#[cfg(feature = "random")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "random")]
+use wasm_bindgen::{Clamped, JsCast};
+#[cfg(feature = "random")]
+use rand::prelude::*;
+#[cfg(feature = "random")]
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, ImageData};
+
+// Hashing constants from the OpenSimplex2 reference implementation. Lattice
+// coordinates are multiplied by a per-axis prime and mixed with the seed.
+#[cfg(feature = "random")]
+const PRIME_X: i64 = 0x5205_402B_9270_C86F;
+#[cfg(feature = "random")]
+const PRIME_Y: i64 = 0x598C_D327_0038_17B5;
+#[cfg(feature = "random")]
+const PRIME_Z: i64 = 0x5BCC_226E_9FA0_BACB;
+#[cfg(feature = "random")]
+const HASH_MULTIPLIER: i64 = 0x53A3_F72D_EEC5_46F5;
+/// Seed used for the second (half-offset) 3D lattice.
+#[cfg(feature = "random")]
+const SEED_FLIP_3D: i64 = -0x52D5_47B2_E96E_D629;
+
+// Skew and unskew factors for the 2D simplex lattice.
+#[cfg(feature = "random")]
+const SKEW_2D: f64 = 0.366_025_403_784_438_6;
+#[cfg(feature = "random")]
+const UNSKEW_2D: f64 = 0.211_324_865_405_187_1;
+
+/// Rotation that maps the cubic grid's main diagonal onto the z axis, used
+/// to sample the 3D lattice without directional artifacts in any plane.
+#[cfg(feature = "random")]
+const FALLBACK_ROTATE_3D: f64 = 2.0 / 3.0;
+
+// Squared kernel radii and the normalizers that bring the sums into [-1, 1].
+#[cfg(feature = "random")]
+const RSQUARED_2D: f64 = 0.5;
+#[cfg(feature = "random")]
+const RSQUARED_3D: f64 = 0.6;
+#[cfg(feature = "random")]
+const NORMALIZER_2D: f64 = 0.010_016_341_213_657_12;
+#[cfg(feature = "random")]
+const NORMALIZER_3D: f64 = 0.079_698_376_689_353_31;
+
+/// The 24 OpenSimplex2 gradient directions in 2D, 15 degrees apart.
+#[cfg(feature = "random")]
+const GRADIENTS_2D: [(f64, f64); 24] = [
+    (0.382_683_432_365_09, 0.923_879_532_511_287),
+    (0.923_879_532_511_287, 0.382_683_432_365_09),
+    (0.923_879_532_511_287, -0.382_683_432_365_09),
+    (0.382_683_432_365_09, -0.923_879_532_511_287),
+    (-0.382_683_432_365_09, -0.923_879_532_511_287),
+    (-0.923_879_532_511_287, -0.382_683_432_365_09),
+    (-0.923_879_532_511_287, 0.382_683_432_365_09),
+    (-0.382_683_432_365_09, 0.923_879_532_511_287),
+    (0.130_526_192_220_052, 0.991_444_861_373_81),
+    (0.608_761_429_008_721, 0.793_353_340_291_235),
+    (0.793_353_340_291_235, 0.608_761_429_008_721),
+    (0.991_444_861_373_81, 0.130_526_192_220_052),
+    (0.991_444_861_373_81, -0.130_526_192_220_052),
+    (0.793_353_340_291_235, -0.608_761_429_008_721),
+    (0.608_761_429_008_721, -0.793_353_340_291_235),
+    (0.130_526_192_220_052, -0.991_444_861_373_81),
+    (-0.130_526_192_220_052, -0.991_444_861_373_81),
+    (-0.608_761_429_008_721, -0.793_353_340_291_235),
+    (-0.793_353_340_291_235, -0.608_761_429_008_721),
+    (-0.991_444_861_373_81, -0.130_526_192_220_052),
+    (-0.991_444_861_373_81, 0.130_526_192_220_052),
+    (-0.793_353_340_291_235, 0.608_761_429_008_721),
+    (-0.608_761_429_008_721, 0.793_353_340_291_235),
+    (-0.130_526_192_220_052, 0.991_444_861_373_81),
+];
+
+// Components of the 3D gradients: every vector is a signed permutation of
+// (A, A, 1) or (B, C, 0), all of the same length.
+#[cfg(feature = "random")]
+const GRAD_A: f64 = 2.224_744_871_39;
+#[cfg(feature = "random")]
+const GRAD_B: f64 = 3.086_266_468_797_201_7;
+#[cfg(feature = "random")]
+const GRAD_C: f64 = 1.172_151_342_246_497_8;
+
+/// The 48 OpenSimplex2 gradient directions in 3D.
+#[cfg(feature = "random")]
+const GRADIENTS_3D: [(f64, f64, f64); 48] = [
+    (GRAD_A, GRAD_A, -1.0),
+    (GRAD_A, GRAD_A, 1.0),
+    (GRAD_B, GRAD_C, 0.0),
+    (GRAD_C, GRAD_B, 0.0),
+    (-GRAD_A, GRAD_A, -1.0),
+    (-GRAD_A, GRAD_A, 1.0),
+    (-GRAD_C, GRAD_B, 0.0),
+    (-GRAD_B, GRAD_C, 0.0),
+    (-1.0, -GRAD_A, -GRAD_A),
+    (1.0, -GRAD_A, -GRAD_A),
+    (0.0, -GRAD_B, -GRAD_C),
+    (0.0, -GRAD_C, -GRAD_B),
+    (-1.0, -GRAD_A, GRAD_A),
+    (1.0, -GRAD_A, GRAD_A),
+    (0.0, -GRAD_C, GRAD_B),
+    (0.0, -GRAD_B, GRAD_C),
+    (-GRAD_A, -GRAD_A, -1.0),
+    (-GRAD_A, -GRAD_A, 1.0),
+    (-GRAD_B, -GRAD_C, 0.0),
+    (-GRAD_C, -GRAD_B, 0.0),
+    (-GRAD_A, -1.0, -GRAD_A),
+    (-GRAD_A, 1.0, -GRAD_A),
+    (-GRAD_C, 0.0, -GRAD_B),
+    (-GRAD_B, 0.0, -GRAD_C),
+    (-GRAD_A, -1.0, GRAD_A),
+    (-GRAD_A, 1.0, GRAD_A),
+    (-GRAD_B, 0.0, GRAD_C),
+    (-GRAD_C, 0.0, GRAD_B),
+    (-1.0, GRAD_A, -GRAD_A),
+    (1.0, GRAD_A, -GRAD_A),
+    (0.0, GRAD_C, -GRAD_B),
+    (0.0, GRAD_B, -GRAD_C),
+    (-1.0, GRAD_A, GRAD_A),
+    (1.0, GRAD_A, GRAD_A),
+    (0.0, GRAD_B, GRAD_C),
+    (0.0, GRAD_C, GRAD_B),
+    (GRAD_A, -GRAD_A, -1.0),
+    (GRAD_A, -GRAD_A, 1.0),
+    (GRAD_C, -GRAD_B, 0.0),
+    (GRAD_B, -GRAD_C, 0.0),
+    (GRAD_A, -1.0, -GRAD_A),
+    (GRAD_A, 1.0, -GRAD_A),
+    (GRAD_B, 0.0, -GRAD_C),
+    (GRAD_C, 0.0, -GRAD_B),
+    (GRAD_A, -1.0, GRAD_A),
+    (GRAD_A, 1.0, GRAD_A),
+    (GRAD_C, 0.0, GRAD_B),
+    (GRAD_B, 0.0, GRAD_C),
+];
+
+#[cfg(feature = "random")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoiseAlgorithm {
+    Perlin,
+    OpenSimplex2,
+}
+
+/// Seeded gradient noise source shared by both algorithms.
+#[cfg(feature = "random")]
+#[derive(Clone)]
+pub struct NoiseSource {
+    algorithm: NoiseAlgorithm,
+    seed: i64,
+    perm: [u8; 512],
+}
+
+#[cfg(feature = "random")]
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+#[cfg(feature = "random")]
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + t * (b - a)
+}
+
+/// Dot product of the hashed 2D gradient at a lattice vertex with (dx, dy).
+/// `xsvp` and `ysvp` are the vertex coordinates premultiplied by their primes.
+#[cfg(feature = "random")]
+fn grad2(seed: i64, xsvp: i64, ysvp: i64, dx: f64, dy: f64) -> f64 {
+    let mut hash = (seed ^ xsvp ^ ysvp).wrapping_mul(HASH_MULTIPLIER);
+    hash ^= hash >> 58;
+    let (gx, gy) = GRADIENTS_2D[((hash & 0xFE) >> 1) as usize % 24];
+    gx * dx + gy * dy
+}
+
+/// Dot product of the hashed 3D gradient at a lattice vertex with (dx, dy, dz).
+#[cfg(feature = "random")]
+fn grad3(seed: i64, xrvp: i64, yrvp: i64, zrvp: i64, dx: f64, dy: f64, dz: f64) -> f64 {
+    let mut hash = ((seed ^ xrvp) ^ (yrvp ^ zrvp)).wrapping_mul(HASH_MULTIPLIER);
+    hash ^= hash >> 58;
+    let (gx, gy, gz) = GRADIENTS_3D[((hash & 0x3FC) >> 2) as usize % 48];
+    gx * dx + gy * dy + gz * dz
+}
+
+#[cfg(feature = "random")]
+impl NoiseSource {
+    pub fn new(algorithm: NoiseAlgorithm, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut table: Vec<u8> = (0..=255).collect();
+        table.shuffle(&mut rng);
+        let mut perm = [0u8; 512];
+        for i in 0..512 {
+            perm[i] = table[i & 255];
+        }
+
+        NoiseSource { algorithm, seed: seed as i64, perm }
+    }
+
+    fn hash2(&self, x: i64, y: i64) -> usize {
+        self.perm[(self.perm[(x & 255) as usize] as usize + (y & 255) as usize) & 511] as usize
+    }
+
+    fn hash3(&self, x: i64, y: i64, z: i64) -> usize {
+        self.perm[(self.hash2(x, y) + (z & 255) as usize) & 511] as usize
+    }
+
+    pub fn noise2(&self, x: f64, y: f64) -> f64 {
+        match self.algorithm {
+            NoiseAlgorithm::Perlin => self.perlin2(x, y),
+            NoiseAlgorithm::OpenSimplex2 => (self.open_simplex2(x, y) / NORMALIZER_2D).clamp(-1.0, 1.0),
+        }
+    }
+
+    pub fn noise3(&self, x: f64, y: f64, z: f64) -> f64 {
+        match self.algorithm {
+            NoiseAlgorithm::Perlin => self.perlin3(x, y, z),
+            NoiseAlgorithm::OpenSimplex2 => (self.open_simplex3(x, y, z) / NORMALIZER_3D).clamp(-1.0, 1.0),
+        }
+    }
+
+    fn perlin_grad2(hash: usize, x: f64, y: f64) -> f64 {
+        match hash & 7 {
+            0 => x + y,
+            1 => -x + y,
+            2 => x - y,
+            3 => -x - y,
+            4 => x,
+            5 => -x,
+            6 => y,
+            _ => -y,
+        }
+    }
+
+    fn perlin_grad3(hash: usize, x: f64, y: f64, z: f64) -> f64 {
+        let h = hash & 15;
+        let u = if h < 8 { x } else { y };
+        let v = if h < 4 { y } else if h == 12 || h == 14 { x } else { z };
+        (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+    }
+
+    /// Improved Perlin noise (2002) in 2D.
+    fn perlin2(&self, x: f64, y: f64) -> f64 {
+        let (xf, yf) = (x.floor(), y.floor());
+        let (xi, yi) = (xf as i64, yf as i64);
+        let (x, y) = (x - xf, y - yf);
+        let (u, v) = (fade(x), fade(y));
+
+        let g = |dx: i64, dy: i64| Self::perlin_grad2(self.hash2(xi + dx, yi + dy), x - dx as f64, y - dy as f64);
+        lerp(lerp(g(0, 0), g(1, 0), u), lerp(g(0, 1), g(1, 1), u), v)
+    }
+
+    /// Improved Perlin noise (2002) in 3D.
+    fn perlin3(&self, x: f64, y: f64, z: f64) -> f64 {
+        let (xf, yf, zf) = (x.floor(), y.floor(), z.floor());
+        let (xi, yi, zi) = (xf as i64, yf as i64, zf as i64);
+        let (x, y, z) = (x - xf, y - yf, z - zf);
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        let g = |dx: i64, dy: i64, dz: i64| {
+            Self::perlin_grad3(self.hash3(xi + dx, yi + dy, zi + dz), x - dx as f64, y - dy as f64, z - dz as f64)
+        };
+        let value = lerp(
+            lerp(lerp(g(0, 0, 0), g(1, 0, 0), u), lerp(g(0, 1, 0), g(1, 1, 0), u), v),
+            lerp(lerp(g(0, 0, 1), g(1, 0, 1), u), lerp(g(0, 1, 1), g(1, 1, 1), u), v),
+            w,
+        );
+        value.clamp(-1.0, 1.0)
+    }
+
+    /// OpenSimplex2 in 2D, before normalization: the skewed triangle
+    /// lattice, where each point sees the three vertices of its triangle.
+    fn open_simplex2(&self, x: f64, y: f64) -> f64 {
+        let s = SKEW_2D * (x + y);
+        let (xs, ys) = (x + s, y + s);
+        let (xsb, ysb) = (xs.floor(), ys.floor());
+        let (xi, yi) = (xs - xsb, ys - ysb);
+        let xsbp = (xsb as i64).wrapping_mul(PRIME_X);
+        let ysbp = (ysb as i64).wrapping_mul(PRIME_Y);
+
+        let t = (xi + yi) * UNSKEW_2D;
+        let (dx0, dy0) = (xi - t, yi - t);
+        let contribution = |xsvp: i64, ysvp: i64, dx: f64, dy: f64| {
+            let a = RSQUARED_2D - dx * dx - dy * dy;
+            if a > 0.0 { a.powi(4) * grad2(self.seed, xsvp, ysvp, dx, dy) } else { 0.0 }
+        };
+
+        let mut value = contribution(xsbp, ysbp, dx0, dy0);
+        let diagonal = 1.0 - 2.0 * UNSKEW_2D;
+        value += contribution(
+            xsbp.wrapping_add(PRIME_X),
+            ysbp.wrapping_add(PRIME_Y),
+            dx0 - diagonal,
+            dy0 - diagonal,
+        );
+        value += if dy0 > dx0 {
+            contribution(xsbp, ysbp.wrapping_add(PRIME_Y), dx0 + UNSKEW_2D, dy0 - (1.0 - UNSKEW_2D))
+        } else {
+            contribution(xsbp.wrapping_add(PRIME_X), ysbp, dx0 - (1.0 - UNSKEW_2D), dy0 + UNSKEW_2D)
+        };
+        value
+    }
+
+    /// OpenSimplex2 in 3D, before normalization, with the fallback
+    /// orientation of the lattice.
+    fn open_simplex3(&self, x: f64, y: f64, z: f64) -> f64 {
+        let r = FALLBACK_ROTATE_3D * (x + y + z);
+        self.open_simplex3_rotated(r - x, r - y, r - z)
+    }
+
+    /// Sums the kernels of a rotated body-centred cubic lattice: the integer
+    /// lattice plus a copy offset by half a cell with a flipped seed. Each
+    /// half visits its closest vertex and, when it is in range, the next
+    /// closest one along the dominant axis. As in the reference, a neighbour
+    /// along another axis can fall just inside the r² = 0.6 kernel and is
+    /// skipped; its weight stays within a few thousandths of the output range.
+    fn open_simplex3_rotated(&self, xr: f64, yr: f64, zr: f64) -> f64 {
+        let (xrb, yrb, zrb) = (xr.round(), yr.round(), zr.round());
+        let (mut xri, mut yri, mut zri) = (xr - xrb, yr - yrb, zr - zrb);
+
+        // Direction from the point back towards the far side of its cell.
+        let sign = |d: f64| if d >= 0.0 { -1i64 } else { 1 };
+        let (mut x_sign, mut y_sign, mut z_sign) = (sign(xri), sign(yri), sign(zri));
+        let (mut ax0, mut ay0, mut az0) = (xri.abs(), yri.abs(), zri.abs());
+
+        let mut xrbp = (xrb as i64).wrapping_mul(PRIME_X);
+        let mut yrbp = (yrb as i64).wrapping_mul(PRIME_Y);
+        let mut zrbp = (zrb as i64).wrapping_mul(PRIME_Z);
+        let mut seed = self.seed;
+
+        let mut value = 0.0;
+        let mut a = RSQUARED_3D - xri * xri - yri * yri - zri * zri;
+        for lattice in 0..2 {
+            if a > 0.0 {
+                value += a.powi(4) * grad3(seed, xrbp, yrbp, zrbp, xri, yri, zri);
+            }
+
+            // The second vertex's falloff, rewritten in terms of the first's.
+            if ax0 >= ay0 && ax0 >= az0 {
+                let b = a + ax0 + ax0 - 1.0;
+                if b > 0.0 {
+                    let xvp = xrbp.wrapping_sub(x_sign.wrapping_mul(PRIME_X));
+                    value += b.powi(4) * grad3(seed, xvp, yrbp, zrbp, xri + x_sign as f64, yri, zri);
+                }
+            } else if ay0 > ax0 && ay0 >= az0 {
+                let b = a + ay0 + ay0 - 1.0;
+                if b > 0.0 {
+                    let yvp = yrbp.wrapping_sub(y_sign.wrapping_mul(PRIME_Y));
+                    value += b.powi(4) * grad3(seed, xrbp, yvp, zrbp, xri, yri + y_sign as f64, zri);
+                }
+            } else {
+                let b = a + az0 + az0 - 1.0;
+                if b > 0.0 {
+                    let zvp = zrbp.wrapping_sub(z_sign.wrapping_mul(PRIME_Z));
+                    value += b.powi(4) * grad3(seed, xrbp, yrbp, zvp, xri, yri, zri + z_sign as f64);
+                }
+            }
+
+            if lattice == 0 {
+                // Step to the closest vertex of the half-offset lattice.
+                (ax0, ay0, az0) = (0.5 - ax0, 0.5 - ay0, 0.5 - az0);
+                (xri, yri, zri) = (x_sign as f64 * ax0, y_sign as f64 * ay0, z_sign as f64 * az0);
+                a += (0.75 - ax0) - (ay0 + az0);
+                if x_sign < 0 {
+                    xrbp = xrbp.wrapping_add(PRIME_X);
+                }
+                if y_sign < 0 {
+                    yrbp = yrbp.wrapping_add(PRIME_Y);
+                }
+                if z_sign < 0 {
+                    zrbp = zrbp.wrapping_add(PRIME_Z);
+                }
+                (x_sign, y_sign, z_sign) = (-x_sign, -y_sign, -z_sign);
+                seed ^= SEED_FLIP_3D;
+            }
+        }
+        value
+    }
+}
+
+/// Fractal Brownian motion settings: `octaves` layers of noise, each
+/// `lacunarity` times the frequency and `gain` times the amplitude of the last.
+#[cfg(feature = "random")]
+#[derive(Clone, Copy, Debug)]
+pub struct FractalSettings {
+    pub frequency: f64,
+    pub octaves: u32,
+    pub lacunarity: f64,
+    pub gain: f64,
+}
+
+#[cfg(feature = "random")]
+impl Default for FractalSettings {
+    fn default() -> Self {
+        FractalSettings { frequency: 1.0 / 64.0, octaves: 5, lacunarity: 2.0, gain: 0.5 }
+    }
+}
+
+/// Sums octaves of noise and normalizes the result back to [-1, 1].
+#[cfg(feature = "random")]
+pub fn fbm(source: &NoiseSource, settings: &FractalSettings, x: f64, y: f64, z: Option<f64>) -> f64 {
+    let mut frequency = settings.frequency;
+    let mut amplitude = 1.0;
+    let mut total = 0.0;
+    let mut max_amplitude = 0.0;
+
+    for octave in 0..settings.octaves.max(1) {
+        // Shift each octave so their lattices do not line up at the origin.
+        let shift = octave as f64 * 19.19;
+        let sample = match z {
+            Some(z) => source.noise3(x * frequency + shift, y * frequency + shift, z * frequency + shift),
+            None => source.noise2(x * frequency + shift, y * frequency + shift),
+        };
+        total += sample * amplitude;
+        max_amplitude += amplitude;
+        amplitude *= settings.gain;
+        frequency *= settings.lacunarity;
+    }
+    total / max_amplitude
+}
+
+/// Samples a `width` x `height` heightmap in row-major order with values in [0, 1].
+#[cfg(feature = "random")]
+pub fn generate_heightmap(
+    source: &NoiseSource,
+    settings: &FractalSettings,
+    width: usize,
+    height: usize,
+    z: Option<f64>,
+) -> Vec<f32> {
+    let mut data = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            let value = fbm(source, settings, x as f64, y as f64, z);
+            data.push(((value + 1.0) / 2.0).clamp(0.0, 1.0) as f32);
+        }
+    }
+    data
+}
+
+/// Maps a height in [0, 1] to an RGB color.
+#[cfg(feature = "random")]
+pub fn colormap(name: &str, height: f32) -> Result<[u8; 3], String> {
+    let h = height.clamp(0.0, 1.0);
+    let mix = |a: [u8; 3], b: [u8; 3], t: f32| -> [u8; 3] {
+        std::array::from_fn(|i| (a[i] as f32 + (b[i] as f32 - a[i] as f32) * t.clamp(0.0, 1.0)) as u8)
+    };
+
+    Ok(match name {
+        "grayscale" => {
+            let v = (h * 255.0) as u8;
+            [v, v, v]
+        }
+        "heat" => {
+            if h < 0.5 {
+                mix([0, 0, 0], [220, 40, 0], h * 2.0)
+            } else {
+                mix([220, 40, 0], [255, 240, 120], (h - 0.5) * 2.0)
+            }
+        }
+        "terrain" => {
+            // (upper bound, low color, high color) for each band.
+            const BANDS: [(f32, [u8; 3], [u8; 3]); 6] = [
+                (0.35, [10, 30, 90], [30, 80, 170]),
+                (0.42, [30, 80, 170], [70, 150, 210]),
+                (0.46, [210, 200, 140], [230, 215, 160]),
+                (0.65, [70, 150, 60], [40, 100, 40]),
+                (0.82, [110, 95, 80], [140, 130, 120]),
+                (1.0, [220, 220, 225], [255, 255, 255]),
+            ];
+            let mut lower = 0.0;
+            let mut color = BANDS[5].2;
+            for (upper, low, high) in BANDS {
+                if h <= upper {
+                    color = mix(low, high, (h - lower) / (upper - lower));
+                    break;
+                }
+                lower = upper;
+            }
+            color
+        }
+        other => return Err(format!("Unknown colormap: {}", other)),
+    })
+}
+
+/// Converts a heightmap into RGBA pixels for `ImageData`.
+#[cfg(feature = "random")]
+pub fn heightmap_to_rgba(heights: &[f32], colormap_name: &str) -> Result<Vec<u8>, String> {
+    let mut pixels = Vec::with_capacity(heights.len() * 4);
+    for &h in heights {
+        let [r, g, b] = colormap(colormap_name, h)?;
+        pixels.extend_from_slice(&[r, g, b, 255]);
+    }
+    Ok(pixels)
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub struct NoiseField {
+    source: NoiseSource,
+    settings: FractalSettings,
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+impl NoiseField {
+    /// `algorithm` is "perlin" or "opensimplex2".
+    #[wasm_bindgen(constructor)]
+    pub fn new(algorithm: &str, seed: u32) -> Result<NoiseField, JsValue> {
+        let algorithm = match algorithm {
+            "perlin" => NoiseAlgorithm::Perlin,
+            "opensimplex2" => NoiseAlgorithm::OpenSimplex2,
+            other => return Err(JsValue::from_str(&format!("Unknown noise algorithm: {}", other))),
+        };
+        Ok(NoiseField { source: NoiseSource::new(algorithm, seed as u64), settings: FractalSettings::default() })
+    }
+
+    /// Sets the base frequency (in cycles per pixel) and fBm parameters.
+    pub fn set_fractal(&mut self, frequency: f64, octaves: u32, lacunarity: f64, gain: f64) {
+        self.settings = FractalSettings { frequency, octaves, lacunarity, gain };
+    }
+
+    pub fn sample2d(&self, x: f64, y: f64) -> f64 {
+        fbm(&self.source, &self.settings, x, y, None)
+    }
+
+    pub fn sample3d(&self, x: f64, y: f64, z: f64) -> f64 {
+        fbm(&self.source, &self.settings, x, y, Some(z))
+    }
+
+    /// Raw heightmap in row-major order; pass `z` to take a slice of 3D noise.
+    pub fn heightmap(&self, width: u32, height: u32, z: Option<f64>) -> js_sys::Float32Array {
+        let data = generate_heightmap(&self.source, &self.settings, width as usize, height as usize, z);
+        js_sys::Float32Array::from(&data[..])
+    }
+
+    /// Fills the canvas with a colored heightmap at the canvas's own size.
+    /// `colormap` is "terrain", "grayscale" or "heat".
+    pub fn render_to_canvas(&self, canvas: &HtmlCanvasElement, colormap: &str, z: Option<f64>) -> Result<(), JsValue> {
+        let (width, height) = (canvas.width(), canvas.height());
+        let heights = generate_heightmap(&self.source, &self.settings, width as usize, height as usize, z);
+        let pixels = heightmap_to_rgba(&heights, colormap).map_err(|e| JsValue::from_str(&e))?;
+
+        let context = canvas
+            .get_context("2d")?
+            .unwrap()
+            .dyn_into::<CanvasRenderingContext2d>()?;
+        let image = ImageData::new_with_u8_clamped_array_and_sh(Clamped(&pixels), width, height)?;
+        context.put_image_data(&image, 0.0, 0.0)
+    }
+}
+
+#[cfg(all(test, feature = "random"))]
+mod tests {
+    use super::*;
+
+    /// Sample points spread over positive and negative coordinates.
+    fn sample_points() -> Vec<(f64, f64, f64)> {
+        let mut rng = StdRng::seed_from_u64(3);
+        (0..2000).map(|_| (rng.gen_range(-50.0..50.0), rng.gen_range(-50.0..50.0), rng.gen_range(-50.0..50.0))).collect()
+    }
+
+    #[test]
+    fn gradient_sets_are_distinct_and_equal_length() {
+        for (i, a) in GRADIENTS_2D.iter().enumerate() {
+            assert!((a.0.hypot(a.1) - 1.0).abs() < 1e-12);
+            assert!(GRADIENTS_2D[i + 1..].iter().all(|b| (a.0 - b.0).abs() + (a.1 - b.1).abs() > 1e-6));
+        }
+        let length = |g: &(f64, f64, f64)| (g.0 * g.0 + g.1 * g.1 + g.2 * g.2).sqrt();
+        for (i, a) in GRADIENTS_3D.iter().enumerate() {
+            assert!((length(a) - length(&GRADIENTS_3D[0])).abs() < 1e-9);
+            assert!(GRADIENTS_3D[i + 1..].iter().all(|b| (a.0 - b.0).abs() + (a.1 - b.1).abs() + (a.2 - b.2).abs() > 1e-6));
+        }
+    }
+
+    #[test]
+    fn open_simplex2_matches_brute_force_lattice_sum() {
+        let source = NoiseSource::new(NoiseAlgorithm::OpenSimplex2, 42);
+        for (x, y, _) in sample_points() {
+            let s = SKEW_2D * (x + y);
+            let (xs, ys) = (x + s, y + s);
+            let mut expected = 0.0;
+            for i in xs.floor() as i64 - 2..=xs.floor() as i64 + 2 {
+                for j in ys.floor() as i64 - 2..=ys.floor() as i64 + 2 {
+                    let (sx, sy) = (xs - i as f64, ys - j as f64);
+                    let t = (sx + sy) * UNSKEW_2D;
+                    let (dx, dy) = (sx - t, sy - t);
+                    let a = RSQUARED_2D - dx * dx - dy * dy;
+                    if a > 0.0 {
+                        let (xp, yp) = (i.wrapping_mul(PRIME_X), j.wrapping_mul(PRIME_Y));
+                        expected += a.powi(4) * grad2(source.seed, xp, yp, dx, dy);
+                    }
+                }
+            }
+            assert!((source.open_simplex2(x, y) - expected).abs() < 1e-12, "({}, {})", x, y);
+        }
+    }
+
+    /// OpenSimplex2 skips the rare second-shell vertex off the dominant axis
+    /// (see `open_simplex3_rotated`), so the sums agree to within its weight.
+    #[test]
+    fn open_simplex3_matches_brute_force_lattice_sum() {
+        let source = NoiseSource::new(NoiseAlgorithm::OpenSimplex2, 42);
+        for (x, y, z) in sample_points() {
+            let mut expected = 0.0;
+            // Vertex k of the offset lattice sits at k - 0.5 and hashes as k.
+            for (offset, seed) in [(0.0, source.seed), (0.5, source.seed ^ SEED_FLIP_3D)] {
+                let (xr, yr, zr) = (x + offset, y + offset, z + offset);
+                for i in xr.round() as i64 - 1..=xr.round() as i64 + 1 {
+                    for j in yr.round() as i64 - 1..=yr.round() as i64 + 1 {
+                        for k in zr.round() as i64 - 1..=zr.round() as i64 + 1 {
+                            let (dx, dy, dz) = (xr - i as f64, yr - j as f64, zr - k as f64);
+                            let a = RSQUARED_3D - dx * dx - dy * dy - dz * dz;
+                            if a > 0.0 {
+                                let (xp, yp, zp) = (i.wrapping_mul(PRIME_X), j.wrapping_mul(PRIME_Y), k.wrapping_mul(PRIME_Z));
+                                expected += a.powi(4) * grad3(seed, xp, yp, zp, dx, dy, dz);
+                            }
+                        }
+                    }
+                }
+            }
+            let difference = (source.open_simplex3_rotated(x, y, z) - expected) / NORMALIZER_3D;
+            assert!(difference.abs() < 5e-3, "({}, {}, {}): off by {}", x, y, z, difference);
+        }
+    }
+
+    #[test]
+    fn noise_is_bounded_and_seed_dependent() {
+        let a = NoiseSource::new(NoiseAlgorithm::OpenSimplex2, 1);
+        let b = NoiseSource::new(NoiseAlgorithm::OpenSimplex2, 2);
+        let mut differs = false;
+        for (x, y, z) in sample_points() {
+            for value in [a.noise2(x, y), a.noise3(x, y, z)] {
+                assert!((-1.0..=1.0).contains(&value));
+            }
+            differs |= a.noise2(x, y) != b.noise2(x, y);
+        }
+        assert!(differs);
+        assert_eq!(a.noise2(0.0, 0.0), 0.0);
+    }
+}
@@ -0,0 +1,1121 @@
+#[cfg(feature = "math")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "math")]
+use serde::Serialize;
+#[cfg(feature = "math")]
+use std::cell::Cell;
+#[cfg(feature = "math")]
+use std::cmp::Ordering;
+#[cfg(feature = "math")]
+use std::collections::BinaryHeap;
+#[cfg(feature = "math")]
+use super::expression::{parse_error_to_js, parse_expression_source, Expr};
+#[cfg(feature = "math")]
+use super::symbolic::{differentiate, simplify};
+
+#[cfg(feature = "math")]
+pub const DEFAULT_TOLERANCE: f64 = 1e-10;
+#[cfg(feature = "math")]
+pub const DEFAULT_MAX_ITERATIONS: u32 = 100;
+
+/// Hard caps on root-finder iterations (and so on their history) and on
+/// Gauss–Kronrod subdivisions, whatever the caller asks for.
+#[cfg(feature = "math")]
+pub const MAX_ROOT_ITERATIONS: u32 = 10_000;
+#[cfg(feature = "math")]
+pub const MAX_SUBDIVISIONS: u32 = 10_000;
+
+/// Simpson's rule starts from this many intervals and doubles until the
+/// estimate settles, but never accepts fewer than `SIMPSON_MIN_INTERVALS`.
+#[cfg(feature = "math")]
+const SIMPSON_START_INTERVALS: usize = 2;
+#[cfg(feature = "math")]
+const SIMPSON_MIN_INTERVALS: usize = 16;
+/// At most 2^25 intervals, whatever the caller asks for.
+#[cfg(feature = "math")]
+const SIMPSON_MAX_DOUBLINGS: u32 = 24;
+
+// 15-point Kronrod rule and its embedded 7-point Gauss rule (QUADPACK qk15).
+// Nodes are on [-1, 1], listed from the outside in; the last is the centre.
+#[cfg(feature = "math")]
+const KRONROD_NODES: [f64; 8] = [
+    0.991_455_371_120_812_6,
+    0.949_107_912_342_758_5,
+    0.864_864_423_359_769_1,
+    0.741_531_185_599_394_4,
+    0.586_087_235_467_691_1,
+    0.405_845_151_377_397_2,
+    0.207_784_955_007_898_5,
+    0.0,
+];
+#[cfg(feature = "math")]
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022_935_322_010_529_22,
+    0.063_092_092_629_978_55,
+    0.104_790_010_322_250_2,
+    0.140_653_259_715_525_9,
+    0.169_004_726_639_267_9,
+    0.190_350_578_064_785_4,
+    0.204_432_940_075_298_9,
+    0.209_482_141_084_727_8,
+];
+/// Weights of the Gauss nodes, which are the odd-indexed Kronrod nodes.
+#[cfg(feature = "math")]
+const GAUSS_WEIGHTS: [f64; 4] = [
+    0.129_484_966_168_869_7,
+    0.279_705_391_489_276_7,
+    0.381_830_050_505_118_9,
+    0.417_959_183_673_469_4,
+];
+
+/// Wraps `f` so calls are counted.
+#[cfg(feature = "math")]
+struct Counted<'a> {
+    f: &'a dyn Fn(f64) -> f64,
+    calls: Cell<usize>,
+}
+
+#[cfg(feature = "math")]
+impl<'a> Counted<'a> {
+    fn new(f: &'a dyn Fn(f64) -> f64) -> Self {
+        Counted { f, calls: Cell::new(0) }
+    }
+
+    fn call(&self, x: f64) -> f64 {
+        self.calls.set(self.calls.get() + 1);
+        (self.f)(x)
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RootMethod {
+    Bisection,
+    Newton,
+    Brent,
+}
+
+#[cfg(feature = "math")]
+impl RootMethod {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "bisection" => Ok(RootMethod::Bisection),
+            "newton" => Ok(RootMethod::Newton),
+            "brent" => Ok(RootMethod::Brent),
+            _ => Err(format!("Unknown root-finding method: {}", name)),
+        }
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct RootIteration {
+    pub iteration: u32,
+    pub x: f64,
+    pub fx: f64,
+    /// Half the bracket width, or the last step size for Newton.
+    pub error: f64,
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct RootResult {
+    pub method: RootMethod,
+    pub root: f64,
+    /// f(root).
+    pub value: f64,
+    pub iterations: u32,
+    pub evaluations: usize,
+    pub error_estimate: f64,
+    pub converged: bool,
+    /// Why the method stopped early, when it did.
+    pub message: Option<String>,
+    pub history: Vec<RootIteration>,
+}
+
+#[cfg(feature = "math")]
+fn check_tolerance(tolerance: f64) -> Result<(), String> {
+    if tolerance.is_finite() && tolerance > 0.0 {
+        Ok(())
+    } else {
+        Err("The tolerance must be a positive number".to_string())
+    }
+}
+
+#[cfg(feature = "math")]
+fn check_bracket(a: f64, fa: f64, b: f64, fb: f64) -> Result<(), String> {
+    if !(a.is_finite() && b.is_finite() && a < b) {
+        return Err("The bracket must be finite with a < b".to_string());
+    }
+    if !(fa.is_finite() && fb.is_finite()) {
+        return Err("The function is undefined at an end of the bracket".to_string());
+    }
+    if fa * fb > 0.0 {
+        return Err(format!("f(a) = {} and f(b) = {} have the same sign, so [a, b] may not bracket a root", fa, fb));
+    }
+    Ok(())
+}
+
+/// Halves `[a, b]` until it is narrower than `2 * tolerance`. Needs a sign
+/// change across the bracket. Like the other root finders, it runs at most
+/// `MAX_ROOT_ITERATIONS` iterations.
+#[cfg(feature = "math")]
+pub fn bisection(f: &dyn Fn(f64) -> f64, a: f64, b: f64, tolerance: f64, max_iterations: u32) -> Result<RootResult, String> {
+    check_tolerance(tolerance)?;
+    let max_iterations = max_iterations.min(MAX_ROOT_ITERATIONS);
+    let f = Counted::new(f);
+    let (mut a, mut b) = (a, b);
+    let (mut fa, fb) = (f.call(a), f.call(b));
+    check_bracket(a, fa, b, fb)?;
+
+    let mut history = Vec::new();
+    let (mut mid, mut fmid) = if fa == 0.0 { (a, fa) } else { (b, fb) };
+    let mut converged = fa == 0.0 || fb == 0.0;
+    let mut iterations = 0;
+    while !converged && iterations < max_iterations {
+        iterations += 1;
+        mid = 0.5 * (a + b);
+        fmid = f.call(mid);
+        if (fmid > 0.0) == (fa > 0.0) {
+            a = mid;
+            fa = fmid;
+        } else {
+            b = mid;
+        }
+        let error = 0.5 * (b - a);
+        history.push(RootIteration { iteration: iterations, x: mid, fx: fmid, error });
+        converged = fmid == 0.0 || error <= tolerance;
+    }
+
+    Ok(RootResult {
+        method: RootMethod::Bisection,
+        root: mid,
+        value: fmid,
+        iterations,
+        evaluations: f.calls.get(),
+        error_estimate: if fmid == 0.0 { 0.0 } else { 0.5 * (b - a) },
+        converged,
+        message: (!converged).then(|| "Iteration limit reached".to_string()),
+        history,
+    })
+}
+
+/// Newton's method from `x0`, stopping once a step is below `tolerance`.
+/// Stalls (a zero or undefined derivative) are reported rather than raised.
+#[cfg(feature = "math")]
+pub fn newton(
+    f: &dyn Fn(f64) -> f64,
+    derivative: &dyn Fn(f64) -> f64,
+    x0: f64,
+    tolerance: f64,
+    max_iterations: u32,
+) -> Result<RootResult, String> {
+    if !x0.is_finite() {
+        return Err("The starting point must be finite".to_string());
+    }
+    check_tolerance(tolerance)?;
+    let max_iterations = max_iterations.min(MAX_ROOT_ITERATIONS);
+    let f = Counted::new(f);
+    let mut x = x0;
+    let mut fx = f.call(x);
+    let mut step = f64::INFINITY;
+    let mut history = Vec::new();
+    let mut message = None;
+    let mut converged = fx == 0.0;
+    let mut iterations = 0;
+    while !converged && iterations < max_iterations {
+        let slope = derivative(x);
+        if !fx.is_finite() || !slope.is_finite() {
+            message = Some(format!("The function or its derivative is undefined at x = {}", x));
+            break;
+        }
+        if slope == 0.0 {
+            message = Some(format!("The derivative vanishes at x = {}", x));
+            break;
+        }
+        iterations += 1;
+        step = fx / slope;
+        x -= step;
+        fx = f.call(x);
+        history.push(RootIteration { iteration: iterations, x, fx, error: step.abs() });
+        converged = fx == 0.0 || step.abs() <= tolerance;
+    }
+    if !converged && message.is_none() {
+        message = Some("Iteration limit reached".to_string());
+    }
+
+    Ok(RootResult {
+        method: RootMethod::Newton,
+        root: x,
+        value: fx,
+        iterations,
+        evaluations: f.calls.get(),
+        error_estimate: if fx == 0.0 { 0.0 } else { step.abs() },
+        converged,
+        message,
+        history,
+    })
+}
+
+/// Brent's method: inverse quadratic interpolation and secant steps,
+/// falling back to bisection whenever they would leave the bracket or
+/// shrink it too slowly.
+#[cfg(feature = "math")]
+pub fn brent(f: &dyn Fn(f64) -> f64, a: f64, b: f64, tolerance: f64, max_iterations: u32) -> Result<RootResult, String> {
+    check_tolerance(tolerance)?;
+    let max_iterations = max_iterations.min(MAX_ROOT_ITERATIONS);
+    let f = Counted::new(f);
+    let (mut a, mut b) = (a, b);
+    let (mut fa, mut fb) = (f.call(a), f.call(b));
+    check_bracket(a, fa, b, fb)?;
+
+    // b is the best estimate, c the other end of the bracket, and d and e
+    // the last two step sizes.
+    let (mut c, mut fc) = (b, fb);
+    let (mut d, mut e) = (b - a, b - a);
+    let mut history = Vec::new();
+    let mut error = 0.5 * (b - a);
+    let mut converged = false;
+    let mut iterations = 0;
+    while iterations < max_iterations {
+        if (fb > 0.0) == (fc > 0.0) {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            (a, b, c) = (b, c, b);
+            (fa, fb, fc) = (fb, fc, fb);
+        }
+        let tol = 2.0 * f64::EPSILON * b.abs() + 0.5 * tolerance;
+        let half = 0.5 * (c - b);
+        error = half.abs();
+        if error <= tol || fb == 0.0 {
+            converged = true;
+            break;
+        }
+
+        if e.abs() >= tol && fa.abs() > fb.abs() {
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (2.0 * half * s, 1.0 - s)
+            } else {
+                let (q, r) = (fa / fc, fb / fc);
+                (s * (2.0 * half * q * (q - r) - (b - a) * (r - 1.0)), (q - 1.0) * (r - 1.0) * (s - 1.0))
+            };
+            if p > 0.0 {
+                q = -q;
+            }
+            p = p.abs();
+            if 2.0 * p < (3.0 * half * q - (tol * q).abs()).min((e * q).abs()) {
+                e = d;
+                d = p / q;
+            } else {
+                d = half;
+                e = d;
+            }
+        } else {
+            d = half;
+            e = d;
+        }
+        a = b;
+        fa = fb;
+        b += if d.abs() > tol { d } else { tol.copysign(half) };
+        fb = f.call(b);
+        iterations += 1;
+        history.push(RootIteration { iteration: iterations, x: b, fx: fb, error: 0.5 * (c - b).abs() });
+    }
+
+    Ok(RootResult {
+        method: RootMethod::Brent,
+        root: b,
+        value: fb,
+        iterations,
+        evaluations: f.calls.get(),
+        error_estimate: if fb == 0.0 { 0.0 } else { error },
+        converged,
+        message: (!converged).then(|| "Iteration limit reached".to_string()),
+        history,
+    })
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuadratureMethod {
+    Simpson,
+    GaussKronrod,
+}
+
+#[cfg(feature = "math")]
+impl QuadratureMethod {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "simpson" => Ok(QuadratureMethod::Simpson),
+            "gauss_kronrod" | "gk15" => Ok(QuadratureMethod::GaussKronrod),
+            _ => Err(format!("Unknown integration method: {}", name)),
+        }
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct IntegrationResult {
+    pub method: QuadratureMethod,
+    pub value: f64,
+    pub error_estimate: f64,
+    /// Interval doublings for Simpson, bisections for Gauss–Kronrod.
+    pub iterations: u32,
+    pub evaluations: usize,
+    /// Subintervals in the final estimate.
+    pub intervals: usize,
+    pub converged: bool,
+}
+
+/// Whether `error` is within `tolerance`, taken as absolute for small
+/// values and relative for large ones. A non-finite estimate never is.
+#[cfg(feature = "math")]
+fn within_tolerance(error: f64, value: f64, tolerance: f64) -> bool {
+    value.is_finite() && error <= tolerance * value.abs().max(1.0)
+}
+
+/// Composite Simpson's rule, doubling the interval count until two
+/// successive estimates agree. The error estimate is Richardson's
+/// |S(2n) - S(n)| / 15. `max_doublings` is capped at
+/// `SIMPSON_MAX_DOUBLINGS`.
+#[cfg(feature = "math")]
+pub fn simpson(f: &dyn Fn(f64) -> f64, a: f64, b: f64, tolerance: f64, max_doublings: u32) -> Result<IntegrationResult, String> {
+    if !(a.is_finite() && b.is_finite()) {
+        return Err("Simpson's rule needs finite limits; use gauss_kronrod for infinite ones".to_string());
+    }
+    let max_doublings = max_doublings.min(SIMPSON_MAX_DOUBLINGS);
+    let f = Counted::new(f);
+    let mut n = SIMPSON_START_INTERVALS;
+    let h = |n: usize| (b - a) / n as f64;
+    // Interior points split by index parity: odd ones get weight 4 and
+    // become even (weight 2) once the interval count doubles.
+    let ends = f.call(a) + f.call(b);
+    let mut even = 0.0;
+    let mut odd = (1..n).step_by(2).map(|i| f.call(a + i as f64 * h(n))).sum::<f64>();
+    let mut estimate = h(n) / 3.0 * (ends + 4.0 * odd + 2.0 * even);
+    let mut error = f64::INFINITY;
+    let mut iterations = 0;
+    let mut converged = false;
+    while iterations < max_doublings {
+        iterations += 1;
+        n *= 2;
+        even += odd;
+        odd = (1..n).step_by(2).map(|i| f.call(a + i as f64 * h(n))).sum();
+        let refined = h(n) / 3.0 * (ends + 4.0 * odd + 2.0 * even);
+        error = (refined - estimate).abs() / 15.0;
+        estimate = refined;
+        if !estimate.is_finite() {
+            break;
+        }
+        if n >= SIMPSON_MIN_INTERVALS && within_tolerance(error, estimate, tolerance) {
+            converged = true;
+            break;
+        }
+    }
+
+    Ok(IntegrationResult {
+        method: QuadratureMethod::Simpson,
+        value: estimate,
+        error_estimate: error,
+        iterations,
+        evaluations: f.calls.get(),
+        intervals: n,
+        converged,
+    })
+}
+
+/// The 15-point Kronrod estimate over `[a, b]` and its distance from the
+/// embedded 7-point Gauss estimate.
+#[cfg(feature = "math")]
+fn gauss_kronrod_15(f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> (f64, f64) {
+    let (centre, half) = (0.5 * (a + b), 0.5 * (b - a));
+    let fc = f(centre);
+    let mut kronrod = KRONROD_WEIGHTS[7] * fc;
+    let mut gauss = GAUSS_WEIGHTS[3] * fc;
+    for i in 0..7 {
+        let dx = half * KRONROD_NODES[i];
+        let pair = f(centre - dx) + f(centre + dx);
+        kronrod += KRONROD_WEIGHTS[i] * pair;
+        if i % 2 == 1 {
+            gauss += GAUSS_WEIGHTS[i / 2] * pair;
+        }
+    }
+    (kronrod * half, ((kronrod - gauss) * half).abs())
+}
+
+/// A subinterval of an adaptive integration, ordered by its error estimate.
+#[cfg(feature = "math")]
+struct Piece {
+    lo: f64,
+    hi: f64,
+    value: f64,
+    error: f64,
+}
+
+#[cfg(feature = "math")]
+impl PartialEq for Piece {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+#[cfg(feature = "math")]
+impl Eq for Piece {}
+
+#[cfg(feature = "math")]
+impl PartialOrd for Piece {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "math")]
+impl Ord for Piece {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.error.total_cmp(&other.error)
+    }
+}
+
+/// Adaptive Gauss–Kronrod (G7/K15): the subinterval with the largest error
+/// estimate is bisected until the total error is within `tolerance`.
+/// Infinite limits are mapped onto a finite interval first.
+/// `max_subdivisions` is capped at `MAX_SUBDIVISIONS`.
+#[cfg(feature = "math")]
+pub fn gauss_kronrod(f: &dyn Fn(f64) -> f64, a: f64, b: f64, tolerance: f64, max_subdivisions: u32) -> Result<IntegrationResult, String> {
+    if a.is_nan() || b.is_nan() {
+        return Err("Integration limits must be numbers".to_string());
+    }
+    check_tolerance(tolerance)?;
+    let max_subdivisions = max_subdivisions.min(MAX_SUBDIVISIONS);
+    if a == b {
+        return Ok(IntegrationResult {
+            method: QuadratureMethod::GaussKronrod,
+            value: 0.0,
+            error_estimate: 0.0,
+            iterations: 0,
+            evaluations: 0,
+            intervals: 0,
+            converged: true,
+        });
+    }
+    if a > b {
+        let mut result = gauss_kronrod(f, b, a, tolerance, max_subdivisions)?;
+        result.value = -result.value;
+        return Ok(result);
+    }
+
+    let counted = Counted::new(f);
+    let f = |x: f64| counted.call(x);
+    // The substitutions keep the nodes, which never touch an end of the
+    // interval, away from the infinities.
+    let (g, lower, upper): (Box<dyn Fn(f64) -> f64 + '_>, f64, f64) = match (a.is_finite(), b.is_finite()) {
+        (true, true) => (Box::new(f), a, b),
+        (true, false) => (Box::new(move |t: f64| f(a + t / (1.0 - t)) / ((1.0 - t) * (1.0 - t))), 0.0, 1.0),
+        (false, true) => (Box::new(move |t: f64| f(b - (1.0 - t) / t) / (t * t)), 0.0, 1.0),
+        (false, false) => (
+            Box::new(move |t: f64| {
+                let s = 1.0 - t * t;
+                f(t / s) * (1.0 + t * t) / (s * s)
+            }),
+            -1.0,
+            1.0,
+        ),
+    };
+
+    let (value, error) = gauss_kronrod_15(&g, lower, upper);
+    let mut pieces = BinaryHeap::from([Piece { lo: lower, hi: upper, value, error }]);
+    let mut iterations = 0;
+    let (mut total, mut total_error) = (value, error);
+    while !within_tolerance(total_error, total, tolerance) && iterations < max_subdivisions && total.is_finite() {
+        let worst = pieces.pop().unwrap();
+        let mid = 0.5 * (worst.lo + worst.hi);
+        let (left, left_error) = gauss_kronrod_15(&g, worst.lo, mid);
+        let (right, right_error) = gauss_kronrod_15(&g, mid, worst.hi);
+        pieces.push(Piece { lo: worst.lo, hi: mid, value: left, error: left_error });
+        pieces.push(Piece { lo: mid, hi: worst.hi, value: right, error: right_error });
+        iterations += 1;
+        total += left + right - worst.value;
+        total_error += left_error + right_error - worst.error;
+        // The running totals drift as terms come and go, so re-sum before
+        // trusting them to stop.
+        if within_tolerance(total_error, total, tolerance) {
+            total = pieces.iter().map(|p| p.value).sum();
+            total_error = pieces.iter().map(|p| p.error).sum();
+        }
+    }
+
+    Ok(IntegrationResult {
+        method: QuadratureMethod::GaussKronrod,
+        value: total,
+        error_estimate: total_error,
+        iterations,
+        evaluations: counted.calls.get(),
+        intervals: pieces.len(),
+        converged: within_tolerance(total_error, total, tolerance),
+    })
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InterpolationMethod {
+    Lagrange,
+    Newton,
+    Spline,
+}
+
+#[cfg(feature = "math")]
+impl InterpolationMethod {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "lagrange" => Ok(InterpolationMethod::Lagrange),
+            "newton" => Ok(InterpolationMethod::Newton),
+            "spline" | "cubic_spline" => Ok(InterpolationMethod::Spline),
+            _ => Err(format!("Unknown interpolation method: {}", name)),
+        }
+    }
+}
+
+#[cfg(feature = "math")]
+fn check_nodes(xs: &[f64], ys: &[f64], minimum: usize) -> Result<(), String> {
+    if xs.len() != ys.len() {
+        return Err(format!("Got {} x values but {} y values", xs.len(), ys.len()));
+    }
+    if xs.len() < minimum {
+        return Err(format!("At least {} points are needed", minimum));
+    }
+    if !xs.iter().chain(ys).all(|v| v.is_finite()) {
+        return Err("Points must be finite".to_string());
+    }
+    let mut sorted = xs.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    if let Some(w) = sorted.windows(2).find(|w| w[0] == w[1]) {
+        return Err(format!("x = {} appears more than once", w[0]));
+    }
+    Ok(())
+}
+
+/// The interpolating polynomial in barycentric Lagrange form.
+#[cfg(feature = "math")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LagrangePolynomial {
+    pub xs: Vec<f64>,
+    pub ys: Vec<f64>,
+    pub weights: Vec<f64>,
+}
+
+#[cfg(feature = "math")]
+impl LagrangePolynomial {
+    pub fn new(xs: &[f64], ys: &[f64]) -> Result<Self, String> {
+        check_nodes(xs, ys, 1)?;
+        let weights = (0..xs.len())
+            .map(|j| 1.0 / (0..xs.len()).filter(|&k| k != j).map(|k| xs[j] - xs[k]).product::<f64>())
+            .collect();
+        Ok(LagrangePolynomial { xs: xs.to_vec(), ys: ys.to_vec(), weights })
+    }
+
+    pub fn eval(&self, x: f64) -> f64 {
+        barycentric(&self.xs, &self.ys, &self.weights, x)
+    }
+
+    /// Distance from the interpolant through all but the last node.
+    pub fn error_estimate(&self, x: f64) -> Option<f64> {
+        let n = self.xs.len();
+        if n < 2 {
+            return None;
+        }
+        let last = self.xs[n - 1];
+        // Dropping a node rescales the remaining weights by (x_j - x_last).
+        let reduced: Vec<f64> = self.weights[..n - 1].iter().zip(&self.xs).map(|(w, xj)| w * (xj - last)).collect();
+        Some((self.eval(x) - barycentric(&self.xs[..n - 1], &self.ys[..n - 1], &reduced, x)).abs())
+    }
+}
+
+#[cfg(feature = "math")]
+fn barycentric(xs: &[f64], ys: &[f64], weights: &[f64], x: f64) -> f64 {
+    let (mut numerator, mut denominator) = (0.0, 0.0);
+    for ((&xj, &yj), &wj) in xs.iter().zip(ys).zip(weights) {
+        if x == xj {
+            return yj;
+        }
+        let t = wj / (x - xj);
+        numerator += t * yj;
+        denominator += t;
+    }
+    numerator / denominator
+}
+
+/// The interpolating polynomial in Newton form, from divided differences.
+#[cfg(feature = "math")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct NewtonPolynomial {
+    pub xs: Vec<f64>,
+    /// f[x0], f[x0, x1], ..., f[x0, ..., xn].
+    pub coefficients: Vec<f64>,
+}
+
+#[cfg(feature = "math")]
+impl NewtonPolynomial {
+    pub fn new(xs: &[f64], ys: &[f64]) -> Result<Self, String> {
+        check_nodes(xs, ys, 1)?;
+        let mut table = ys.to_vec();
+        let mut coefficients = vec![table[0]];
+        for level in 1..xs.len() {
+            for i in 0..xs.len() - level {
+                table[i] = (table[i + 1] - table[i]) / (xs[i + level] - xs[i]);
+            }
+            coefficients.push(table[0]);
+        }
+        Ok(NewtonPolynomial { xs: xs.to_vec(), coefficients })
+    }
+
+    pub fn eval(&self, x: f64) -> f64 {
+        let n = self.coefficients.len();
+        (0..n - 1).rev().fold(self.coefficients[n - 1], |acc, i| acc * (x - self.xs[i]) + self.coefficients[i])
+    }
+
+    /// Size of the last term, i.e. how much the final node changed the value.
+    pub fn error_estimate(&self, x: f64) -> Option<f64> {
+        let n = self.coefficients.len();
+        if n < 2 {
+            return None;
+        }
+        Some((self.coefficients[n - 1] * self.xs[..n - 1].iter().map(|xi| x - xi).product::<f64>()).abs())
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SplineBoundary {
+    /// Zero second derivative at both ends.
+    Natural,
+    /// Prescribed first derivatives at the ends.
+    Clamped { start: f64, end: f64 },
+}
+
+/// `y = a + b (x - x0) + c (x - x0)^2 + d (x - x0)^3` on `[x0, x1]`.
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct SplineSegment {
+    pub x0: f64,
+    pub x1: f64,
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+}
+
+#[cfg(feature = "math")]
+impl SplineSegment {
+    fn eval(&self, x: f64) -> f64 {
+        let t = x - self.x0;
+        self.a + t * (self.b + t * (self.c + t * self.d))
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CubicSpline {
+    pub boundary: SplineBoundary,
+    pub segments: Vec<SplineSegment>,
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+}
+
+#[cfg(feature = "math")]
+impl CubicSpline {
+    /// Points may come in any order; they are sorted by x.
+    pub fn new(xs: &[f64], ys: &[f64], boundary: SplineBoundary) -> Result<Self, String> {
+        check_nodes(xs, ys, 2)?;
+        let mut points: Vec<(f64, f64)> = xs.iter().copied().zip(ys.iter().copied()).collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let (xs, ys): (Vec<f64>, Vec<f64>) = points.into_iter().unzip();
+        let n = xs.len() - 1;
+        let h: Vec<f64> = xs.windows(2).map(|w| w[1] - w[0]).collect();
+        let slope: Vec<f64> = (0..n).map(|i| (ys[i + 1] - ys[i]) / h[i]).collect();
+
+        // Tridiagonal system for the second derivatives m_0..m_n.
+        let (mut lower, mut diagonal, mut upper, mut rhs) = (vec![0.0; n + 1], vec![0.0; n + 1], vec![0.0; n + 1], vec![0.0; n + 1]);
+        for i in 1..n {
+            lower[i] = h[i - 1];
+            diagonal[i] = 2.0 * (h[i - 1] + h[i]);
+            upper[i] = h[i];
+            rhs[i] = 6.0 * (slope[i] - slope[i - 1]);
+        }
+        match boundary {
+            SplineBoundary::Natural => {
+                diagonal[0] = 1.0;
+                diagonal[n] = 1.0;
+            }
+            SplineBoundary::Clamped { start, end } => {
+                diagonal[0] = 2.0 * h[0];
+                upper[0] = h[0];
+                rhs[0] = 6.0 * (slope[0] - start);
+                lower[n] = h[n - 1];
+                diagonal[n] = 2.0 * h[n - 1];
+                rhs[n] = 6.0 * (end - slope[n - 1]);
+            }
+        }
+        let m = solve_tridiagonal(&lower, &diagonal, &upper, &rhs);
+
+        let segments = (0..n)
+            .map(|i| SplineSegment {
+                x0: xs[i],
+                x1: xs[i + 1],
+                a: ys[i],
+                b: slope[i] - h[i] * (2.0 * m[i] + m[i + 1]) / 6.0,
+                c: m[i] / 2.0,
+                d: (m[i + 1] - m[i]) / (6.0 * h[i]),
+            })
+            .collect();
+        Ok(CubicSpline { boundary, segments, xs, ys })
+    }
+
+    fn segment_index(&self, x: f64) -> usize {
+        self.segments.partition_point(|s| s.x1 < x).min(self.segments.len() - 1)
+    }
+
+    /// Outside the nodes the end segments are extended.
+    pub fn eval(&self, x: f64) -> f64 {
+        self.segments[self.segment_index(x)].eval(x)
+    }
+
+    /// A rough estimate: the distance from the cubic through the four
+    /// nodes nearest `x`. Needs at least four points.
+    pub fn error_estimate(&self, x: f64) -> Option<f64> {
+        if self.xs.len() < 4 {
+            return None;
+        }
+        let start = self.segment_index(x).saturating_sub(1).min(self.xs.len() - 4);
+        let local = LagrangePolynomial::new(&self.xs[start..start + 4], &self.ys[start..start + 4]).ok()?;
+        Some((self.eval(x) - local.eval(x)).abs())
+    }
+}
+
+/// Thomas algorithm; `lower[0]` and `upper[n - 1]` are ignored. The splines
+/// built here are diagonally dominant, so no pivoting is needed.
+#[cfg(feature = "math")]
+fn solve_tridiagonal(lower: &[f64], diagonal: &[f64], upper: &[f64], rhs: &[f64]) -> Vec<f64> {
+    let n = diagonal.len();
+    let (mut c, mut d) = (vec![0.0; n], vec![0.0; n]);
+    c[0] = upper[0] / diagonal[0];
+    d[0] = rhs[0] / diagonal[0];
+    for i in 1..n {
+        let denominator = diagonal[i] - lower[i] * c[i - 1];
+        c[i] = upper[i] / denominator;
+        d[i] = (rhs[i] - lower[i] * d[i - 1]) / denominator;
+    }
+    for i in (0..n - 1).rev() {
+        d[i] -= c[i] * d[i + 1];
+    }
+    d
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct InterpolationResult {
+    pub method: InterpolationMethod,
+    /// The interpolant at each query point.
+    pub values: Vec<f64>,
+    /// Per query point; see the `error_estimate` methods for what each
+    /// method measures. None when there are too few points to tell.
+    pub error_estimates: Vec<Option<f64>>,
+    /// Query points outside the range of the nodes.
+    pub extrapolated: usize,
+    /// Barycentric weights (Lagrange) or divided differences (Newton).
+    pub coefficients: Vec<f64>,
+    /// Spline pieces, empty for the polynomial methods.
+    pub segments: Vec<SplineSegment>,
+}
+
+#[cfg(feature = "math")]
+pub fn interpolate(
+    xs: &[f64],
+    ys: &[f64],
+    at: &[f64],
+    method: InterpolationMethod,
+    boundary: SplineBoundary,
+) -> Result<InterpolationResult, String> {
+    let (values, error_estimates, coefficients, segments): (Vec<f64>, Vec<Option<f64>>, Vec<f64>, Vec<SplineSegment>) = match method {
+        InterpolationMethod::Lagrange => {
+            let p = LagrangePolynomial::new(xs, ys)?;
+            (at.iter().map(|&x| p.eval(x)).collect(), at.iter().map(|&x| p.error_estimate(x)).collect(), p.weights, Vec::new())
+        }
+        InterpolationMethod::Newton => {
+            let p = NewtonPolynomial::new(xs, ys)?;
+            (at.iter().map(|&x| p.eval(x)).collect(), at.iter().map(|&x| p.error_estimate(x)).collect(), p.coefficients, Vec::new())
+        }
+        InterpolationMethod::Spline => {
+            let s = CubicSpline::new(xs, ys, boundary)?;
+            (at.iter().map(|&x| s.eval(x)).collect(), at.iter().map(|&x| s.error_estimate(x)).collect(), Vec::new(), s.segments)
+        }
+    };
+    let (min, max) = xs.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &x| (lo.min(x), hi.max(x)));
+    Ok(InterpolationResult {
+        method,
+        values,
+        error_estimates,
+        extrapolated: at.iter().filter(|&&x| x < min || x > max).count(),
+        coefficients,
+        segments,
+    })
+}
+
+/// Parses a function of at most one variable, returning it with the
+/// variable's name (`x` when the expression is constant).
+#[cfg(feature = "math")]
+fn parse_function(source: &str) -> Result<(Expr, String), JsValue> {
+    let expr = parse_expression_source(source).map_err(parse_error_to_js)?;
+    let variables: Vec<String> = expr.variables().into_iter().collect();
+    if variables.len() > 1 {
+        return Err(JsValue::from_str(&format!("Expected a function of one variable, found {}", variables.join(", "))));
+    }
+    let variable = variables.into_iter().next().unwrap_or_else(|| "x".to_string());
+    Ok((expr, variable))
+}
+
+#[cfg(feature = "math")]
+fn evaluate_at(expr: &Expr, variable: &str, x: f64) -> f64 {
+    expr.eval_with(&|name| (name == variable).then_some(x)).unwrap_or(f64::NAN)
+}
+
+/// Finds a root of a one-variable expression. Bisection and Brent need a
+/// bracket `[a, b]`; Newton starts from `a` and differentiates the
+/// expression symbolically.
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub fn find_root(
+    expression: &str,
+    method: &str,
+    a: f64,
+    b: Option<f64>,
+    tolerance: Option<f64>,
+    max_iterations: Option<u32>,
+) -> Result<JsValue, JsValue> {
+    let method = RootMethod::parse(method).map_err(|e| JsValue::from_str(&e))?;
+    let tolerance = tolerance.unwrap_or(DEFAULT_TOLERANCE);
+    check_tolerance(tolerance).map_err(|e| JsValue::from_str(&e))?;
+    let (expr, variable) = parse_function(expression)?;
+    let f = |x: f64| evaluate_at(&expr, &variable, x);
+    let max_iterations = max_iterations.unwrap_or(DEFAULT_MAX_ITERATIONS);
+    let bracket_end = || b.ok_or_else(|| "This method needs both ends of a bracket".to_string());
+
+    let result = match method {
+        RootMethod::Bisection => bracket_end().and_then(|b| bisection(&f, a, b, tolerance, max_iterations)),
+        RootMethod::Brent => bracket_end().and_then(|b| brent(&f, a, b, tolerance, max_iterations)),
+        RootMethod::Newton => differentiate(&expr, &variable).and_then(|d| {
+            let d = simplify(&d);
+            newton(&f, &|x| evaluate_at(&d, &variable, x), a, tolerance, max_iterations)
+        }),
+    }
+    .map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result).unwrap())
+}
+
+/// Integrates a one-variable expression over `[a, b]`; Gauss–Kronrod also
+/// accepts infinite limits. `max_iterations` caps interval doublings
+/// (Simpson) or subdivisions (Gauss–Kronrod).
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub fn integrate_expression(
+    expression: &str,
+    method: &str,
+    a: f64,
+    b: f64,
+    tolerance: Option<f64>,
+    max_iterations: Option<u32>,
+) -> Result<JsValue, JsValue> {
+    let method = QuadratureMethod::parse(method).map_err(|e| JsValue::from_str(&e))?;
+    let tolerance = tolerance.unwrap_or(DEFAULT_TOLERANCE);
+    check_tolerance(tolerance).map_err(|e| JsValue::from_str(&e))?;
+    let (expr, variable) = parse_function(expression)?;
+    let f = |x: f64| evaluate_at(&expr, &variable, x);
+    let result = match method {
+        QuadratureMethod::Simpson => simpson(&f, a, b, tolerance, max_iterations.unwrap_or(20)),
+        QuadratureMethod::GaussKronrod => gauss_kronrod(&f, a, b, tolerance, max_iterations.unwrap_or(200)),
+    }
+    .map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result).unwrap())
+}
+
+/// Interpolates the points `(xs[i], ys[i])` at each of `at`. Splines are
+/// natural unless both end slopes are given.
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub fn interpolate_points(
+    xs: &[f64],
+    ys: &[f64],
+    at: &[f64],
+    method: &str,
+    start_slope: Option<f64>,
+    end_slope: Option<f64>,
+) -> Result<JsValue, JsValue> {
+    let method = InterpolationMethod::parse(method).map_err(|e| JsValue::from_str(&e))?;
+    let boundary = match (start_slope, end_slope) {
+        (Some(start), Some(end)) => SplineBoundary::Clamped { start, end },
+        (None, None) => SplineBoundary::Natural,
+        _ => return Err(JsValue::from_str("Give both end slopes for a clamped spline, or neither")),
+    };
+    let result = interpolate(xs, ys, at, method, boundary).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result).unwrap())
+}
+
+#[cfg(all(test, feature = "math"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simpson_caps_the_doubling_count() {
+        // A tolerance of zero never converges, so the cap decides the work.
+        let result = simpson(&|x: f64| x.sin(), 0.0, 1.0, 0.0, u32::MAX).unwrap();
+        assert!(!result.converged);
+        assert_eq!(result.iterations, SIMPSON_MAX_DOUBLINGS);
+        assert_eq!(result.intervals, SIMPSON_START_INTERVALS << SIMPSON_MAX_DOUBLINGS);
+        assert!((result.value - (1.0 - 1f64.cos())).abs() < 1e-12);
+    }
+
+    fn square_minus_two(x: f64) -> f64 {
+        x * x - 2.0
+    }
+
+    #[test]
+    fn root_finders_converge_on_sqrt_two() {
+        let bisected = bisection(&square_minus_two, 0.0, 2.0, 1e-12, DEFAULT_MAX_ITERATIONS).unwrap();
+        let newtons = newton(&square_minus_two, &|x| 2.0 * x, 1.0, 1e-12, DEFAULT_MAX_ITERATIONS).unwrap();
+        let brents = brent(&square_minus_two, 0.0, 2.0, 1e-12, DEFAULT_MAX_ITERATIONS).unwrap();
+        for result in [&bisected, &newtons, &brents] {
+            assert!(result.converged, "{:?} did not converge", result.method);
+            assert!((result.root - 2f64.sqrt()).abs() < 1e-11, "{:?} gave {}", result.method, result.root);
+            assert_eq!(result.history.len(), result.iterations as usize);
+        }
+        // Newton converges quadratically and Brent superlinearly.
+        assert!(newtons.iterations <= 6);
+        assert!(brents.iterations < bisected.iterations);
+    }
+
+    #[test]
+    fn brent_solves_cos_x_equals_x() {
+        let result = brent(&|x: f64| x.cos() - x, 0.0, 1.0, 1e-14, DEFAULT_MAX_ITERATIONS).unwrap();
+        assert!(result.converged);
+        assert!((result.root - 0.739_085_133_215_160_6).abs() < 1e-13);
+    }
+
+    #[test]
+    fn root_finders_reject_bad_tolerances() {
+        for tolerance in [0.0, -1e-3, f64::NAN, f64::INFINITY] {
+            assert!(bisection(&square_minus_two, 0.0, 2.0, tolerance, 10).is_err());
+            assert!(newton(&square_minus_two, &|x| 2.0 * x, 1.0, tolerance, 10).is_err());
+            assert!(brent(&square_minus_two, 0.0, 2.0, tolerance, 10).is_err());
+            assert!(gauss_kronrod(&|x| x, 0.0, 1.0, tolerance, 10).is_err());
+        }
+    }
+
+    #[test]
+    fn root_iterations_are_capped() {
+        // A tolerance far below the float spacing near the root never converges.
+        let result = bisection(&square_minus_two, 0.0, 2.0, 1e-300, u32::MAX).unwrap();
+        assert!(!result.converged);
+        assert_eq!(result.iterations, MAX_ROOT_ITERATIONS);
+        assert_eq!(result.history.len(), MAX_ROOT_ITERATIONS as usize);
+    }
+
+    #[test]
+    fn gauss_kronrod_handles_infinite_limits() {
+        let gaussian = gauss_kronrod(&|x: f64| (-x * x).exp(), f64::NEG_INFINITY, f64::INFINITY, 1e-10, 200).unwrap();
+        assert!(gaussian.converged);
+        assert!((gaussian.value - std::f64::consts::PI.sqrt()).abs() < 1e-9);
+
+        let decay = gauss_kronrod(&|x: f64| (-x).exp(), 0.0, f64::INFINITY, 1e-10, 200).unwrap();
+        assert!((decay.value - 1.0).abs() < 1e-9);
+
+        let reversed = gauss_kronrod(&|x: f64| 1.0 / (1.0 + x * x), f64::INFINITY, 0.0, 1e-10, 200).unwrap();
+        assert!((reversed.value + std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn gauss_kronrod_caps_the_subdivisions() {
+        // More jumps than the cap allows subdivisions, so the error never vanishes.
+        let sawtooth = |x: f64| (x * 12_345.5).fract();
+        let result = gauss_kronrod(&sawtooth, 0.0, 1.0, 1e-300, u32::MAX).unwrap();
+        assert!(!result.converged);
+        assert_eq!(result.iterations, MAX_SUBDIVISIONS);
+        assert_eq!(result.intervals, MAX_SUBDIVISIONS as usize + 1);
+        assert!((result.value - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn gauss_kronrod_does_not_converge_to_infinity() {
+        let result = gauss_kronrod(&|x: f64| 1.0 / x.sqrt(), 0.0, 1.0, 1e-300, u32::MAX).unwrap();
+        assert!(!result.value.is_finite());
+        assert!(!result.converged);
+    }
+
+    fn cubic(x: f64) -> f64 {
+        x * x * x - 2.0 * x + 1.0
+    }
+
+    #[test]
+    fn polynomial_interpolants_reproduce_a_cubic() {
+        let xs = [-1.0, 0.5, 2.0, 3.0];
+        let ys = xs.map(cubic);
+        let lagrange = LagrangePolynomial::new(&xs, &ys).unwrap();
+        let newton = NewtonPolynomial::new(&xs, &ys).unwrap();
+        for x in [-2.0, 0.0, 1.25, 2.5, 4.0] {
+            assert!((lagrange.eval(x) - cubic(x)).abs() < 1e-12, "Lagrange at {}", x);
+            assert!((newton.eval(x) - cubic(x)).abs() < 1e-12, "Newton at {}", x);
+        }
+    }
+
+    #[test]
+    fn polynomial_interpolants_converge_on_exp() {
+        let error = |n: usize| {
+            let xs: Vec<f64> = (0..n).map(|i| i as f64 / (n - 1) as f64).collect();
+            let ys: Vec<f64> = xs.iter().map(|x| x.exp()).collect();
+            let lagrange = LagrangePolynomial::new(&xs, &ys).unwrap();
+            let newton = NewtonPolynomial::new(&xs, &ys).unwrap();
+            (0..=100)
+                .map(|i| i as f64 / 100.0)
+                .map(|x| (lagrange.eval(x) - x.exp()).abs().max((newton.eval(x) - x.exp()).abs()))
+                .fold(0.0, f64::max)
+        };
+        assert!(error(4) < 1e-2);
+        assert!(error(8) < 1e-6);
+        assert!(error(12) < 1e-10);
+    }
+
+    #[test]
+    fn clamped_spline_reproduces_a_cubic() {
+        let xs = [0.0, 1.0, 1.5, 3.0, 4.0];
+        let ys = xs.map(cubic);
+        let boundary = SplineBoundary::Clamped { start: -2.0, end: 46.0 };
+        let spline = CubicSpline::new(&xs, &ys, boundary).unwrap();
+        for x in [0.25, 1.2, 2.0, 3.5, 4.0] {
+            assert!((spline.eval(x) - cubic(x)).abs() < 1e-12, "spline at {}", x);
+        }
+    }
+
+    #[test]
+    fn natural_spline_converges_at_fourth_order() {
+        // Away from the ends the natural boundary's error does not dominate.
+        let error = |n: usize| {
+            let xs: Vec<f64> = (0..=n).map(|i| std::f64::consts::PI * i as f64 / n as f64).collect();
+            let ys: Vec<f64> = xs.iter().map(|x| x.sin()).collect();
+            let spline = CubicSpline::new(&xs, &ys, SplineBoundary::Natural).unwrap();
+            (0..=100)
+                .map(|i| 1.0 + i as f64 / 100.0)
+                .map(|x| (spline.eval(x) - x.sin()).abs())
+                .fold(0.0, f64::max)
+        };
+        let (coarse, fine) = (error(10), error(20));
+        assert!(coarse < 1e-4);
+        assert!(coarse / fine > 10.0, "{} vs {}", coarse, fine);
+    }
+}
@@ -0,0 +1,753 @@
+#[cfg(feature = "math")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "math")]
+use serde::Serialize;
+#[cfg(feature = "math")]
+use std::collections::BTreeMap;
+#[cfg(feature = "math")]
+use super::expression::{parse_expression_source, Expr, MathConstant, ParseError};
+
+#[cfg(feature = "math")]
+pub const DEFAULT_ODE_TOLERANCE: f64 = 1e-6;
+#[cfg(feature = "math")]
+pub const DEFAULT_MAX_STEPS: usize = 100_000;
+/// Fixed-step methods default to this many steps across the time span.
+#[cfg(feature = "math")]
+const DEFAULT_FIXED_STEPS: f64 = 1000.0;
+
+// Dormand–Prince 5(4) tableau. The fifth-order weights equal the last row
+// of A, so the final stage doubles as the first stage of the next step.
+#[cfg(feature = "math")]
+const DP_C: [f64; 7] = [0.0, 1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0];
+#[cfg(feature = "math")]
+const DP_A: [[f64; 6]; 7] = [
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+    [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+    [19372.0 / 6561.0, -25360.0 / 2187.0, 64448.0 / 6561.0, -212.0 / 729.0, 0.0, 0.0],
+    [9017.0 / 3168.0, -355.0 / 33.0, 46732.0 / 5247.0, 49.0 / 176.0, -5103.0 / 18656.0, 0.0],
+    [35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0],
+];
+/// Fifth-order minus fourth-order weights, giving the local error estimate.
+#[cfg(feature = "math")]
+const DP_ERROR: [f64; 7] = [
+    71.0 / 57600.0,
+    0.0,
+    -71.0 / 16695.0,
+    71.0 / 1920.0,
+    -17253.0 / 339200.0,
+    22.0 / 525.0,
+    -1.0 / 40.0,
+];
+
+// Step-size controller: safety factor and limits on how fast h may change.
+#[cfg(feature = "math")]
+const STEP_SAFETY: f64 = 0.9;
+#[cfg(feature = "math")]
+const STEP_MIN_FACTOR: f64 = 0.2;
+#[cfg(feature = "math")]
+const STEP_MAX_FACTOR: f64 = 5.0;
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OdeMethod {
+    Euler,
+    Rk4,
+    /// Adaptive Dormand–Prince 5(4).
+    Rk45,
+}
+
+#[cfg(feature = "math")]
+impl OdeMethod {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "euler" => Ok(OdeMethod::Euler),
+            "rk4" => Ok(OdeMethod::Rk4),
+            "rk45" | "dopri5" => Ok(OdeMethod::Rk45),
+            _ => Err(format!("Unknown ODE method: {}", name)),
+        }
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StepControl {
+    /// The step for Euler and RK4; the first trial step for RK45.
+    pub step: Option<f64>,
+    pub relative_tolerance: f64,
+    pub absolute_tolerance: f64,
+    pub max_steps: usize,
+}
+
+#[cfg(feature = "math")]
+impl Default for StepControl {
+    fn default() -> Self {
+        StepControl {
+            step: None,
+            relative_tolerance: DEFAULT_ODE_TOLERANCE,
+            absolute_tolerance: DEFAULT_ODE_TOLERANCE * 1e-3,
+            max_steps: DEFAULT_MAX_STEPS,
+        }
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct StepStatistics {
+    pub method: OdeMethod,
+    pub accepted_steps: usize,
+    /// Steps RK45 retried with a smaller step; always 0 for fixed steps.
+    pub rejected_steps: usize,
+    pub evaluations: usize,
+    pub min_step: f64,
+    pub max_step: f64,
+    pub mean_step: f64,
+    /// Whether the end time was reached.
+    pub completed: bool,
+    pub message: Option<String>,
+}
+
+/// States are stored row-major, `dimension` values per time.
+#[cfg(feature = "math")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trajectory {
+    pub dimension: usize,
+    pub times: Vec<f64>,
+    pub states: Vec<f64>,
+    /// Size of each accepted step, one fewer than `times`.
+    pub steps: Vec<f64>,
+    pub statistics: StepStatistics,
+}
+
+#[cfg(feature = "math")]
+impl Trajectory {
+    pub fn state(&self, index: usize) -> &[f64] {
+        &self.states[index * self.dimension..(index + 1) * self.dimension]
+    }
+
+    pub fn component(&self, index: usize) -> Vec<f64> {
+        self.states.iter().skip(index).step_by(self.dimension).copied().collect()
+    }
+}
+
+/// Right-hand side `dy/dt = f(t, y)`, written into the last argument.
+#[cfg(feature = "math")]
+pub type OdeFunction<'a> = &'a dyn Fn(f64, &[f64], &mut [f64]);
+
+/// Accumulates the trajectory and counts evaluations as a solver runs.
+#[cfg(feature = "math")]
+struct Recorder<'a> {
+    f: OdeFunction<'a>,
+    evaluations: usize,
+    trajectory: Trajectory,
+}
+
+#[cfg(feature = "math")]
+impl Recorder<'_> {
+    fn eval(&mut self, t: f64, y: &[f64], out: &mut [f64]) {
+        self.evaluations += 1;
+        (self.f)(t, y, out);
+    }
+
+    fn record(&mut self, t: f64, y: &[f64], step: f64) {
+        self.trajectory.times.push(t);
+        self.trajectory.states.extend_from_slice(y);
+        self.trajectory.steps.push(step);
+    }
+
+    fn finish(mut self, outcome: Outcome) -> Trajectory {
+        let steps = &self.trajectory.steps;
+        let stats = &mut self.trajectory.statistics;
+        stats.accepted_steps = steps.len();
+        stats.rejected_steps = outcome.rejected;
+        stats.evaluations = self.evaluations;
+        if !steps.is_empty() {
+            stats.min_step = steps.iter().copied().fold(f64::INFINITY, f64::min);
+            stats.max_step = steps.iter().copied().fold(0.0, f64::max);
+            stats.mean_step = steps.iter().sum::<f64>() / steps.len() as f64;
+        }
+        stats.completed = outcome.stopped.is_none();
+        stats.message = outcome.stopped;
+        self.trajectory
+    }
+}
+
+/// How a solver loop ended.
+#[cfg(feature = "math")]
+struct Outcome {
+    rejected: usize,
+    /// Why the end time wasn't reached.
+    stopped: Option<String>,
+}
+
+/// Integrates `dy/dt = f(t, y)` from `t0` to `t1`, recording every step.
+#[cfg(feature = "math")]
+pub fn integrate_ode(f: OdeFunction, y0: &[f64], t0: f64, t1: f64, method: OdeMethod, control: &StepControl) -> Result<Trajectory, String> {
+    if !(t0.is_finite() && t1.is_finite() && t0 < t1) {
+        return Err("The time span must be finite with start < end".to_string());
+    }
+    if y0.is_empty() || !y0.iter().all(|v| v.is_finite()) {
+        return Err("The initial state must be non-empty and finite".to_string());
+    }
+    if let Some(h) = control.step {
+        if !(h.is_finite() && h > 0.0) {
+            return Err("The step size must be positive".to_string());
+        }
+    }
+    let positive = |v: f64| v.is_finite() && v > 0.0;
+    if !(positive(control.relative_tolerance) && positive(control.absolute_tolerance)) {
+        return Err("The tolerances must be positive".to_string());
+    }
+
+    let mut recorder = Recorder {
+        f,
+        evaluations: 0,
+        trajectory: Trajectory {
+            dimension: y0.len(),
+            times: vec![t0],
+            states: y0.to_vec(),
+            steps: Vec::new(),
+            statistics: StepStatistics {
+                method,
+                accepted_steps: 0,
+                rejected_steps: 0,
+                evaluations: 0,
+                min_step: 0.0,
+                max_step: 0.0,
+                mean_step: 0.0,
+                completed: false,
+                message: None,
+            },
+        },
+    };
+    let outcome = match method {
+        OdeMethod::Euler | OdeMethod::Rk4 => fixed_step(&mut recorder, y0, t0, t1, method, control),
+        OdeMethod::Rk45 => dormand_prince(&mut recorder, y0, t0, t1, control),
+    };
+    Ok(recorder.finish(outcome))
+}
+
+/// `out = y + h * k`.
+#[cfg(feature = "math")]
+fn offset(y: &[f64], k: &[f64], h: f64, out: &mut [f64]) {
+    for ((o, yi), ki) in out.iter_mut().zip(y).zip(k) {
+        *o = yi + h * ki;
+    }
+}
+
+#[cfg(feature = "math")]
+fn fixed_step(recorder: &mut Recorder, y0: &[f64], t0: f64, t1: f64, method: OdeMethod, control: &StepControl) -> Outcome {
+    let n = y0.len();
+    let h = control.step.unwrap_or((t1 - t0) / DEFAULT_FIXED_STEPS);
+    let steps = ((t1 - t0) / h - 1e-9).ceil().max(1.0) as usize;
+    if steps > control.max_steps {
+        let stopped = Some(format!("{} steps would be needed, more than the limit of {}", steps, control.max_steps));
+        return Outcome { rejected: 0, stopped };
+    }
+
+    let mut y = y0.to_vec();
+    let mut k = vec![vec![0.0; n]; 4];
+    let mut scratch = vec![0.0; n];
+    for i in 0..steps {
+        let t = t0 + i as f64 * h;
+        // The last step lands exactly on t1.
+        let h = if i + 1 == steps { t1 - t } else { h };
+        match method {
+            OdeMethod::Euler => {
+                recorder.eval(t, &y, &mut k[0]);
+                y.iter_mut().zip(&k[0]).for_each(|(yi, ki)| *yi += h * ki);
+            }
+            _ => {
+                recorder.eval(t, &y, &mut k[0]);
+                for (stage, fraction) in [0.5, 0.5, 1.0].into_iter().enumerate() {
+                    offset(&y, &k[stage], fraction * h, &mut scratch);
+                    recorder.eval(t + fraction * h, &scratch, &mut k[stage + 1]);
+                }
+                for j in 0..n {
+                    y[j] += h / 6.0 * (k[0][j] + 2.0 * k[1][j] + 2.0 * k[2][j] + k[3][j]);
+                }
+            }
+        }
+        let t_next = if i + 1 == steps { t1 } else { t + h };
+        if !y.iter().all(|v| v.is_finite()) {
+            let stopped = Some(format!("The solution stopped being finite at t = {}", t_next));
+            return Outcome { rejected: 0, stopped };
+        }
+        recorder.record(t_next, &y, h);
+    }
+    Outcome { rejected: 0, stopped: None }
+}
+
+/// Weighted RMS norm used by the step controller.
+#[cfg(feature = "math")]
+fn error_norm(error: &[f64], y: &[f64], y_new: &[f64], control: &StepControl) -> f64 {
+    let sum: f64 = error
+        .iter()
+        .zip(y.iter().zip(y_new))
+        .map(|(e, (a, b))| {
+            let scale = control.absolute_tolerance + control.relative_tolerance * a.abs().max(b.abs());
+            (e / scale).powi(2)
+        })
+        .sum();
+    (sum / error.len() as f64).sqrt()
+}
+
+#[cfg(feature = "math")]
+fn dormand_prince(recorder: &mut Recorder, y0: &[f64], t0: f64, t1: f64, control: &StepControl) -> Outcome {
+    let n = y0.len();
+    let mut y = y0.to_vec();
+    let mut t = t0;
+    let mut k = vec![vec![0.0; n]; 7];
+    let mut stage_y = vec![0.0; n];
+    let mut y_new = vec![0.0; n];
+    let mut error = vec![0.0; n];
+    let mut rejected = 0;
+    recorder.eval(t, &y, &mut k[0]);
+
+    let mut h = control.step.unwrap_or_else(|| initial_step(&y, &k[0], t1 - t0, control));
+    let mut last_rejected = false;
+    while t < t1 {
+        if recorder.trajectory.steps.len() + rejected >= control.max_steps {
+            let stopped = Some(format!("Stopped at t = {} after {} steps", t, control.max_steps));
+            return Outcome { rejected, stopped };
+        }
+        h = h.min(t1 - t);
+        if h <= f64::EPSILON * t.abs().max(1.0) {
+            // Usually the solution is blowing up, or the system is stiff.
+            let stopped = Some(format!("Step size underflow at t = {}", t));
+            return Outcome { rejected, stopped };
+        }
+
+        for stage in 1..7 {
+            for j in 0..n {
+                stage_y[j] = y[j] + h * (0..stage).map(|s| DP_A[stage][s] * k[s][j]).sum::<f64>();
+            }
+            recorder.eval(t + DP_C[stage] * h, &stage_y, &mut k[stage]);
+        }
+        // Stage 7 was evaluated at the fifth-order solution itself.
+        y_new.copy_from_slice(&stage_y);
+        for j in 0..n {
+            error[j] = h * (0..7).map(|s| DP_ERROR[s] * k[s][j]).sum::<f64>();
+        }
+        let norm = error_norm(&error, &y, &y_new, control);
+
+        let factor = if norm == 0.0 { STEP_MAX_FACTOR } else { STEP_SAFETY * norm.powf(-0.2) };
+        if norm <= 1.0 {
+            let taken = h;
+            t = if t1 - (t + h) <= f64::EPSILON * t1.abs().max(1.0) { t1 } else { t + h };
+            y.copy_from_slice(&y_new);
+            k.swap(0, 6);
+            recorder.record(t, &y, taken);
+            // Don't grow straight after a rejection.
+            let max_factor = if last_rejected { 1.0 } else { STEP_MAX_FACTOR };
+            h *= factor.clamp(STEP_MIN_FACTOR, max_factor);
+            last_rejected = false;
+        } else {
+            // A NaN norm (a stage left the function's domain) lands here
+            // too and takes the smallest factor.
+            rejected += 1;
+            last_rejected = true;
+            h *= if norm.is_nan() { STEP_MIN_FACTOR } else { factor.clamp(STEP_MIN_FACTOR, 1.0) };
+        }
+    }
+    Outcome { rejected, stopped: None }
+}
+
+/// A first trial step from the sizes of y and dy/dt (after Hairer, Nørsett
+/// and Wanner), capped at a tenth of the span.
+#[cfg(feature = "math")]
+fn initial_step(y: &[f64], dydt: &[f64], span: f64, control: &StepControl) -> f64 {
+    let scale = |v: f64| control.absolute_tolerance + control.relative_tolerance * v.abs();
+    let rms = |values: &[f64]| (values.iter().zip(y).map(|(v, yi)| (v / scale(*yi)).powi(2)).sum::<f64>() / y.len() as f64).sqrt();
+    let (d0, d1) = (rms(y), rms(dydt));
+    let h = if d0 < 1e-5 || d1 < 1e-5 { 1e-6 } else { 0.01 * d0 / d1 };
+    h.min(span / 10.0)
+}
+
+#[cfg(feature = "math")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OdePreset {
+    Lorenz,
+    VanDerPol,
+    Pendulum,
+    PredatorPrey,
+}
+
+/// Equations, parameters and a starting state for a preset.
+#[cfg(feature = "math")]
+pub struct PresetDefinition {
+    pub variables: &'static [&'static str],
+    pub equations: &'static [&'static str],
+    pub parameters: &'static [(&'static str, f64)],
+    pub initial: &'static [f64],
+}
+
+#[cfg(feature = "math")]
+impl OdePreset {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "lorenz" => Ok(OdePreset::Lorenz),
+            "van_der_pol" => Ok(OdePreset::VanDerPol),
+            "pendulum" => Ok(OdePreset::Pendulum),
+            "predator_prey" | "lotka_volterra" => Ok(OdePreset::PredatorPrey),
+            _ => Err(format!("Unknown ODE preset: {}", name)),
+        }
+    }
+
+    pub fn definition(&self) -> PresetDefinition {
+        match self {
+            OdePreset::Lorenz => PresetDefinition {
+                variables: &["x", "y", "z"],
+                equations: &["sigma * (y - x)", "x * (rho - z) - y", "x * y - beta * z"],
+                parameters: &[("sigma", 10.0), ("rho", 28.0), ("beta", 8.0 / 3.0)],
+                initial: &[1.0, 1.0, 1.0],
+            },
+            OdePreset::VanDerPol => PresetDefinition {
+                variables: &["x", "v"],
+                equations: &["v", "mu * (1 - x^2) * v - x"],
+                parameters: &[("mu", 1.0)],
+                initial: &[2.0, 0.0],
+            },
+            OdePreset::Pendulum => PresetDefinition {
+                variables: &["theta", "omega"],
+                equations: &["omega", "-(g / length) * sin(theta) - damping * omega"],
+                parameters: &[("g", 9.81), ("length", 1.0), ("damping", 0.0)],
+                initial: &[2.5, 0.0],
+            },
+            OdePreset::PredatorPrey => PresetDefinition {
+                variables: &["prey", "predator"],
+                equations: &["alpha * prey - beta * prey * predator", "delta * prey * predator - gamma * predator"],
+                parameters: &[("alpha", 1.1), ("beta", 0.4), ("delta", 0.1), ("gamma", 0.4)],
+                initial: &[10.0, 10.0],
+            },
+        }
+    }
+}
+
+/// `d(variables[i])/dt = equations[i]`. Other free names in the equations
+/// are parameters; `t` is time.
+#[cfg(feature = "math")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct OdeSystem {
+    pub variables: Vec<String>,
+    pub equations: Vec<Expr>,
+    pub parameters: BTreeMap<String, Option<f64>>,
+}
+
+#[cfg(feature = "math")]
+impl OdeSystem {
+    /// Parse errors carry the index of the offending equation.
+    pub fn new(variables: &[String], equations: &[String]) -> Result<Self, (usize, ParseError)> {
+        let parsed = equations
+            .iter()
+            .enumerate()
+            .map(|(i, source)| parse_expression_source(source).map_err(|e| (i, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut parameters = BTreeMap::new();
+        for name in parsed.iter().flat_map(|e| e.variables()) {
+            if name != "t" && !variables.contains(&name) {
+                parameters.insert(name, None);
+            }
+        }
+        Ok(OdeSystem { variables: variables.to_vec(), equations: parsed, parameters })
+    }
+
+    pub fn preset(preset: OdePreset) -> Self {
+        let definition = preset.definition();
+        let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let mut system = OdeSystem::new(&strings(definition.variables), &strings(definition.equations))
+            .expect("preset equations parse");
+        for (name, value) in definition.parameters {
+            system.parameters.insert(name.to_string(), Some(*value));
+        }
+        system
+    }
+
+    fn check_shape(&self) -> Result<(), String> {
+        if self.variables.is_empty() || self.variables.len() != self.equations.len() {
+            return Err(format!("Need one equation per variable: {} variables, {} equations", self.variables.len(), self.equations.len()));
+        }
+        if self.variables.iter().any(|v| v == "t") {
+            return Err("'t' is reserved for time".to_string());
+        }
+        // The parser reads these names as constants, so the variable could
+        // never be referenced.
+        if let Some(name) = self.variables.iter().find(|v| MathConstant::from_name(v).is_some()) {
+            return Err(format!("'{}' is a constant and cannot be a variable", name));
+        }
+        for (i, name) in self.variables.iter().enumerate() {
+            if self.variables[..i].contains(name) {
+                return Err(format!("Variable '{}' is listed more than once", name));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn derivative(&self, t: f64, y: &[f64], out: &mut [f64]) {
+        let lookup = |name: &str| {
+            if name == "t" {
+                return Some(t);
+            }
+            match self.variables.iter().position(|v| v == name) {
+                Some(i) => Some(y[i]),
+                None => self.parameters.get(name).copied().flatten(),
+            }
+        };
+        for (value, equation) in out.iter_mut().zip(&self.equations) {
+            *value = equation.eval_with(&lookup).unwrap_or(f64::NAN);
+        }
+    }
+
+    pub fn solve(&self, y0: &[f64], t0: f64, t1: f64, method: OdeMethod, control: &StepControl) -> Result<Trajectory, String> {
+        self.check_shape()?;
+        if let Some((name, _)) = self.parameters.iter().find(|(_, v)| v.is_none()) {
+            return Err(format!("Parameter '{}' has no value", name));
+        }
+        if y0.len() != self.variables.len() {
+            return Err(format!("Expected {} initial values, got {}", self.variables.len(), y0.len()));
+        }
+        integrate_ode(&|t, y, out| self.derivative(t, y, out), y0, t0, t1, method, control)
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize)]
+struct EquationParseError {
+    equation: usize,
+    #[serde(flatten)]
+    error: ParseError,
+}
+
+/// A system of ODEs with parameter values and a starting state, ready to
+/// solve repeatedly.
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub struct OdeSolver {
+    system: OdeSystem,
+    initial: Vec<f64>,
+}
+
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+impl OdeSolver {
+    /// `equations[i]` is the derivative of `variables[i]`. Any other names
+    /// become parameters to set before solving. Parse errors are thrown as
+    /// `{ message, span, equation }`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(variables: Vec<String>, equations: Vec<String>) -> Result<OdeSolver, JsValue> {
+        let system = OdeSystem::new(&variables, &equations)
+            .map_err(|(equation, error)| serde_wasm_bindgen::to_value(&EquationParseError { equation, error }).unwrap())?;
+        system.check_shape().map_err(|e| JsValue::from_str(&e))?;
+        let initial = vec![0.0; variables.len()];
+        Ok(OdeSolver { system, initial })
+    }
+
+    /// "lorenz", "van_der_pol", "pendulum" or "predator_prey", with its
+    /// usual parameters and starting state.
+    pub fn preset(name: &str) -> Result<OdeSolver, JsValue> {
+        let preset = OdePreset::parse(name).map_err(|e| JsValue::from_str(&e))?;
+        Ok(OdeSolver { system: OdeSystem::preset(preset), initial: preset.definition().initial.to_vec() })
+    }
+
+    pub fn variables(&self) -> Vec<String> {
+        self.system.variables.clone()
+    }
+
+    /// The equations as parsed, in normalized form.
+    pub fn equations(&self) -> Vec<String> {
+        self.system.equations.iter().map(|e| e.to_string()).collect()
+    }
+
+    /// Parameter names mapped to values, or null when unset.
+    pub fn parameters(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.system.parameters).unwrap()
+    }
+
+    pub fn set_parameter(&mut self, name: &str, value: f64) -> Result<(), JsValue> {
+        match self.system.parameters.get_mut(name) {
+            Some(slot) => {
+                *slot = Some(value);
+                Ok(())
+            }
+            None => Err(JsValue::from_str(&format!("'{}' is not a parameter of this system", name))),
+        }
+    }
+
+    pub fn initial(&self) -> Vec<f64> {
+        self.initial.clone()
+    }
+
+    pub fn set_initial(&mut self, values: &[f64]) -> Result<(), JsValue> {
+        if values.len() != self.system.variables.len() {
+            return Err(JsValue::from_str(&format!("Expected {} initial values, got {}", self.system.variables.len(), values.len())));
+        }
+        self.initial = values.to_vec();
+        Ok(())
+    }
+
+    /// Solves from `t_start` to `t_end` with "euler", "rk4" or "rk45".
+    /// `step` is the fixed step, or RK45's first trial step; `tolerance` is
+    /// RK45's relative tolerance, with an absolute tolerance 1000x smaller.
+    pub fn solve(
+        &self,
+        method: &str,
+        t_start: f64,
+        t_end: f64,
+        step: Option<f64>,
+        tolerance: Option<f64>,
+        max_steps: Option<u32>,
+    ) -> Result<OdeTrajectory, JsValue> {
+        let method = OdeMethod::parse(method).map_err(|e| JsValue::from_str(&e))?;
+        let tolerance = tolerance.unwrap_or(DEFAULT_ODE_TOLERANCE);
+        let control = StepControl {
+            step,
+            relative_tolerance: tolerance,
+            absolute_tolerance: tolerance * 1e-3,
+            max_steps: max_steps.map(|n| n as usize).unwrap_or(DEFAULT_MAX_STEPS),
+        };
+        let trajectory = self
+            .system
+            .solve(&self.initial, t_start, t_end, method, &control)
+            .map_err(|e| JsValue::from_str(&e))?;
+        Ok(OdeTrajectory { trajectory, variables: self.system.variables.clone() })
+    }
+}
+
+/// The result of `OdeSolver.solve`, with buffers ready for plotting.
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub struct OdeTrajectory {
+    trajectory: Trajectory,
+    variables: Vec<String>,
+}
+
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+impl OdeTrajectory {
+    pub fn len(&self) -> usize {
+        self.trajectory.times.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trajectory.times.is_empty()
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.trajectory.dimension
+    }
+
+    pub fn variables(&self) -> Vec<String> {
+        self.variables.clone()
+    }
+
+    pub fn times(&self) -> js_sys::Float64Array {
+        js_sys::Float64Array::from(&self.trajectory.times[..])
+    }
+
+    /// All states, `dimension()` values per time.
+    pub fn states(&self) -> js_sys::Float64Array {
+        js_sys::Float64Array::from(&self.trajectory.states[..])
+    }
+
+    /// One variable over time, by index or name.
+    pub fn component(&self, variable: JsValue) -> Result<js_sys::Float64Array, JsValue> {
+        let index = match variable.as_string() {
+            Some(name) => self.variables.iter().position(|v| *v == name),
+            None => variable.as_f64().map(|i| i as usize).filter(|&i| i < self.trajectory.dimension),
+        }
+        .ok_or_else(|| JsValue::from_str("No such variable"))?;
+        Ok(js_sys::Float64Array::from(&self.trajectory.component(index)[..]))
+    }
+
+    pub fn step_sizes(&self) -> js_sys::Float64Array {
+        js_sys::Float64Array::from(&self.trajectory.steps[..])
+    }
+
+    pub fn statistics(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.trajectory.statistics).unwrap()
+    }
+}
+
+#[cfg(feature = "math")]
+impl OdeTrajectory {
+    pub fn trajectory(&self) -> &Trajectory {
+        &self.trajectory
+    }
+}
+
+#[cfg(all(test, feature = "math"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_non_positive_tolerances() {
+        let decay = |_: f64, y: &[f64], dy: &mut [f64]| dy[0] = -y[0];
+        for tolerance in [0.0, -1e-6, f64::NAN, f64::INFINITY] {
+            let control = StepControl { relative_tolerance: tolerance, absolute_tolerance: tolerance * 1e-3, ..StepControl::default() };
+            assert!(integrate_ode(&decay, &[1.0], 0.0, 1.0, OdeMethod::Rk45, &control).is_err(), "tolerance {}", tolerance);
+        }
+        let result = integrate_ode(&decay, &[1.0], 0.0, 1.0, OdeMethod::Rk45, &StepControl::default()).unwrap();
+        assert!((result.state(result.times.len() - 1)[0] - (-1f64).exp()).abs() < 1e-6);
+    }
+
+    fn last(trajectory: &Trajectory) -> &[f64] {
+        trajectory.state(trajectory.times.len() - 1)
+    }
+
+    #[test]
+    fn solvers_match_exponential_decay() {
+        let decay = |_: f64, y: &[f64], dy: &mut [f64]| dy[0] = -0.5 * y[0];
+        let exact = 3.0 * (-0.5 * 4.0f64).exp();
+        let error = |method, step| {
+            let control = StepControl { step: Some(step), ..StepControl::default() };
+            let trajectory = integrate_ode(&decay, &[3.0], 0.0, 4.0, method, &control).unwrap();
+            assert!(trajectory.statistics.completed);
+            (last(&trajectory)[0] - exact).abs()
+        };
+        // Halving the step shrinks the error by about 2^order.
+        assert!(error(OdeMethod::Euler, 0.01) < 1e-2);
+        assert!((error(OdeMethod::Euler, 0.02) / error(OdeMethod::Euler, 0.01) - 2.0).abs() < 0.1);
+        assert!(error(OdeMethod::Rk4, 0.1) < 1e-6);
+        assert!((error(OdeMethod::Rk4, 0.2) / error(OdeMethod::Rk4, 0.1) - 16.0).abs() < 1.0);
+        assert!(error(OdeMethod::Rk45, 0.1) < 1e-6);
+    }
+
+    #[test]
+    fn solvers_conserve_harmonic_oscillator_energy() {
+        // x'' = -x from (1, 0) is (cos t, -sin t), with energy 1/2 throughout.
+        let oscillator = |_: f64, y: &[f64], dy: &mut [f64]| {
+            dy[0] = y[1];
+            dy[1] = -y[0];
+        };
+        let t1 = 10.0 * std::f64::consts::PI;
+        for (method, step) in [(OdeMethod::Rk4, Some(0.01)), (OdeMethod::Rk45, None)] {
+            let control = StepControl { step, ..StepControl::default() };
+            let trajectory = integrate_ode(&oscillator, &[1.0, 0.0], 0.0, t1, method, &control).unwrap();
+            assert!(trajectory.statistics.completed);
+            for (i, &t) in trajectory.times.iter().enumerate() {
+                let state = trajectory.state(i);
+                assert!((state[0] - t.cos()).abs() < 1e-4, "{:?} x at t = {}", method, t);
+                assert!((state[1] + t.sin()).abs() < 1e-4, "{:?} v at t = {}", method, t);
+                let energy = 0.5 * (state[0] * state[0] + state[1] * state[1]);
+                assert!((energy - 0.5).abs() < 1e-4, "{:?} energy at t = {}", method, t);
+            }
+            assert_eq!(*trajectory.times.last().unwrap(), t1);
+        }
+    }
+
+    #[test]
+    fn systems_reject_ambiguous_variables() {
+        let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let shape = |variables: &[&str], equations: &[&str]| {
+            OdeSystem::new(&strings(variables), &strings(equations)).unwrap().check_shape()
+        };
+        assert!(shape(&["x", "x"], &["1", "2"]).is_err());
+        for constant in ["e", "pi", "tau"] {
+            assert!(shape(&[constant], &["1"]).is_err(), "{}", constant);
+        }
+        assert!(shape(&["t"], &["1"]).is_err());
+        assert!(shape(&["x", "y"], &["y", "-x"]).is_ok());
+    }
+}
@@ -0,0 +1,484 @@
+#[cfg(feature = "random")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "random")]
+use rand::prelude::*;
+#[cfg(feature = "random")]
+use serde::Serialize;
+
+/// Minimum WCAG AA contrast for body text.
+#[cfg(feature = "random")]
+pub const WCAG_AA_TEXT: f64 = 4.5;
+
+/// An sRGB color with gamma-encoded channels in [0, 1].
+#[cfg(feature = "random")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rgb {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+}
+
+#[cfg(feature = "random")]
+impl Rgb {
+    pub fn new(r: f64, g: f64, b: f64) -> Self {
+        Rgb { r, g, b }
+    }
+
+    /// Parses "#rrggbb", "rrggbb" or the short "#rgb" form.
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        let digits = hex.trim().trim_start_matches('#');
+        let expanded: String = match digits.len() {
+            3 => digits.chars().flat_map(|c| [c, c]).collect(),
+            6 => digits.to_string(),
+            _ => return Err(format!("Invalid hex color: {}", hex)),
+        };
+        let value = u32::from_str_radix(&expanded, 16).map_err(|_| format!("Invalid hex color: {}", hex))?;
+        Ok(Rgb::new(
+            ((value >> 16) & 0xff) as f64 / 255.0,
+            ((value >> 8) & 0xff) as f64 / 255.0,
+            (value & 0xff) as f64 / 255.0,
+        ))
+    }
+
+    pub fn to_hex(self) -> String {
+        let channel = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        format!("#{:02x}{:02x}{:02x}", channel(self.r), channel(self.g), channel(self.b))
+    }
+
+    /// Rounds each channel to 8 bits, as the color will be displayed.
+    pub fn quantize(self) -> Self {
+        let channel = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() / 255.0;
+        Rgb::new(channel(self.r), channel(self.g), channel(self.b))
+    }
+
+    fn in_gamut(self) -> bool {
+        const EPSILON: f64 = 1e-6;
+        [self.r, self.g, self.b].iter().all(|v| (-EPSILON..=1.0 + EPSILON).contains(v))
+    }
+}
+
+/// Hue in degrees, saturation and lightness in [0, 1].
+#[cfg(feature = "random")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hsl {
+    pub h: f64,
+    pub s: f64,
+    pub l: f64,
+}
+
+#[cfg(feature = "random")]
+impl Hsl {
+    pub fn from_rgb(rgb: Rgb) -> Self {
+        let max = rgb.r.max(rgb.g).max(rgb.b);
+        let min = rgb.r.min(rgb.g).min(rgb.b);
+        let l = (max + min) / 2.0;
+        let delta = max - min;
+        if delta == 0.0 {
+            return Hsl { h: 0.0, s: 0.0, l };
+        }
+
+        let s = delta / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == rgb.r {
+            60.0 * ((rgb.g - rgb.b) / delta).rem_euclid(6.0)
+        } else if max == rgb.g {
+            60.0 * ((rgb.b - rgb.r) / delta + 2.0)
+        } else {
+            60.0 * ((rgb.r - rgb.g) / delta + 4.0)
+        };
+        Hsl { h, s, l }
+    }
+
+    pub fn to_rgb(self) -> Rgb {
+        let c = (1.0 - (2.0 * self.l - 1.0).abs()) * self.s;
+        let h = self.h.rem_euclid(360.0) / 60.0;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = self.l - c / 2.0;
+        Rgb::new(r + m, g + m, b + m)
+    }
+}
+
+/// OKLCH: perceptual lightness in [0, 1], chroma and hue in degrees.
+#[cfg(feature = "random")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Oklch {
+    pub l: f64,
+    pub c: f64,
+    pub h: f64,
+}
+
+#[cfg(feature = "random")]
+fn srgb_to_linear(v: f64) -> f64 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[cfg(feature = "random")]
+fn linear_to_srgb(v: f64) -> f64 {
+    if v <= 0.003_130_8 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(feature = "random")]
+impl Oklch {
+    pub fn from_rgb(rgb: Rgb) -> Self {
+        let (r, g, b) = (srgb_to_linear(rgb.r), srgb_to_linear(rgb.g), srgb_to_linear(rgb.b));
+        let l = (0.412_221_470_8 * r + 0.536_332_536_3 * g + 0.051_445_992_9 * b).cbrt();
+        let m = (0.211_903_498_2 * r + 0.680_699_545_1 * g + 0.107_396_956_6 * b).cbrt();
+        let s = (0.088_302_461_9 * r + 0.281_718_837_6 * g + 0.629_978_700_5 * b).cbrt();
+
+        let lightness = 0.210_454_255_3 * l + 0.793_617_785_0 * m - 0.004_072_046_8 * s;
+        let a = 1.977_998_495_1 * l - 2.428_592_205_0 * m + 0.450_593_709_9 * s;
+        let b = 0.025_904_037_1 * l + 0.782_771_766_2 * m - 0.808_675_766_0 * s;
+
+        Oklch { l: lightness, c: a.hypot(b), h: b.atan2(a).to_degrees().rem_euclid(360.0) }
+    }
+
+    /// Converts without clamping; the result may fall outside sRGB.
+    pub fn to_rgb_unclamped(self) -> Rgb {
+        let (a, b) = (self.c * self.h.to_radians().cos(), self.c * self.h.to_radians().sin());
+        let l = (self.l + 0.396_337_777_4 * a + 0.215_803_757_3 * b).powi(3);
+        let m = (self.l - 0.105_561_345_8 * a - 0.063_854_172_8 * b).powi(3);
+        let s = (self.l - 0.089_484_177_5 * a - 1.291_485_548_0 * b).powi(3);
+
+        Rgb::new(
+            linear_to_srgb(4.076_741_662_1 * l - 3.307_711_591_3 * m + 0.230_969_929_2 * s),
+            linear_to_srgb(-1.268_438_004_6 * l + 2.609_757_401_1 * m - 0.341_319_396_5 * s),
+            linear_to_srgb(-0.004_196_086_3 * l - 0.703_418_614_7 * m + 1.707_614_701_0 * s),
+        )
+    }
+
+    /// Maps into sRGB by reducing chroma, keeping lightness and hue.
+    pub fn to_rgb(self) -> Rgb {
+        let color = Oklch { l: self.l.clamp(0.0, 1.0), ..self };
+        if color.to_rgb_unclamped().in_gamut() {
+            return clamp_rgb(color.to_rgb_unclamped());
+        }
+        let (mut low, mut high) = (0.0, color.c);
+        for _ in 0..24 {
+            let mid = (low + high) / 2.0;
+            if (Oklch { c: mid, ..color }).to_rgb_unclamped().in_gamut() {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        clamp_rgb(Oklch { c: low, ..color }.to_rgb_unclamped())
+    }
+}
+
+#[cfg(feature = "random")]
+fn clamp_rgb(rgb: Rgb) -> Rgb {
+    Rgb::new(rgb.r.clamp(0.0, 1.0), rgb.g.clamp(0.0, 1.0), rgb.b.clamp(0.0, 1.0))
+}
+
+/// WCAG 2 relative luminance.
+#[cfg(feature = "random")]
+pub fn relative_luminance(rgb: Rgb) -> f64 {
+    0.2126 * srgb_to_linear(rgb.r) + 0.7152 * srgb_to_linear(rgb.g) + 0.0722 * srgb_to_linear(rgb.b)
+}
+
+/// WCAG 2 contrast ratio, from 1 (identical) to 21 (black on white).
+#[cfg(feature = "random")]
+pub fn contrast_ratio(a: Rgb, b: Rgb) -> f64 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+#[cfg(feature = "random")]
+pub fn wcag_level(ratio: f64) -> &'static str {
+    if ratio >= 7.0 {
+        "AAA"
+    } else if ratio >= 4.5 {
+        "AA"
+    } else if ratio >= 3.0 {
+        "AA Large"
+    } else {
+        "Fail"
+    }
+}
+
+/// Picks a text color for `background` reaching `min_ratio`. Prefers a
+/// shade of the background's own hue, moving lightness as little as
+/// possible, and falls back to black or white.
+#[cfg(feature = "random")]
+pub fn accessible_text_color(background: Rgb, min_ratio: f64) -> Rgb {
+    let base = Oklch::from_rgb(background);
+    let mut best: Option<(f64, Rgb)> = None;
+
+    for direction in [-1.0, 1.0] {
+        let mut lightness = base.l;
+        while (0.0..=1.0).contains(&lightness) {
+            let candidate = Oklch { l: lightness, ..base }.to_rgb().quantize();
+            if contrast_ratio(candidate, background) >= min_ratio {
+                let distance = (lightness - base.l).abs();
+                if best.is_none_or(|(d, _)| distance < d) {
+                    best = Some((distance, candidate));
+                }
+                break;
+            }
+            lightness += direction * 0.01;
+        }
+    }
+
+    best.map(|(_, color)| color).unwrap_or_else(|| {
+        let (black, white) = (Rgb::new(0.0, 0.0, 0.0), Rgb::new(1.0, 1.0, 1.0));
+        if contrast_ratio(black, background) >= contrast_ratio(white, background) {
+            black
+        } else {
+            white
+        }
+    })
+}
+
+#[cfg(feature = "random")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaletteScheme {
+    Complementary,
+    Analogous,
+    Triadic,
+    Tetradic,
+    Monochrome,
+}
+
+#[cfg(feature = "random")]
+impl PaletteScheme {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "complementary" => Ok(PaletteScheme::Complementary),
+            "analogous" => Ok(PaletteScheme::Analogous),
+            "triadic" => Ok(PaletteScheme::Triadic),
+            "tetradic" => Ok(PaletteScheme::Tetradic),
+            "monochrome" | "monochromatic" => Ok(PaletteScheme::Monochrome),
+            _ => Err(format!("Unknown palette scheme: {}", name)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PaletteScheme::Complementary => "complementary",
+            PaletteScheme::Analogous => "analogous",
+            PaletteScheme::Triadic => "triadic",
+            PaletteScheme::Tetradic => "tetradic",
+            PaletteScheme::Monochrome => "monochrome",
+        }
+    }
+
+    fn hue_offsets(self) -> &'static [f64] {
+        match self {
+            PaletteScheme::Complementary => &[0.0, 180.0],
+            PaletteScheme::Analogous => &[-60.0, -30.0, 0.0, 30.0, 60.0],
+            PaletteScheme::Triadic => &[0.0, 120.0, 240.0],
+            PaletteScheme::Tetradic => &[0.0, 90.0, 180.0, 270.0],
+            PaletteScheme::Monochrome => &[0.0],
+        }
+    }
+}
+
+#[cfg(feature = "random")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorSpace {
+    Hsl,
+    Oklch,
+}
+
+#[cfg(feature = "random")]
+impl ColorSpace {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "hsl" => Ok(ColorSpace::Hsl),
+            "oklch" => Ok(ColorSpace::Oklch),
+            _ => Err(format!("Unknown color space: {}", name)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorSpace::Hsl => "hsl",
+            ColorSpace::Oklch => "oklch",
+        }
+    }
+}
+
+/// Builds the scheme's colors from `base` by rotating hue, or for
+/// monochrome by stepping lightness, in the chosen color space.
+#[cfg(feature = "random")]
+pub fn scheme_colors(base: Rgb, scheme: PaletteScheme, space: ColorSpace) -> Vec<Rgb> {
+    const HSL_STEPS: [f64; 5] = [0.2, 0.35, 0.5, 0.65, 0.8];
+    const OKLCH_STEPS: [f64; 5] = [0.35, 0.5, 0.65, 0.78, 0.9];
+
+    match (scheme, space) {
+        (PaletteScheme::Monochrome, ColorSpace::Hsl) => {
+            let hsl = Hsl::from_rgb(base);
+            HSL_STEPS.iter().map(|&l| Hsl { l, ..hsl }.to_rgb()).collect()
+        }
+        (PaletteScheme::Monochrome, ColorSpace::Oklch) => {
+            let lch = Oklch::from_rgb(base);
+            OKLCH_STEPS.iter().map(|&l| Oklch { l, ..lch }.to_rgb()).collect()
+        }
+        (_, ColorSpace::Hsl) => {
+            let hsl = Hsl::from_rgb(base);
+            scheme.hue_offsets().iter().map(|o| Hsl { h: (hsl.h + o).rem_euclid(360.0), ..hsl }.to_rgb()).collect()
+        }
+        (_, ColorSpace::Oklch) => {
+            let lch = Oklch::from_rgb(base);
+            scheme.hue_offsets().iter().map(|o| Oklch { h: (lch.h + o).rem_euclid(360.0), ..lch }.to_rgb()).collect()
+        }
+    }
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct Swatch {
+    pub hex: String,
+    pub hsl: [f64; 3],
+    pub oklch: [f64; 3],
+    pub text_color: String,
+    pub text_contrast: f64,
+    pub text_level: String,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct ContrastPair {
+    pub a: usize,
+    pub b: usize,
+    pub ratio: f64,
+    pub level: String,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct Palette {
+    pub scheme: String,
+    pub space: String,
+    pub base: String,
+    pub swatches: Vec<Swatch>,
+    pub contrast: Vec<ContrastPair>,
+}
+
+#[cfg(feature = "random")]
+fn round_to(value: f64, places: i32) -> f64 {
+    let factor = 10f64.powi(places);
+    (value * factor).round() / factor
+}
+
+#[cfg(feature = "random")]
+impl Palette {
+    pub fn generate(base: Rgb, scheme: PaletteScheme, space: ColorSpace) -> Self {
+        // Quantize first so reported values match the exported hex colors.
+        let colors: Vec<Rgb> = scheme_colors(base, scheme, space).into_iter().map(Rgb::quantize).collect();
+
+        let swatches = colors
+            .iter()
+            .map(|&color| {
+                let hsl = Hsl::from_rgb(color);
+                let lch = Oklch::from_rgb(color);
+                let text = accessible_text_color(color, WCAG_AA_TEXT);
+                let text_contrast = contrast_ratio(color, text);
+                Swatch {
+                    hex: color.to_hex(),
+                    hsl: [round_to(hsl.h, 1), round_to(hsl.s, 3), round_to(hsl.l, 3)],
+                    oklch: [round_to(lch.l, 3), round_to(lch.c, 3), round_to(lch.h, 1)],
+                    text_color: text.to_hex(),
+                    text_contrast: round_to(text_contrast, 2),
+                    text_level: wcag_level(text_contrast).to_string(),
+                }
+            })
+            .collect();
+
+        let mut contrast = Vec::new();
+        for a in 0..colors.len() {
+            for b in a + 1..colors.len() {
+                let ratio = contrast_ratio(colors[a], colors[b]);
+                contrast.push(ContrastPair { a, b, ratio: round_to(ratio, 2), level: wcag_level(ratio).to_string() });
+            }
+        }
+
+        Palette {
+            scheme: scheme.name().to_string(),
+            space: space.name().to_string(),
+            base: base.to_hex(),
+            swatches,
+            contrast,
+        }
+    }
+
+    /// Emits a `:root` block with `--{prefix}-N` and `--{prefix}-N-text`.
+    pub fn to_css(&self, prefix: &str) -> String {
+        let mut css = String::from(":root {\n");
+        for (i, swatch) in self.swatches.iter().enumerate() {
+            css.push_str(&format!("  --{}-{}: {};\n", prefix, i + 1, swatch.hex));
+            css.push_str(&format!("  --{}-{}-text: {};\n", prefix, i + 1, swatch.text_color));
+        }
+        css.push_str("}\n");
+        css
+    }
+}
+
+#[cfg(feature = "random")]
+fn random_base_color<R: Rng>(rng: &mut R) -> Rgb {
+    Hsl { h: rng.gen_range(0.0..360.0), s: rng.gen_range(0.55..0.85), l: rng.gen_range(0.45..0.6) }.to_rgb()
+}
+
+#[cfg(feature = "random")]
+fn build_palette(scheme: &str, space: &str, base_color: Option<String>, seed: Option<u32>) -> Result<Palette, String> {
+    let scheme = PaletteScheme::parse(scheme)?;
+    let space = ColorSpace::parse(space)?;
+    let base = match base_color {
+        Some(hex) => Rgb::from_hex(&hex)?,
+        None => random_base_color(&mut super::random::seeded_rng(seed)),
+    };
+    Ok(Palette::generate(base, scheme, space))
+}
+
+/// `scheme` is complementary, analogous, triadic, tetradic or monochrome;
+/// `space` is "hsl" or "oklch". Without `base_color` a random one is used.
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn generate_palette(scheme: &str, space: &str, base_color: Option<String>, seed: Option<u32>) -> Result<JsValue, JsValue> {
+    let palette = build_palette(scheme, space, base_color, seed).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&palette).unwrap())
+}
+
+/// Same palette as `generate_palette`, exported as "css" or "json" text.
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn export_palette(
+    scheme: &str,
+    space: &str,
+    base_color: Option<String>,
+    seed: Option<u32>,
+    format: &str,
+    prefix: Option<String>,
+) -> Result<String, JsValue> {
+    let palette = build_palette(scheme, space, base_color, seed).map_err(|e| JsValue::from_str(&e))?;
+    match format {
+        "css" => Ok(palette.to_css(prefix.as_deref().unwrap_or("palette"))),
+        "json" => Ok(serde_json::to_string_pretty(&palette).unwrap()),
+        _ => Err(JsValue::from_str(&format!("Unknown export format: {}", format))),
+    }
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn color_contrast(foreground: &str, background: &str) -> Result<JsValue, JsValue> {
+    let fg = Rgb::from_hex(foreground).map_err(|e| JsValue::from_str(&e))?;
+    let bg = Rgb::from_hex(background).map_err(|e| JsValue::from_str(&e))?;
+    let ratio = contrast_ratio(fg, bg);
+    let pair = ContrastPair { a: 0, b: 1, ratio: round_to(ratio, 2), level: wcag_level(ratio).to_string() };
+    Ok(serde_wasm_bindgen::to_value(&pair).unwrap())
+}
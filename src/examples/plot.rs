@@ -0,0 +1,1101 @@
+#[cfg(feature = "math")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "math")]
+use wasm_bindgen::JsCast;
+#[cfg(feature = "math")]
+use serde::Serialize;
+#[cfg(feature = "math")]
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, MouseEvent};
+#[cfg(feature = "math")]
+use std::cell::Cell;
+#[cfg(feature = "math")]
+use super::expression::{parse_error_to_js, parse_expression_source, BinaryOp, Expr, ParseError, Span};
+
+// Space around the plot area for tick labels, in canvas pixels.
+#[cfg(feature = "math")]
+const MARGIN_LEFT: f64 = 56.0;
+#[cfg(feature = "math")]
+const MARGIN_RIGHT: f64 = 16.0;
+#[cfg(feature = "math")]
+const MARGIN_TOP: f64 = 16.0;
+#[cfg(feature = "math")]
+const MARGIN_BOTTOM: f64 = 32.0;
+
+// Rough advance of one 12px sans-serif glyph. TextMetrics isn't among the
+// enabled web-sys bindings, so label widths are estimated.
+#[cfg(feature = "math")]
+const LABEL_CHAR_WIDTH: f64 = 7.0;
+
+#[cfg(feature = "math")]
+const SERIES_COLORS: [&str; 8] = ["#1f77b4", "#d62728", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b", "#e377c2", "#17becf"];
+
+/// Implicit curves are traced on a grid with cells this many pixels wide.
+#[cfg(feature = "math")]
+const CONTOUR_CELL_PX: f64 = 4.0;
+
+/// The visible region in data coordinates.
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub x_min: f64,
+    pub x_max: f64,
+    pub y_min: f64,
+    pub y_max: f64,
+}
+
+#[cfg(feature = "math")]
+impl Viewport {
+    pub fn new(x_min: f64, x_max: f64, y_min: f64, y_max: f64) -> Result<Self, String> {
+        let view = Viewport { x_min, x_max, y_min, y_max };
+        // The extents can overflow even when every bound is finite.
+        if !(view.width().is_finite() && view.height().is_finite()) || x_min >= x_max || y_min >= y_max {
+            return Err("Viewport bounds must be finite with min < max".to_string());
+        }
+        Ok(view)
+    }
+
+    pub fn width(&self) -> f64 {
+        self.x_max - self.x_min
+    }
+
+    pub fn height(&self) -> f64 {
+        self.y_max - self.y_min
+    }
+
+    /// Scales the view by `factor` (above 1 zooms in) about the data point
+    /// `(x, y)`, which stays fixed on screen.
+    pub fn zoom_about(&mut self, x: f64, y: f64, factor: f64) {
+        let width = self.width() / factor;
+        let height = self.height() / factor;
+        if !(width > 1e-12 && width < 1e12 && height > 1e-12 && height < 1e12) {
+            return;
+        }
+        self.x_min = x - (x - self.x_min) / factor;
+        self.x_max = self.x_min + width;
+        self.y_min = y - (y - self.y_min) / factor;
+        self.y_max = self.y_min + height;
+    }
+
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        self.x_min += dx;
+        self.x_max += dx;
+        self.y_min += dy;
+        self.y_max += dy;
+    }
+}
+
+/// The pixel rectangle the viewport is mapped onto. Screen y grows downward.
+#[cfg(feature = "math")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlotArea {
+    pub left: f64,
+    pub top: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[cfg(feature = "math")]
+impl PlotArea {
+    /// The canvas minus the label margins.
+    pub fn for_canvas(width: f64, height: f64) -> Self {
+        PlotArea {
+            left: MARGIN_LEFT,
+            top: MARGIN_TOP,
+            width: (width - MARGIN_LEFT - MARGIN_RIGHT).max(1.0),
+            height: (height - MARGIN_TOP - MARGIN_BOTTOM).max(1.0),
+        }
+    }
+
+    pub fn to_screen(&self, view: &Viewport, x: f64, y: f64) -> [f64; 2] {
+        [
+            self.left + (x - view.x_min) / view.width() * self.width,
+            self.top + (view.y_max - y) / view.height() * self.height,
+        ]
+    }
+
+    pub fn to_data(&self, view: &Viewport, px: f64, py: f64) -> [f64; 2] {
+        [
+            view.x_min + (px - self.left) / self.width * view.width(),
+            view.y_max - (py - self.top) / self.height * view.height(),
+        ]
+    }
+
+    /// Data units per pixel along each axis.
+    pub fn pixel_size(&self, view: &Viewport) -> [f64; 2] {
+        [view.width() / self.width, view.height() / self.height]
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct AxisTicks {
+    pub step: f64,
+    pub values: Vec<f64>,
+    pub labels: Vec<String>,
+}
+
+/// Rounds `x` to 1, 2 or 5 times a power of ten (Heckbert's "nice numbers").
+#[cfg(feature = "math")]
+fn nice_number(x: f64, round: bool) -> f64 {
+    let exponent = x.log10().floor();
+    let fraction = x / 10f64.powf(exponent);
+    let nice = match (round, fraction) {
+        (true, f) if f < 1.5 => 1.0,
+        (true, f) if f < 3.0 => 2.0,
+        (true, f) if f < 7.0 => 5.0,
+        (false, f) if f <= 1.0 => 1.0,
+        (false, f) if f <= 2.0 => 2.0,
+        (false, f) if f <= 5.0 => 5.0,
+        _ => 10.0,
+    };
+    nice * 10f64.powf(exponent)
+}
+
+/// Tick positions within `[min, max]` at a 1/2/5 step giving roughly
+/// `target` ticks, labelled with just enough decimals to tell them apart.
+#[cfg(feature = "math")]
+pub fn nice_ticks(min: f64, max: f64, target: usize) -> AxisTicks {
+    if !(min.is_finite() && max.is_finite() && max > min) {
+        return AxisTicks { step: 0.0, values: Vec::new(), labels: Vec::new() };
+    }
+    let range = nice_number(max - min, false);
+    let step = nice_number(range / (target.max(2) - 1) as f64, true);
+    // Integer multiples of the step avoid accumulating rounding drift.
+    let first = (min / step - 1e-9).ceil() as i64;
+    let last = (max / step + 1e-9).floor() as i64;
+    let values: Vec<f64> = (first..=last).map(|k| k as f64 * step).collect();
+    let labels = values.iter().map(|&v| format_tick(v, step)).collect();
+    AxisTicks { step, values, labels }
+}
+
+#[cfg(feature = "math")]
+fn format_tick(value: f64, step: f64) -> String {
+    if value.abs() < step * 1e-6 {
+        return "0".to_string();
+    }
+    let step_exponent = step.log10().floor();
+    if value.abs() >= 1e6 || step < 1e-4 {
+        let digits = (value.abs().log10().floor() - step_exponent).max(0.0) as usize;
+        format!("{:.*e}", digits, value)
+    } else {
+        format!("{:.*}", (-step_exponent).max(0.0) as usize, value)
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SamplingOptions {
+    /// Evenly spaced parameter values sampled before refinement.
+    pub initial_samples: usize,
+    /// How many times an interval may be halved.
+    pub max_depth: u32,
+    /// Largest allowed distance, in pixels, between a midpoint and the chord.
+    pub tolerance_px: f64,
+    /// Chords at full depth longer than this are checked for a jump.
+    pub jump_px: f64,
+    pub max_evaluations: usize,
+}
+
+#[cfg(feature = "math")]
+impl Default for SamplingOptions {
+    fn default() -> Self {
+        SamplingOptions { initial_samples: 128, max_depth: 10, tolerance_px: 0.35, jump_px: 2.0, max_evaluations: 50_000 }
+    }
+}
+
+/// Polylines in data coordinates. A curve is split wherever it is
+/// undefined or jumps.
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct SampledCurve {
+    pub polylines: Vec<Vec<[f64; 2]>>,
+    /// Parameter values where a jump or pole was detected.
+    pub discontinuities: Vec<f64>,
+    pub evaluations: usize,
+}
+
+#[cfg(feature = "math")]
+struct CurveSampler<'a> {
+    eval: &'a dyn Fn(f64) -> [f64; 2],
+    view: Viewport,
+    pixel: [f64; 2],
+    options: SamplingOptions,
+    curve: SampledCurve,
+    current: Vec<[f64; 2]>,
+}
+
+#[cfg(feature = "math")]
+impl CurveSampler<'_> {
+    fn point(&mut self, t: f64) -> [f64; 2] {
+        self.curve.evaluations += 1;
+        (self.eval)(t)
+    }
+
+    fn distance_px(&self, a: [f64; 2], b: [f64; 2]) -> f64 {
+        ((b[0] - a[0]) / self.pixel[0]).hypot((b[1] - a[1]) / self.pixel[1])
+    }
+
+    /// Whether all the points lie beyond the same edge of the view, so the
+    /// chord between them can't be seen.
+    fn off_screen(&self, points: &[[f64; 2]]) -> bool {
+        let v = &self.view;
+        points.iter().all(|p| p[0] < v.x_min)
+            || points.iter().all(|p| p[0] > v.x_max)
+            || points.iter().all(|p| p[1] < v.y_min)
+            || points.iter().all(|p| p[1] > v.y_max)
+    }
+
+    fn emit(&mut self, p: [f64; 2]) {
+        if p[0].is_finite() && p[1].is_finite() {
+            self.current.push(p);
+        } else {
+            self.break_line();
+        }
+    }
+
+    fn break_line(&mut self) {
+        if self.current.len() > 1 {
+            self.curve.polylines.push(std::mem::take(&mut self.current));
+        } else {
+            self.current.clear();
+        }
+    }
+
+    /// Emits the points in `(t0, t1]`; `p0` has already been emitted.
+    fn refine(&mut self, t0: f64, p0: [f64; 2], t1: f64, p1: [f64; 2], depth: u32) {
+        let tm = 0.5 * (t0 + t1);
+        let pm = self.point(tm);
+        let defined = [p0, pm, p1].map(|p| p[0].is_finite() && p[1].is_finite());
+        let all_defined = defined.iter().all(|&d| d);
+
+        let needs_refinement = if all_defined {
+            let chord_mid = [0.5 * (p0[0] + p1[0]), 0.5 * (p0[1] + p1[1])];
+            self.distance_px(pm, chord_mid) > self.options.tolerance_px && !self.off_screen(&[p0, pm, p1])
+        } else {
+            // Narrow down where the curve starts or stops being defined.
+            defined.iter().any(|&d| d)
+        };
+        let within_budget = self.curve.evaluations < self.options.max_evaluations;
+        if needs_refinement && depth < self.options.max_depth && within_budget {
+            self.refine(t0, p0, tm, pm, depth + 1);
+            self.refine(tm, pm, t1, p1, depth + 1);
+            return;
+        }
+
+        // At full depth a continuous curve has short chords split roughly
+        // evenly by the midpoint; a jump or pole keeps a long chord with
+        // nearly all of its length on one side.
+        if all_defined && depth >= self.options.max_depth {
+            let chord = self.distance_px(p0, p1);
+            let longer_half = self.distance_px(p0, pm).max(self.distance_px(pm, p1));
+            if chord > self.options.jump_px && longer_half > 0.75 * chord {
+                // Neighbouring intervals can both straddle the same pole.
+                let repeated = self.curve.discontinuities.last().is_some_and(|&d| (tm - d).abs() <= 4.0 * (t1 - t0));
+                if !repeated {
+                    self.curve.discontinuities.push(tm);
+                }
+                self.break_line();
+                self.emit(p1);
+                return;
+            }
+        }
+        self.emit(pm);
+        self.emit(p1);
+    }
+}
+
+/// Samples the curve `t -> eval(t)` over `[t_min, t_max]`, adding points
+/// where it bends by more than the pixel tolerance inside `view`. `eval`
+/// returns NaN where the curve is undefined.
+#[cfg(feature = "math")]
+pub fn sample_curve(
+    eval: &dyn Fn(f64) -> [f64; 2],
+    t_min: f64,
+    t_max: f64,
+    view: &Viewport,
+    area: &PlotArea,
+    options: SamplingOptions,
+) -> SampledCurve {
+    let pixel = area.pixel_size(view);
+    let mut sampler = CurveSampler { eval, view: *view, pixel, options, curve: SampledCurve::default(), current: Vec::new() };
+    let n = options.initial_samples.max(1);
+    let mut t0 = t_min;
+    let mut p0 = sampler.point(t0);
+    sampler.emit(p0);
+    for i in 1..=n {
+        let t1 = t_min + (t_max - t_min) * i as f64 / n as f64;
+        let p1 = sampler.point(t1);
+        sampler.refine(t0, p0, t1, p1, 0);
+        t0 = t1;
+        p0 = p1;
+    }
+    sampler.break_line();
+    sampler.curve
+}
+
+/// Bisection steps used to tell a zero crossing from a pole.
+#[cfg(feature = "math")]
+const CROSSING_BISECTIONS: usize = 8;
+
+/// Whether the sign change of `f` between `a` and `b` comes from a zero
+/// rather than a pole: closing in on a zero shrinks |f|, while closing in
+/// on a pole makes it grow.
+#[cfg(feature = "math")]
+fn brackets_zero(f: &dyn Fn(f64, f64) -> f64, a: [f64; 2], fa: f64, b: [f64; 2], fb: f64) -> bool {
+    let (mut lo, mut hi, mut f_lo, mut f_hi) = (a, b, fa, fb);
+    for _ in 0..CROSSING_BISECTIONS {
+        let mid = [0.5 * (lo[0] + hi[0]), 0.5 * (lo[1] + hi[1])];
+        let f_mid = f(mid[0], mid[1]);
+        if !f_mid.is_finite() {
+            return false;
+        }
+        if (f_mid > 0.0) == (f_lo > 0.0) {
+            (lo, f_lo) = (mid, f_mid);
+        } else {
+            (hi, f_hi) = (mid, f_mid);
+        }
+    }
+    f_lo.abs().min(f_hi.abs()) <= fa.abs().min(fb.abs())
+}
+
+/// Traces `f(x, y) = 0` across the viewport on a `columns` x `rows` grid,
+/// returning one line segment per crossed cell.
+#[cfg(feature = "math")]
+pub fn marching_squares(f: &dyn Fn(f64, f64) -> f64, view: &Viewport, columns: usize, rows: usize) -> Vec<[[f64; 2]; 2]> {
+    let (columns, rows) = (columns.max(1), rows.max(1));
+    let (dx, dy) = (view.width() / columns as f64, view.height() / rows as f64);
+    let grid: Vec<f64> = (0..=rows)
+        .flat_map(|j| (0..=columns).map(move |i| (i, j)))
+        .map(|(i, j)| f(view.x_min + i as f64 * dx, view.y_min + j as f64 * dy))
+        .collect();
+    let at = |i: usize, j: usize| grid[j * (columns + 1) + i];
+
+    let mut segments = Vec::new();
+    for j in 0..rows {
+        for i in 0..columns {
+            let (x0, y0) = (view.x_min + i as f64 * dx, view.y_min + j as f64 * dy);
+            let corners = [at(i, j), at(i + 1, j), at(i + 1, j + 1), at(i, j + 1)];
+            if !corners.iter().all(|v| v.is_finite()) {
+                continue;
+            }
+            let positions = [[x0, y0], [x0 + dx, y0], [x0 + dx, y0 + dy], [x0, y0 + dy]];
+            // Edges in order bottom, right, top, left, as corner pairs.
+            let crossings: Vec<[f64; 2]> = (0..4)
+                .filter_map(|e| {
+                    let (a, b) = (e, (e + 1) % 4);
+                    let (p, q) = (positions[a], positions[b]);
+                    // A sign change across a pole is not a zero.
+                    if (corners[a] > 0.0) == (corners[b] > 0.0) || !brackets_zero(f, p, corners[a], q, corners[b]) {
+                        return None;
+                    }
+                    let t = corners[a] / (corners[a] - corners[b]);
+                    Some([p[0] + t * (q[0] - p[0]), p[1] + t * (q[1] - p[1])])
+                })
+                .collect();
+            let pairs = match crossings.len() {
+                2 => vec![[crossings[0], crossings[1]]],
+                4 => {
+                    // Saddle: the centre value decides which corners connect.
+                    let centre = corners.iter().sum::<f64>() / 4.0;
+                    if (centre > 0.0) == (corners[0] > 0.0) {
+                        vec![[crossings[0], crossings[1]], [crossings[2], crossings[3]]]
+                    } else {
+                        vec![[crossings[0], crossings[3]], [crossings[1], crossings[2]]]
+                    }
+                }
+                _ => continue,
+            };
+            segments.extend(pairs);
+        }
+    }
+    segments
+}
+
+/// Clips the segment `a`-`b` to the rectangle `[min, max]` (Liang–Barsky).
+#[cfg(feature = "math")]
+fn clip_segment(a: [f64; 2], b: [f64; 2], min: [f64; 2], max: [f64; 2]) -> Option<([f64; 2], [f64; 2])> {
+    let d = [b[0] - a[0], b[1] - a[1]];
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+    for axis in 0..2 {
+        for (p, q) in [(-d[axis], a[axis] - min[axis]), (d[axis], max[axis] - a[axis])] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None;
+                }
+                continue;
+            }
+            let r = q / p;
+            if p < 0.0 {
+                t0 = t0.max(r);
+            } else {
+                t1 = t1.min(r);
+            }
+            if t0 > t1 {
+                return None;
+            }
+        }
+    }
+    Some(([a[0] + t0 * d[0], a[1] + t0 * d[1]], [a[0] + t1 * d[0], a[1] + t1 * d[1]]))
+}
+
+#[cfg(feature = "math")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlotCurve {
+    /// `y = f(x)` over the visible x range.
+    Function { expr: Expr },
+    Parametric { x: Expr, y: Expr, t_min: f64, t_max: f64 },
+    /// The zero set of `f(x, y)`.
+    Implicit { expr: Expr },
+}
+
+#[cfg(feature = "math")]
+impl PlotCurve {
+    fn kind(&self) -> &'static str {
+        match self {
+            PlotCurve::Function { .. } => "function",
+            PlotCurve::Parametric { .. } => "parametric",
+            PlotCurve::Implicit { .. } => "implicit",
+        }
+    }
+
+    /// Samples the curve for display in `area` at the current view.
+    pub fn sample(&self, view: &Viewport, area: &PlotArea, options: SamplingOptions) -> SampledCurve {
+        match self {
+            PlotCurve::Function { expr } => {
+                let eval = |x: f64| [x, evaluate_in(expr, &[("x", x)])];
+                sample_curve(&eval, view.x_min, view.x_max, view, area, options)
+            }
+            PlotCurve::Parametric { x, y, t_min, t_max } => {
+                let eval = |t: f64| [evaluate_in(x, &[("t", t)]), evaluate_in(y, &[("t", t)])];
+                sample_curve(&eval, *t_min, *t_max, view, area, options)
+            }
+            PlotCurve::Implicit { expr } => {
+                let evaluations = Cell::new(0);
+                let f = |x: f64, y: f64| {
+                    evaluations.set(evaluations.get() + 1);
+                    evaluate_in(expr, &[("x", x), ("y", y)])
+                };
+                let columns = (area.width / CONTOUR_CELL_PX).ceil() as usize;
+                let rows = (area.height / CONTOUR_CELL_PX).ceil() as usize;
+                let segments = marching_squares(&f, view, columns, rows);
+                SampledCurve {
+                    evaluations: evaluations.get(),
+                    polylines: segments.into_iter().map(|s| s.to_vec()).collect(),
+                    discontinuities: Vec::new(),
+                }
+            }
+        }
+    }
+
+    /// Points spread evenly in the parameter, for choosing axis ranges.
+    fn preview(&self, view: &Viewport, samples: usize) -> Vec<[f64; 2]> {
+        let spread = |t_min: f64, t_max: f64| (0..=samples).map(move |i| t_min + (t_max - t_min) * i as f64 / samples as f64);
+        match self {
+            PlotCurve::Function { expr } => {
+                spread(view.x_min, view.x_max).map(|x| [x, evaluate_in(expr, &[("x", x)])]).collect()
+            }
+            PlotCurve::Parametric { x, y, t_min, t_max } => spread(*t_min, *t_max)
+                .map(|t| [evaluate_in(x, &[("t", t)]), evaluate_in(y, &[("t", t)])])
+                .collect(),
+            PlotCurve::Implicit { .. } => Vec::new(),
+        }
+    }
+}
+
+/// Evaluates with the given bindings; anything undefined becomes NaN.
+#[cfg(feature = "math")]
+fn evaluate_in(expr: &Expr, bindings: &[(&str, f64)]) -> f64 {
+    expr.eval_with(&|name| bindings.iter().find(|(n, _)| *n == name).map(|(_, v)| *v)).unwrap_or(f64::NAN)
+}
+
+#[cfg(feature = "math")]
+fn parse_offset(source: &str, offset: usize) -> Result<Expr, ParseError> {
+    parse_expression_source(source).map_err(|mut e| {
+        e.span = Span { start: e.span.start + offset, end: e.span.end + offset };
+        e
+    })
+}
+
+/// Parses `lhs = rhs` as `lhs - rhs`; without `=` the whole source is
+/// taken as the left-hand side of `... = 0`.
+#[cfg(feature = "math")]
+pub fn parse_equation(source: &str) -> Result<Expr, ParseError> {
+    let Some(equals) = source.find('=') else {
+        return parse_expression_source(source);
+    };
+    if let Some(second) = source[equals + 1..].find('=') {
+        let at = equals + 1 + second;
+        return Err(ParseError { message: "Only one '=' is allowed".to_string(), span: Span { start: at, end: at + 1 } });
+    }
+    let lhs = parse_offset(&source[..equals], 0)?;
+    let rhs = parse_offset(&source[equals + 1..], equals + 1)?;
+    Ok(Expr::binary(BinaryOp::Sub, lhs, rhs))
+}
+
+/// Rejects free variables outside `allowed`.
+#[cfg(feature = "math")]
+fn check_variables(expr: &Expr, allowed: &[&str], what: &str) -> Result<(), String> {
+    match expr.variables().into_iter().find(|v| !allowed.contains(&v.as_str())) {
+        Some(name) => Err(format!("{} can only use {}, found '{}'", what, allowed.join(" and "), name)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlotSeries {
+    pub curve: PlotCurve,
+    pub label: String,
+    pub color: String,
+}
+
+/// Smallest range covering the values, ignoring extreme outliers such as
+/// the values next to a pole.
+#[cfg(feature = "math")]
+fn robust_range(mut values: Vec<f64>) -> Option<(f64, f64)> {
+    values.retain(|v| v.is_finite());
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let quantile = |q: f64| values[((values.len() - 1) as f64 * q).round() as usize];
+    let (low, high) = (quantile(0.02), quantile(0.98));
+    let (min, max) = (values[0], values[values.len() - 1]);
+    if max - min > 4.0 * (high - low) {
+        Some((low, high))
+    } else {
+        Some((min, max))
+    }
+}
+
+/// Widens `[low, high]` by `fraction` on each side, or to a unit range
+/// around a single value. `None` when the result would not fit in an f64.
+#[cfg(feature = "math")]
+fn pad_range(low: f64, high: f64, fraction: f64) -> Option<(f64, f64)> {
+    let pad = if high > low {
+        (high - low) * fraction
+    } else if low == 0.0 {
+        1.0
+    } else {
+        low.abs() * 0.5
+    };
+    let (low, high) = (low - pad, high + pad);
+    ((high - low).is_finite() && high > low).then_some((low, high))
+}
+
+/// A view fitting the series. Parametric curves decide the x range;
+/// otherwise the current x range is kept and y is fitted to it.
+#[cfg(feature = "math")]
+pub fn autoscale_view(series: &[PlotSeries], current: &Viewport, samples: usize) -> Viewport {
+    let parametric: Vec<[f64; 2]> = series
+        .iter()
+        .filter(|s| matches!(s.curve, PlotCurve::Parametric { .. }))
+        .flat_map(|s| s.curve.preview(current, samples))
+        .collect();
+    let (x_min, x_max) = robust_range(parametric.iter().map(|p| p[0]).collect())
+        .and_then(|(low, high)| pad_range(low, high, 0.05))
+        .unwrap_or((current.x_min, current.x_max));
+    let view_x = Viewport { x_min, x_max, ..*current };
+    let ys: Vec<f64> = series.iter().flat_map(|s| s.curve.preview(&view_x, samples)).map(|p| p[1]).collect();
+    let (y_min, y_max) = robust_range(ys)
+        .and_then(|(low, high)| pad_range(low, high, 0.05))
+        .unwrap_or((current.y_min, current.y_max));
+    Viewport { x_min, x_max, y_min, y_max }
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct SeriesSummary {
+    pub label: String,
+    pub color: String,
+    pub kind: &'static str,
+    pub evaluations: usize,
+    pub polylines: usize,
+    pub discontinuities: Vec<f64>,
+}
+
+/// What was drawn by the last render.
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct PlotFrame {
+    pub view: Viewport,
+    pub x_ticks: AxisTicks,
+    pub y_ticks: AxisTicks,
+    pub series: Vec<SeriesSummary>,
+}
+
+/// An interactive plot bound to a canvas. Call `render()` after changing
+/// series, and after any mouse handler that returns `true`.
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub struct FunctionPlot {
+    canvas: HtmlCanvasElement,
+    context: CanvasRenderingContext2d,
+    series: Vec<PlotSeries>,
+    view: Viewport,
+    options: SamplingOptions,
+    drag_from: Option<[f64; 2]>,
+}
+
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+impl FunctionPlot {
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas: HtmlCanvasElement) -> Result<FunctionPlot, JsValue> {
+        let context = canvas
+            .get_context("2d")?
+            .ok_or_else(|| JsValue::from_str("Canvas has no 2d context"))?
+            .dyn_into::<CanvasRenderingContext2d>()?;
+        Ok(FunctionPlot {
+            canvas,
+            context,
+            series: Vec::new(),
+            view: Viewport { x_min: -10.0, x_max: 10.0, y_min: -10.0, y_max: 10.0 },
+            options: SamplingOptions::default(),
+            drag_from: None,
+        })
+    }
+
+    /// Adds `y = f(x)`; a leading `y =` is optional. Returns the series index.
+    pub fn add_function(&mut self, expression: &str, color: Option<String>, label: Option<String>) -> Result<usize, JsValue> {
+        let body = match expression.split_once('=') {
+            Some((lhs, rhs)) if lhs.trim() == "y" => rhs,
+            _ => expression,
+        };
+        let offset = expression.len() - body.len();
+        let expr = parse_offset(body, offset).map_err(parse_error_to_js)?;
+        check_variables(&expr, &["x"], "A function plot").map_err(|e| JsValue::from_str(&e))?;
+        let default_label = format!("y = {}", expr);
+        Ok(self.push(PlotCurve::Function { expr }, color, label.unwrap_or(default_label)))
+    }
+
+    /// Adds the curve `(x(t), y(t))` for `t` in `[t_min, t_max]`.
+    pub fn add_parametric(
+        &mut self,
+        x: &str,
+        y: &str,
+        t_min: f64,
+        t_max: f64,
+        color: Option<String>,
+        label: Option<String>,
+    ) -> Result<usize, JsValue> {
+        if !(t_min.is_finite() && t_max.is_finite() && t_min < t_max) {
+            return Err(JsValue::from_str("Parameter range must be finite with t_min < t_max"));
+        }
+        let x = parse_expression_source(x).map_err(parse_error_to_js)?;
+        let y = parse_expression_source(y).map_err(parse_error_to_js)?;
+        for expr in [&x, &y] {
+            check_variables(expr, &["t"], "A parametric curve").map_err(|e| JsValue::from_str(&e))?;
+        }
+        let default_label = format!("({}, {})", x, y);
+        Ok(self.push(PlotCurve::Parametric { x, y, t_min, t_max }, color, label.unwrap_or(default_label)))
+    }
+
+    /// Adds the contour `lhs = rhs` in `x` and `y`, e.g. `x^2 + y^2 = 4`.
+    pub fn add_implicit(&mut self, equation: &str, color: Option<String>, label: Option<String>) -> Result<usize, JsValue> {
+        let expr = parse_equation(equation).map_err(parse_error_to_js)?;
+        check_variables(&expr, &["x", "y"], "An implicit curve").map_err(|e| JsValue::from_str(&e))?;
+        let default_label = equation.split_whitespace().collect::<Vec<_>>().join(" ");
+        Ok(self.push(PlotCurve::Implicit { expr }, color, label.unwrap_or(default_label)))
+    }
+
+    pub fn remove_series(&mut self, index: usize) -> bool {
+        if index < self.series.len() {
+            self.series.remove(index);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.series.clear();
+    }
+
+    pub fn series_count(&self) -> usize {
+        self.series.len()
+    }
+
+    pub fn set_view(&mut self, x_min: f64, x_max: f64, y_min: f64, y_max: f64) -> Result<(), JsValue> {
+        self.view = Viewport::new(x_min, x_max, y_min, y_max).map_err(|e| JsValue::from_str(&e))?;
+        Ok(())
+    }
+
+    pub fn view(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.view).unwrap()
+    }
+
+    /// Fits the view to the series (see `autoscale_view`).
+    pub fn autoscale(&mut self) {
+        let samples = self.area().width.max(64.0) as usize;
+        self.view = autoscale_view(&self.series, &self.view, samples);
+    }
+
+    /// Data coordinates `[x, y]` under the mouse.
+    pub fn data_at(&self, event: &MouseEvent) -> Vec<f64> {
+        self.data_position(event).to_vec()
+    }
+
+    /// Zooms about the mouse position; `factor` above 1 zooms in. Wheel
+    /// events can be passed straight through, as they are mouse events.
+    pub fn zoom_at(&mut self, event: &MouseEvent, factor: f64) -> bool {
+        if !(factor.is_finite() && factor > 0.0) {
+            return false;
+        }
+        let [x, y] = self.data_position(event);
+        let before = self.view;
+        self.view.zoom_about(x, y, factor);
+        self.view != before
+    }
+
+    pub fn mouse_down(&mut self, event: &MouseEvent) {
+        self.drag_from = Some(self.event_position(event));
+    }
+
+    /// Pans while the primary button is held. Returns whether the view moved.
+    pub fn mouse_move(&mut self, event: &MouseEvent) -> bool {
+        let Some(from) = self.drag_from else {
+            return false;
+        };
+        if event.buttons() & 1 == 0 {
+            self.drag_from = None;
+            return false;
+        }
+        let to = self.event_position(event);
+        let [sx, sy] = self.area().pixel_size(&self.view);
+        self.view.pan(-(to[0] - from[0]) * sx, (to[1] - from[1]) * sy);
+        self.drag_from = Some(to);
+        to != from
+    }
+
+    pub fn mouse_up(&mut self) {
+        self.drag_from = None;
+    }
+
+    /// Draws grid, axes, series and legend, returning the ticks and
+    /// per-series sampling statistics.
+    pub fn render(&self) -> Result<JsValue, JsValue> {
+        let frame = self.draw()?;
+        Ok(serde_wasm_bindgen::to_value(&frame).unwrap())
+    }
+}
+
+#[cfg(feature = "math")]
+impl FunctionPlot {
+    fn push(&mut self, curve: PlotCurve, color: Option<String>, label: String) -> usize {
+        let color = color.unwrap_or_else(|| SERIES_COLORS[self.series.len() % SERIES_COLORS.len()].to_string());
+        self.series.push(PlotSeries { curve, label, color });
+        self.series.len() - 1
+    }
+
+    pub fn series(&self) -> &[PlotSeries] {
+        &self.series
+    }
+
+    fn area(&self) -> PlotArea {
+        PlotArea::for_canvas(self.canvas.width() as f64, self.canvas.height() as f64)
+    }
+
+    /// The event's offset in canvas pixels, allowing for CSS scaling.
+    fn event_position(&self, event: &MouseEvent) -> [f64; 2] {
+        let scale = |pixels: u32, css: i32| if css > 0 { pixels as f64 / css as f64 } else { 1.0 };
+        [
+            event.offset_x() as f64 * scale(self.canvas.width(), self.canvas.client_width()),
+            event.offset_y() as f64 * scale(self.canvas.height(), self.canvas.client_height()),
+        ]
+    }
+
+    fn data_position(&self, event: &MouseEvent) -> [f64; 2] {
+        let [px, py] = self.event_position(event);
+        self.area().to_data(&self.view, px, py)
+    }
+
+    fn draw(&self) -> Result<PlotFrame, JsValue> {
+        let ctx = &self.context;
+        let (width, height) = (self.canvas.width() as f64, self.canvas.height() as f64);
+        let area = self.area();
+        let view = &self.view;
+        let x_ticks = nice_ticks(view.x_min, view.x_max, (area.width / 90.0).max(2.0) as usize);
+        let y_ticks = nice_ticks(view.y_min, view.y_max, (area.height / 60.0).max(2.0) as usize);
+        let (right, bottom) = (area.left + area.width, area.top + area.height);
+        // Half-pixel offsets keep one-pixel lines sharp.
+        let snap = |v: f64| v.round() + 0.5;
+
+        ctx.set_fill_style_str("#ffffff");
+        ctx.fill_rect(0.0, 0.0, width, height);
+
+        ctx.set_line_width(1.0);
+        ctx.set_stroke_style_str("#e5e7eb");
+        ctx.begin_path();
+        for &x in &x_ticks.values {
+            let px = snap(area.to_screen(view, x, 0.0)[0]);
+            ctx.move_to(px, area.top);
+            ctx.line_to(px, bottom);
+        }
+        for &y in &y_ticks.values {
+            let py = snap(area.to_screen(view, 0.0, y)[1]);
+            ctx.move_to(area.left, py);
+            ctx.line_to(right, py);
+        }
+        ctx.stroke();
+
+        let [origin_x, origin_y] = area.to_screen(view, 0.0, 0.0);
+        ctx.set_stroke_style_str("#6b7280");
+        ctx.begin_path();
+        if view.x_min <= 0.0 && 0.0 <= view.x_max {
+            ctx.move_to(snap(origin_x), area.top);
+            ctx.line_to(snap(origin_x), bottom);
+        }
+        if view.y_min <= 0.0 && 0.0 <= view.y_max {
+            ctx.move_to(area.left, snap(origin_y));
+            ctx.line_to(right, snap(origin_y));
+        }
+        ctx.stroke();
+        ctx.set_stroke_style_str("#9ca3af");
+        ctx.stroke_rect(snap(area.left), snap(area.top), area.width.round(), area.height.round());
+
+        ctx.set_fill_style_str("#374151");
+        ctx.set_font("12px sans-serif");
+        ctx.set_text_align("center");
+        ctx.set_text_baseline("top");
+        for (&x, label) in x_ticks.values.iter().zip(&x_ticks.labels) {
+            ctx.fill_text(label, area.to_screen(view, x, 0.0)[0], bottom + 6.0)?;
+        }
+        ctx.set_text_align("right");
+        ctx.set_text_baseline("middle");
+        for (&y, label) in y_ticks.values.iter().zip(&y_ticks.labels) {
+            ctx.fill_text(label, area.left - 6.0, area.to_screen(view, 0.0, y)[1])?;
+        }
+
+        ctx.save();
+        ctx.begin_path();
+        ctx.rect(area.left, area.top, area.width, area.height);
+        ctx.clip();
+        ctx.set_line_width(2.0);
+        ctx.set_line_join("round");
+        ctx.set_line_cap("round");
+        // Clip to a slightly larger box so far-off points don't reach the
+        // canvas as huge coordinates.
+        let clip_min = [area.left - 8.0, area.top - 8.0];
+        let clip_max = [right + 8.0, bottom + 8.0];
+        let mut summaries = Vec::with_capacity(self.series.len());
+        for series in &self.series {
+            let curve = series.curve.sample(view, &area, self.options);
+            ctx.set_stroke_style_str(&series.color);
+            ctx.begin_path();
+            for line in &curve.polylines {
+                let mut pen: Option<[f64; 2]> = None;
+                for pair in line.windows(2) {
+                    let a = area.to_screen(view, pair[0][0], pair[0][1]);
+                    let b = area.to_screen(view, pair[1][0], pair[1][1]);
+                    match clip_segment(a, b, clip_min, clip_max) {
+                        Some((a, b)) => {
+                            if pen != Some(a) {
+                                ctx.move_to(a[0], a[1]);
+                            }
+                            ctx.line_to(b[0], b[1]);
+                            pen = Some(b);
+                        }
+                        None => pen = None,
+                    }
+                }
+            }
+            ctx.stroke();
+            summaries.push(SeriesSummary {
+                label: series.label.clone(),
+                color: series.color.clone(),
+                kind: series.curve.kind(),
+                evaluations: curve.evaluations,
+                polylines: curve.polylines.len(),
+                discontinuities: curve.discontinuities,
+            });
+        }
+        ctx.restore();
+
+        self.draw_legend(&area)?;
+        Ok(PlotFrame { view: self.view, x_ticks, y_ticks, series: summaries })
+    }
+
+    fn draw_legend(&self, area: &PlotArea) -> Result<(), JsValue> {
+        if self.series.is_empty() {
+            return Ok(());
+        }
+        let ctx = &self.context;
+        let longest = self.series.iter().map(|s| s.label.chars().count()).max().unwrap_or(0);
+        let (row_height, swatch) = (18.0, 20.0);
+        let box_width = 8.0 + swatch + 6.0 + longest as f64 * LABEL_CHAR_WIDTH + 8.0;
+        let box_height = 6.0 + row_height * self.series.len() as f64 + 2.0;
+        let left = area.left + area.width - box_width - 8.0;
+        let top = area.top + 8.0;
+
+        ctx.set_fill_style_str("rgba(255, 255, 255, 0.85)");
+        ctx.fill_rect(left, top, box_width, box_height);
+        ctx.set_line_width(1.0);
+        ctx.set_stroke_style_str("#d1d5db");
+        ctx.stroke_rect(left.round() + 0.5, top.round() + 0.5, box_width.round(), box_height.round());
+
+        ctx.set_font("12px sans-serif");
+        ctx.set_text_align("left");
+        ctx.set_text_baseline("middle");
+        for (i, series) in self.series.iter().enumerate() {
+            let y = top + 6.0 + row_height * (i as f64 + 0.5);
+            ctx.set_line_width(2.0);
+            ctx.set_stroke_style_str(&series.color);
+            ctx.begin_path();
+            ctx.move_to(left + 8.0, y);
+            ctx.line_to(left + 8.0 + swatch, y);
+            ctx.stroke();
+            ctx.set_fill_style_str("#111827");
+            ctx.fill_text(&series.label, left + 8.0 + swatch + 6.0, y)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "math"))]
+mod tests {
+    use super::*;
+
+    fn view(x_min: f64, x_max: f64, y_min: f64, y_max: f64) -> Viewport {
+        Viewport::new(x_min, x_max, y_min, y_max).unwrap()
+    }
+
+    fn series(curve: PlotCurve) -> PlotSeries {
+        PlotSeries { curve, label: String::new(), color: String::new() }
+    }
+
+    #[test]
+    fn rejects_viewports_with_overflowing_extents() {
+        assert!(Viewport::new(-1e308, 1e308, 0.0, 1.0).is_err());
+        assert!(Viewport::new(0.0, 1.0, f64::NAN, 1.0).is_err());
+        assert!(Viewport::new(1.0, 1.0, 0.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn picks_nice_tick_steps() {
+        let ticks = nice_ticks(0.0, 10.0, 5);
+        assert_eq!(ticks.step, 2.0);
+        assert_eq!(ticks.values, [0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+
+        let ticks = nice_ticks(-1.0, 1.0, 5);
+        assert_eq!(ticks.step, 0.5);
+        assert_eq!(ticks.labels, ["-1.0", "-0.5", "0", "0.5", "1.0"]);
+
+        let ticks = nice_ticks(0.13, 0.47, 4);
+        assert_eq!(ticks.step, 0.2);
+        assert_eq!(ticks.labels, ["0.2", "0.4"]);
+
+        assert!(nice_ticks(1.0, 1.0, 5).values.is_empty());
+        assert!(nice_ticks(0.0, f64::INFINITY, 5).values.is_empty());
+    }
+
+    #[test]
+    fn formats_ticks_for_their_step() {
+        assert_eq!(format_tick(3.0, 1.0), "3");
+        assert_eq!(format_tick(0.25, 0.05), "0.25");
+        assert_eq!(format_tick(1e-17, 0.1), "0");
+        assert_eq!(format_tick(2e6, 1e6), "2e6");
+        assert_eq!(format_tick(2.5e6, 5e5), "2.5e6");
+        assert_eq!(format_tick(1.2e-4, 1e-5), "1.2e-4");
+    }
+
+    #[test]
+    fn sample_curve_finds_poles_but_not_smooth_bends() {
+        let (v, area) = (view(-1.0, 1.0, -10.0, 10.0), PlotArea::for_canvas(472.0, 348.0));
+
+        let pole = |x: f64| [x, 1.0 / (x - 0.3)];
+        let curve = sample_curve(&pole, v.x_min, v.x_max, &v, &area, SamplingOptions::default());
+        assert_eq!(curve.discontinuities.len(), 1);
+        assert!((curve.discontinuities[0] - 0.3).abs() < 1e-3);
+        assert_eq!(curve.polylines.len(), 2);
+
+        let smooth = |x: f64| [x, 8.0 * (4.0 * x).sin()];
+        let curve = sample_curve(&smooth, v.x_min, v.x_max, &v, &area, SamplingOptions::default());
+        assert!(curve.discontinuities.is_empty());
+        assert_eq!(curve.polylines.len(), 1);
+
+        // Undefined stretches split the curve without counting as jumps.
+        let sqrt = |x: f64| [x, x.sqrt()];
+        let curve = sample_curve(&sqrt, v.x_min, v.x_max, &v, &area, SamplingOptions::default());
+        assert!(curve.discontinuities.is_empty());
+        assert!(curve.polylines[0][0][0] < 1e-3);
+    }
+
+    #[test]
+    fn marching_squares_resolves_saddles_by_the_centre() {
+        let v = view(-1.0, 1.0, -1.0, 1.0);
+        let on_edge = |[x, y]: [f64; 2]| {
+            if y == -1.0 {
+                "bottom"
+            } else if x == 1.0 {
+                "right"
+            } else if y == 1.0 {
+                "top"
+            } else {
+                "left"
+            }
+        };
+        let edges = |offset: f64| -> Vec<[&str; 2]> {
+            marching_squares(&|x, y| x * y + offset, &v, 1, 1).iter().map(|[p, q]| [on_edge(*p), on_edge(*q)]).collect()
+        };
+        // A positive centre joins the positive corners (bottom-left and top-right).
+        assert_eq!(edges(0.1), [["bottom", "right"], ["top", "left"]]);
+        assert_eq!(edges(-0.1), [["bottom", "left"], ["right", "top"]]);
+    }
+
+    #[test]
+    fn marching_squares_skips_sign_changes_across_poles() {
+        let v = view(-1.0, 1.0, -1.0, 1.0);
+        for offset in [0.01, 0.2499, 0.125] {
+            assert!(marching_squares(&|x, _| 1.0 / (x - offset), &v, 8, 8).is_empty(), "pole at {}", offset);
+        }
+        let line = marching_squares(&|x, _| x - 0.01, &v, 8, 8);
+        assert_eq!(line.len(), 8);
+        let circle = marching_squares(&|x, y| x * x + y * y - 0.5, &v, 40, 40);
+        assert!(circle.len() > 40);
+        assert!(circle.iter().flatten().all(|p| (p[0].hypot(p[1]) - 0.5f64.sqrt()).abs() < 1e-2));
+    }
+
+    #[test]
+    fn clips_segments_to_the_rectangle() {
+        let (min, max) = ([0.0, 0.0], [1.0, 1.0]);
+        assert_eq!(clip_segment([-1.0, 0.5], [2.0, 0.5], min, max), Some(([0.0, 0.5], [1.0, 0.5])));
+        assert_eq!(clip_segment([0.2, 0.2], [0.8, 0.6], min, max), Some(([0.2, 0.2], [0.8, 0.6])));
+        assert_eq!(clip_segment([-1.0, -1.0], [2.0, 2.0], min, max), Some(([0.0, 0.0], [1.0, 1.0])));
+        assert_eq!(clip_segment([-1.0, 2.0], [2.0, 2.0], min, max), None);
+        assert_eq!(clip_segment([-1.0, 0.5], [0.5, 2.0], min, max), None);
+    }
+
+    #[test]
+    fn autoscales_to_the_curves() {
+        let current = view(-2.0, 2.0, -1.0, 1.0);
+        let parabola = series(PlotCurve::Function { expr: parse_expression_source("x^2").unwrap() });
+        let fitted = autoscale_view(&[parabola], &current, 200);
+        assert_eq!((fitted.x_min, fitted.x_max), (-2.0, 2.0));
+        assert!((fitted.y_min + 0.2).abs() < 1e-9 && (fitted.y_max - 4.2).abs() < 1e-9);
+
+        let circle = series(PlotCurve::Parametric {
+            x: parse_expression_source("cos(t)").unwrap(),
+            y: parse_expression_source("sin(t)").unwrap(),
+            t_min: 0.0,
+            t_max: std::f64::consts::TAU,
+        });
+        let fitted = autoscale_view(&[circle], &current, 200);
+        assert!((fitted.x_min + 1.1).abs() < 1e-9 && (fitted.x_max - 1.1).abs() < 1e-9);
+
+        // A pole's huge values are dropped as outliers.
+        let pole = series(PlotCurve::Function { expr: parse_expression_source("1 / x").unwrap() });
+        let fitted = autoscale_view(&[pole], &current, 200);
+        assert!(fitted.y_max < 100.0);
+
+        // Ranges too wide for an f64 keep the current view.
+        let huge = series(PlotCurve::Parametric {
+            x: parse_expression_source("1.7e308 * cos(t)").unwrap(),
+            y: parse_expression_source("t").unwrap(),
+            t_min: 0.0,
+            t_max: std::f64::consts::PI,
+        });
+        let fitted = autoscale_view(&[huge], &current, 200);
+        assert_eq!((fitted.x_min, fitted.x_max), (-2.0, 2.0));
+    }
+}
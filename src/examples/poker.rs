@@ -0,0 +1,359 @@
+#[cfg(feature = "random")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "random")]
+use rand::prelude::*;
+#[cfg(feature = "random")]
+use serde::Serialize;
+#[cfg(feature = "random")]
+use super::random::{new_deck, seeded_rng, Card};
+
+#[cfg(feature = "random")]
+const HAND_CATEGORIES: [&str; 9] = [
+    "High Card", "One Pair", "Two Pair", "Three of a Kind", "Straight",
+    "Flush", "Full House", "Four of a Kind", "Straight Flush",
+];
+
+// How many trials run between progress callbacks.
+#[cfg(feature = "random")]
+const PROGRESS_INTERVAL: u64 = 10_000;
+
+#[cfg(feature = "random")]
+fn score(category: u32, ranks: &[u8]) -> u32 {
+    let mut value = category;
+    for i in 0..5 {
+        value = (value << 4) | ranks.get(i).copied().unwrap_or(0) as u32;
+    }
+    value
+}
+
+#[cfg(feature = "random")]
+fn straight_high(mask: u16) -> Option<u8> {
+    // The ace also plays low in A-2-3-4-5.
+    let mask = if mask & (1 << 14) != 0 { mask | (1 << 1) } else { mask };
+    (5..=14u8).rev().find(|&high| {
+        let run = 0b11111u16 << (high - 4);
+        mask & run == run
+    })
+}
+
+#[cfg(feature = "random")]
+fn top_ranks(mask: u16, count: usize) -> Vec<u8> {
+    (2..=14u8).rev().filter(|&r| mask & (1 << r) != 0).take(count).collect()
+}
+
+/// Scores the best five-card hand that can be made from 5 to 7 cards.
+/// Higher scores beat lower ones; the top bits hold the hand category.
+#[cfg(feature = "random")]
+pub fn evaluate_hand(cards: &[Card]) -> u32 {
+    let mut counts = [0u8; 15];
+    let mut suit_masks = [0u16; 4];
+    for card in cards {
+        counts[card.rank as usize] += 1;
+        suit_masks[card.suit as usize] |= 1 << card.rank;
+    }
+    let rank_mask = suit_masks.iter().fold(0, |acc, mask| acc | mask);
+    let flush_mask = suit_masks.iter().copied().find(|mask| mask.count_ones() >= 5);
+
+    if let Some(high) = flush_mask.and_then(straight_high) {
+        return score(8, &[high]);
+    }
+
+    // Ranks grouped by multiplicity, largest groups first, then highest rank.
+    let mut groups: Vec<(u8, u8)> = (2..=14u8)
+        .rev()
+        .filter(|&r| counts[r as usize] > 0)
+        .map(|r| (counts[r as usize], r))
+        .collect();
+    groups.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+
+    let kickers = |exclude: &[u8], count: usize| -> Vec<u8> {
+        let mask = exclude.iter().fold(rank_mask, |acc, &r| acc & !(1 << r));
+        top_ranks(mask, count)
+    };
+
+    let (top_count, top_rank) = groups[0];
+    let second = groups.get(1).copied();
+
+    if top_count == 4 {
+        let mut ranks = vec![top_rank];
+        ranks.extend(kickers(&[top_rank], 1));
+        return score(7, &ranks);
+    }
+    if let (3, Some((second_count, second_rank))) = (top_count, second) {
+        if second_count >= 2 {
+            return score(6, &[top_rank, second_rank]);
+        }
+    }
+    if let Some(mask) = flush_mask {
+        return score(5, &top_ranks(mask, 5));
+    }
+    if let Some(high) = straight_high(rank_mask) {
+        return score(4, &[high]);
+    }
+    if top_count == 3 {
+        let mut ranks = vec![top_rank];
+        ranks.extend(kickers(&[top_rank], 2));
+        return score(3, &ranks);
+    }
+    if let (2, Some((2, second_rank))) = (top_count, second) {
+        let mut ranks = vec![top_rank, second_rank];
+        ranks.extend(kickers(&[top_rank, second_rank], 1));
+        return score(2, &ranks);
+    }
+    if top_count == 2 {
+        let mut ranks = vec![top_rank];
+        ranks.extend(kickers(&[top_rank], 3));
+        return score(1, &ranks);
+    }
+    score(0, &top_ranks(rank_mask, 5))
+}
+
+/// Returns the category name ("Flush", "Two Pair", ...) for a hand score.
+#[cfg(feature = "random")]
+pub fn hand_category(score: u32) -> &'static str {
+    HAND_CATEGORIES[(score >> 20) as usize]
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Debug, Clone)]
+pub struct PlayerEquity {
+    pub hand: String,
+    pub wins: u64,
+    pub ties: u64,
+    pub losses: u64,
+    pub win_pct: f64,
+    pub tie_pct: f64,
+    pub loss_pct: f64,
+    /// Share of the pot won on average, counting split pots fractionally.
+    pub equity_pct: f64,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Debug, Clone)]
+pub struct EquityReport {
+    pub board: String,
+    pub trials: u64,
+    /// True when every possible runout was enumerated instead of sampled.
+    pub exhaustive: bool,
+    pub players: Vec<PlayerEquity>,
+}
+
+#[cfg(feature = "random")]
+struct Tally {
+    wins: Vec<u64>,
+    ties: Vec<u64>,
+    shares: Vec<f64>,
+    trials: u64,
+}
+
+#[cfg(feature = "random")]
+impl Tally {
+    fn new(players: usize) -> Self {
+        Tally { wins: vec![0; players], ties: vec![0; players], shares: vec![0.0; players], trials: 0 }
+    }
+
+    fn record(&mut self, hands: &[[Card; 2]], board: &[Card]) {
+        let mut cards = [Card::new(2, 0); 7];
+        cards[2..2 + board.len()].copy_from_slice(board);
+
+        let scores: Vec<u32> = hands
+            .iter()
+            .map(|hole| {
+                cards[..2].copy_from_slice(hole);
+                evaluate_hand(&cards[..2 + board.len()])
+            })
+            .collect();
+        let best = *scores.iter().max().unwrap();
+        let winners = scores.iter().filter(|&&s| s == best).count();
+
+        for (i, &s) in scores.iter().enumerate() {
+            if s == best {
+                if winners == 1 {
+                    self.wins[i] += 1;
+                } else {
+                    self.ties[i] += 1;
+                }
+                self.shares[i] += 1.0 / winners as f64;
+            }
+        }
+        self.trials += 1;
+    }
+}
+
+#[cfg(feature = "random")]
+fn binomial(n: u64, k: u64) -> u64 {
+    (0..k).fold(1u64, |acc, i| acc.saturating_mul(n - i) / (i + 1))
+}
+
+/// Computes win/tie/loss percentages for each player's hole cards.
+///
+/// Runouts are enumerated exactly when there are no more of them than
+/// `iterations`; otherwise `iterations` random runouts are sampled.
+/// `on_progress` is called with `(done, total)` while the simulation runs.
+#[cfg(feature = "random")]
+pub fn poker_equity<R: Rng + ?Sized>(
+    hands: &[[Card; 2]],
+    board: &[Card],
+    iterations: u64,
+    rng: &mut R,
+    on_progress: &mut dyn FnMut(u64, u64),
+) -> Result<EquityReport, String> {
+    if hands.len() < 2 {
+        return Err("At least two players are required".to_string());
+    }
+    if iterations == 0 {
+        return Err("Run at least one iteration".to_string());
+    }
+    if board.len() > 5 {
+        return Err("The board cannot hold more than five cards".to_string());
+    }
+
+    let mut used: Vec<Card> = hands.iter().flatten().copied().collect();
+    used.extend_from_slice(board);
+    for (i, card) in used.iter().enumerate() {
+        if used[..i].contains(card) {
+            return Err(format!("Card {} appears more than once", card));
+        }
+    }
+
+    let mut remaining: Vec<Card> = new_deck().into_iter().filter(|c| !used.contains(c)).collect();
+    let needed = 5 - board.len();
+    if remaining.len() < needed {
+        return Err(format!(
+            "Only {} cards are left for the board but {} are needed; use fewer players",
+            remaining.len(),
+            needed
+        ));
+    }
+    let combinations = binomial(remaining.len() as u64, needed as u64);
+    let exhaustive = combinations <= iterations;
+    let total = if exhaustive { combinations } else { iterations };
+
+    let mut tally = Tally::new(hands.len());
+    let mut runout = board.to_vec();
+
+    if exhaustive {
+        let mut indices: Vec<usize> = (0..needed).collect();
+        loop {
+            runout.truncate(board.len());
+            runout.extend(indices.iter().map(|&i| remaining[i]));
+            tally.record(hands, &runout);
+            if tally.trials.is_multiple_of(PROGRESS_INTERVAL) {
+                on_progress(tally.trials, total);
+            }
+
+            // Advance to the next combination in lexicographic order.
+            let n = remaining.len();
+            let Some(pos) = (0..needed).rev().find(|&i| indices[i] < n - needed + i) else {
+                break;
+            };
+            indices[pos] += 1;
+            for i in pos + 1..needed {
+                indices[i] = indices[i - 1] + 1;
+            }
+        }
+    } else {
+        for _ in 0..iterations {
+            let (drawn, _) = remaining.partial_shuffle(rng, needed);
+            runout.truncate(board.len());
+            runout.extend_from_slice(drawn);
+            tally.record(hands, &runout);
+            if tally.trials.is_multiple_of(PROGRESS_INTERVAL) {
+                on_progress(tally.trials, total);
+            }
+        }
+    }
+    on_progress(tally.trials, total);
+
+    let trials = tally.trials.max(1) as f64;
+    let players = hands
+        .iter()
+        .enumerate()
+        .map(|(i, hole)| {
+            let losses = tally.trials - tally.wins[i] - tally.ties[i];
+            PlayerEquity {
+                hand: format!("{} {}", hole[0], hole[1]),
+                wins: tally.wins[i],
+                ties: tally.ties[i],
+                losses,
+                win_pct: tally.wins[i] as f64 / trials * 100.0,
+                tie_pct: tally.ties[i] as f64 / trials * 100.0,
+                loss_pct: losses as f64 / trials * 100.0,
+                equity_pct: tally.shares[i] / trials * 100.0,
+            }
+        })
+        .collect();
+
+    Ok(EquityReport {
+        board: board.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" "),
+        trials: tally.trials,
+        exhaustive,
+        players,
+    })
+}
+
+/// Parses hole cards for several players separated by `|`, e.g. "As Kd | Qh Qc".
+#[cfg(feature = "random")]
+pub fn parse_hole_cards(text: &str) -> Result<Vec<[Card; 2]>, String> {
+    text.split('|')
+        .map(|player| match Card::parse_list(player)?.as_slice() {
+            [first, second] => Ok([*first, *second]),
+            _ => Err(format!("Each player needs exactly two hole cards: '{}'", player.trim())),
+        })
+        .collect()
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn calculate_poker_equity(
+    hands: &str,
+    board: &str,
+    iterations: u32,
+    seed: Option<u32>,
+    on_progress: Option<js_sys::Function>,
+) -> Result<JsValue, JsValue> {
+    let hands = parse_hole_cards(hands).map_err(|e| JsValue::from_str(&e))?;
+    let board = Card::parse_list(board).map_err(|e| JsValue::from_str(&e))?;
+    let mut rng = seeded_rng(seed);
+
+    let mut report_progress = |done: u64, total: u64| {
+        if let Some(callback) = &on_progress {
+            let _ = callback.call2(&JsValue::NULL, &JsValue::from_f64(done as f64), &JsValue::from_f64(total as f64));
+        }
+    };
+
+    let report = poker_equity(&hands, &board, iterations as u64, &mut rng, &mut report_progress)
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    Ok(serde_wasm_bindgen::to_value(&report).unwrap())
+}
+
+#[cfg(all(test, feature = "random"))]
+mod tests {
+    use super::*;
+
+    fn deal_hands(players: usize) -> Vec<[Card; 2]> {
+        new_deck().chunks(2).take(players).map(|pair| [pair[0], pair[1]]).collect()
+    }
+
+    #[test]
+    fn rejects_more_players_than_the_deck_can_serve() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let result = poker_equity(&deal_hands(24), &[], 1000, &mut rng, &mut |_, _| {});
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rejects_zero_iterations() {
+        let mut rng = StdRng::seed_from_u64(1);
+        assert!(poker_equity(&deal_hands(2), &[], 0, &mut rng, &mut |_, _| {}).is_err());
+    }
+
+    #[test]
+    fn twenty_three_players_still_get_a_runout() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let report = poker_equity(&deal_hands(23), &[], 1000, &mut rng, &mut |_, _| {}).unwrap();
+        assert!(report.exhaustive);
+        assert_eq!(report.trials, 6);
+    }
+}
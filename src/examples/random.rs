@@ -55,53 +55,13 @@ pub fn roll_dice(num_dice: u32, sides: u32) -> String {
 }
 
 #[cfg(feature = "random")]
-#[derive(Serialize, Deserialize)]
-struct GameCharacter {
-    name: String,
-    level: u32,
-    health: u32,
-    mana: u32,
-    stats: CharacterStats,
-}
-
-#[cfg(feature = "random")]
-#[derive(Serialize, Deserialize)]
-struct CharacterStats {
-    strength: u32,
-    dexterity: u32,
-    intelligence: u32,
-    luck: u32,
-}
-
-#[cfg(feature = "random")]
-#[wasm_bindgen]
-pub fn create_random_character(name: &str) -> JsValue {
-    let mut rng = thread_rng();
-    
-    let character = GameCharacter {
-        name: name.to_string(),
-        level: rng.gen_range(1..=50),
-        health: rng.gen_range(50..=200),
-        mana: rng.gen_range(30..=150),
-        stats: CharacterStats {
-            strength: rng.gen_range(10..=20),
-            dexterity: rng.gen_range(10..=20),
-            intelligence: rng.gen_range(10..=20),
-            luck: rng.gen_range(5..=15),
-        },
-    };
-    
-    serde_wasm_bindgen::to_value(&character).unwrap()
-}
-
-#[cfg(feature = "random")]
-const FIRST_NAMES: &[&str] = &[
+pub(crate) const FIRST_NAMES: &[&str] = &[
     "Aiden", "Bella", "Connor", "Diana", "Ethan", "Fiona", "Gabriel", "Hannah",
     "Isaac", "Jade", "Kyle", "Luna", "Mason", "Nova", "Oscar", "Piper",
 ];
 
 #[cfg(feature = "random")]
-const LAST_NAMES: &[&str] = &[
+pub(crate) const LAST_NAMES: &[&str] = &[
     "Ashford", "Blake", "Cross", "Drake", "Evans", "Fox", "Gray", "Hunt",
     "Kane", "Lane", "Moore", "Nash", "Pierce", "Quinn", "Reed", "Stone",
 ];
@@ -116,22 +76,105 @@ pub fn generate_random_name() -> String {
 }
 
 #[cfg(feature = "random")]
-#[wasm_bindgen]
-pub fn shuffle_and_deal_cards() -> String {
-    let suits = ["♠", "♥", "♦", "♣"];
-    let ranks = ["A", "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K"];
-    
-    let mut deck = Vec::new();
-    for suit in suits.iter() {
-        for rank in ranks.iter() {
-            deck.push(format!("{}{}", rank, suit));
+const SUITS: [&str; 4] = ["♠", "♥", "♦", "♣"];
+
+#[cfg(feature = "random")]
+const RANKS: [&str; 13] = ["2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K", "A"];
+
+/// A playing card. `rank` runs from 2 to 14 (ace high), `suit` indexes `SUITS`.
+#[cfg(feature = "random")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Card {
+    pub rank: u8,
+    pub suit: u8,
+}
+
+#[cfg(feature = "random")]
+impl Card {
+    pub fn new(rank: u8, suit: u8) -> Self {
+        Card { rank, suit }
+    }
+
+    /// Parses cards like "As", "10h", "Td" or "Q♥".
+    pub fn parse(text: &str) -> Option<Card> {
+        let text = text.trim();
+        let suit_char = text.chars().last()?;
+        let rank_text = &text[..text.len() - suit_char.len_utf8()];
+
+        let suit = match suit_char {
+            's' | 'S' | '♠' => 0,
+            'h' | 'H' | '♥' => 1,
+            'd' | 'D' | '♦' => 2,
+            'c' | 'C' | '♣' => 3,
+            _ => return None,
+        };
+        let rank = match rank_text.to_ascii_uppercase().as_str() {
+            "A" => 14,
+            "K" => 13,
+            "Q" => 12,
+            "J" => 11,
+            "T" | "10" => 10,
+            digit => match digit.parse::<u8>() {
+                Ok(n) if (2..=9).contains(&n) => n,
+                _ => return None,
+            },
+        };
+
+        Some(Card { rank, suit })
+    }
+
+    /// Parses a whitespace or comma separated list of cards.
+    pub fn parse_list(text: &str) -> Result<Vec<Card>, String> {
+        text.split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .map(|token| Card::parse(token).ok_or_else(|| format!("Invalid card: {}", token)))
+            .collect()
+    }
+}
+
+#[cfg(feature = "random")]
+impl std::fmt::Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", RANKS[(self.rank - 2) as usize], SUITS[self.suit as usize])
+    }
+}
+
+/// Returns an ordered 52-card deck, suit by suit.
+#[cfg(feature = "random")]
+pub fn new_deck() -> Vec<Card> {
+    let mut deck = Vec::with_capacity(52);
+    for suit in 0..SUITS.len() as u8 {
+        for rank in 2..=14 {
+            deck.push(Card::new(rank, suit));
         }
     }
-    
+    deck
+}
+
+/// Returns `count` shuffled decks concatenated into one shoe.
+#[cfg(feature = "random")]
+pub fn shuffled_shoe<R: Rng + ?Sized>(count: usize, rng: &mut R) -> Vec<Card> {
+    let mut shoe: Vec<Card> = (0..count).flat_map(|_| new_deck()).collect();
+    shoe.shuffle(rng);
+    shoe
+}
+
+/// Returns an RNG seeded from `seed`, or from OS entropy when no seed is given.
+#[cfg(feature = "random")]
+pub fn seeded_rng(seed: Option<u32>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed as u64),
+        None => StdRng::from_entropy(),
+    }
+}
+
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn shuffle_and_deal_cards() -> String {
     let mut rng = thread_rng();
-    deck.shuffle(&mut rng);
+    let deck = shuffled_shoe(1, &mut rng);
     
-    let hand: Vec<String> = deck.iter().take(5).cloned().collect();
+    let hand: Vec<String> = deck.iter().take(5).map(|card| card.to_string()).collect();
     
     format!("Your poker hand: {}", hand.join(", "))
 }
\ No newline at end of file
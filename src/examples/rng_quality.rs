@@ -0,0 +1,342 @@
+#[cfg(feature = "random")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "random")]
+use rand::prelude::*;
+#[cfg(feature = "random")]
+use rand::rngs::OsRng;
+#[cfg(feature = "random")]
+use serde::Serialize;
+
+/// Significance level: a test fails when its p-value is below this.
+#[cfg(feature = "random")]
+pub const DEFAULT_ALPHA: f64 = 0.01;
+
+#[cfg(feature = "random")]
+const CHI_SQUARE_BINS: usize = 64;
+
+// Birthday spacings parameters: 512 birthdays in a 2^24-day year give
+// lambda = 512^3 / (4 * 2^24) = 2 expected duplicate spacings per round.
+#[cfg(feature = "random")]
+const BIRTHDAYS: usize = 512;
+#[cfg(feature = "random")]
+const BIRTHDAY_YEAR_BITS: u32 = 24;
+#[cfg(feature = "random")]
+const BIRTHDAY_ROUNDS: usize = 20;
+
+/// Log-gamma by the Lanczos approximation.
+#[cfg(feature = "random")]
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.001_208_650_973_866_179,
+        -0.000_005_395_239_384_953,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let series = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .fold(1.000_000_000_190_015, |acc, (i, c)| acc + c / (x + 1.0 + i as f64));
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+/// Upper regularized incomplete gamma function Q(a, x).
+#[cfg(feature = "random")]
+pub fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let prefix = (-x + a * x.ln() - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        // Series for P(a, x).
+        let (mut term, mut sum, mut n) = (1.0 / a, 1.0 / a, a);
+        for _ in 0..1000 {
+            n += 1.0;
+            term *= x / n;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        (1.0 - sum * prefix).clamp(0.0, 1.0)
+    } else {
+        // Continued fraction for Q(a, x) (modified Lentz).
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..1000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            d = if d.abs() < tiny { tiny } else { d };
+            c = b + an / c;
+            c = if c.abs() < tiny { tiny } else { c };
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        (prefix * h).clamp(0.0, 1.0)
+    }
+}
+
+/// Complementary error function, via erfc(x) = Q(1/2, x^2) for x >= 0.
+#[cfg(feature = "random")]
+pub fn erfc(x: f64) -> f64 {
+    if x >= 0.0 {
+        gamma_q(0.5, x * x)
+    } else {
+        2.0 - gamma_q(0.5, x * x)
+    }
+}
+
+#[cfg(feature = "random")]
+fn poisson_cdf(k: u64, lambda: f64) -> f64 {
+    let mut term = (-lambda).exp();
+    let mut sum = term;
+    for i in 1..=k {
+        term *= lambda / i as f64;
+        sum += term;
+    }
+    sum.min(1.0)
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct RandomnessTestResult {
+    pub name: String,
+    pub statistic: f64,
+    pub p_value: f64,
+    pub passed: bool,
+}
+
+#[cfg(feature = "random")]
+#[derive(Serialize, Clone, Debug)]
+pub struct RandomnessReport {
+    pub generator: String,
+    pub sample_size: usize,
+    pub alpha: f64,
+    pub tests: Vec<RandomnessTestResult>,
+    pub passed: usize,
+    pub failed: usize,
+    pub all_passed: bool,
+}
+
+#[cfg(feature = "random")]
+fn result(name: &str, statistic: f64, p_value: f64, alpha: f64) -> RandomnessTestResult {
+    RandomnessTestResult { name: name.to_string(), statistic, p_value, passed: p_value >= alpha }
+}
+
+/// NIST SP 800-22 frequency (monobit) test over the bits of `words`.
+#[cfg(feature = "random")]
+pub fn monobit_test(words: &[u32], alpha: f64) -> RandomnessTestResult {
+    let n = words.len() as f64 * 32.0;
+    let ones: u64 = words.iter().map(|w| w.count_ones() as u64).sum();
+    let sum = 2.0 * ones as f64 - n;
+    let statistic = sum.abs() / n.sqrt();
+    result("monobit_frequency", statistic, erfc(statistic / std::f64::consts::SQRT_2), alpha)
+}
+
+/// NIST SP 800-22 runs test: counts maximal blocks of identical bits.
+#[cfg(feature = "random")]
+pub fn runs_test(words: &[u32], alpha: f64) -> RandomnessTestResult {
+    let n = words.len() as f64 * 32.0;
+    let ones: u64 = words.iter().map(|w| w.count_ones() as u64).sum();
+    let pi = ones as f64 / n;
+
+    // The runs test presumes the frequency test passes.
+    if (pi - 0.5).abs() >= 2.0 / n.sqrt() {
+        return result("runs", f64::NAN, 0.0, alpha);
+    }
+
+    let mut runs = 1u64;
+    let mut previous = words[0] & 1;
+    for word in words {
+        for bit in 0..32 {
+            let current = (word >> bit) & 1;
+            if current != previous {
+                runs += 1;
+                previous = current;
+            }
+        }
+    }
+
+    let expected = 2.0 * n * pi * (1.0 - pi);
+    let p_value = erfc((runs as f64 - expected).abs() / (2.0 * (2.0 * n).sqrt() * pi * (1.0 - pi)));
+    result("runs", runs as f64, p_value, alpha)
+}
+
+/// Pearson chi-square test that values fall evenly into equal-width bins.
+#[cfg(feature = "random")]
+pub fn chi_square_uniformity_test(values: &[f64], alpha: f64) -> RandomnessTestResult {
+    let mut counts = [0u64; CHI_SQUARE_BINS];
+    for &v in values {
+        counts[((v * CHI_SQUARE_BINS as f64) as usize).min(CHI_SQUARE_BINS - 1)] += 1;
+    }
+    let expected = values.len() as f64 / CHI_SQUARE_BINS as f64;
+    let statistic: f64 = counts.iter().map(|&c| (c as f64 - expected).powi(2) / expected).sum();
+    let degrees_of_freedom = (CHI_SQUARE_BINS - 1) as f64;
+    result("chi_square_uniformity", statistic, gamma_q(degrees_of_freedom / 2.0, statistic / 2.0), alpha)
+}
+
+/// Lag-1 serial correlation; under independence `r * sqrt(n)` is roughly N(0, 1).
+#[cfg(feature = "random")]
+pub fn serial_correlation_test(values: &[f64], alpha: f64) -> RandomnessTestResult {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance: f64 = values.iter().map(|v| (v - mean).powi(2)).sum();
+    let covariance: f64 = values.windows(2).map(|w| (w[0] - mean) * (w[1] - mean)).sum();
+    let r = covariance / variance;
+    let z = r * n.sqrt();
+    result("serial_correlation", r, erfc(z.abs() / std::f64::consts::SQRT_2), alpha)
+}
+
+/// Marsaglia's birthday spacings test. The number of repeated spacings
+/// between sorted birthdays is Poisson distributed; the p-value is two-sided.
+#[cfg(feature = "random")]
+pub fn birthday_spacings_test<R: RngCore + ?Sized>(rng: &mut R, alpha: f64) -> RandomnessTestResult {
+    let mut duplicates = 0u64;
+    for _ in 0..BIRTHDAY_ROUNDS {
+        let mut birthdays: Vec<u32> = (0..BIRTHDAYS).map(|_| rng.next_u32() >> (32 - BIRTHDAY_YEAR_BITS)).collect();
+        birthdays.sort_unstable();
+        let mut spacings: Vec<u32> = birthdays.windows(2).map(|w| w[1] - w[0]).collect();
+        spacings.sort_unstable();
+        duplicates += spacings.windows(2).filter(|w| w[0] == w[1]).count() as u64;
+    }
+
+    let lambda_per_round = (BIRTHDAYS as f64).powi(3) / (4.0 * (1u64 << BIRTHDAY_YEAR_BITS) as f64);
+    let lambda = lambda_per_round * BIRTHDAY_ROUNDS as f64;
+    let lower = poisson_cdf(duplicates, lambda);
+    let upper = 1.0 - if duplicates == 0 { 0.0 } else { poisson_cdf(duplicates - 1, lambda) };
+    result("birthday_spacings", duplicates as f64, (2.0 * lower.min(upper)).min(1.0), alpha)
+}
+
+/// Runs every test on `sample_size` 32-bit outputs from `rng`. Works with
+/// any `RngCore`, so native code can check generators directly.
+#[cfg(feature = "random")]
+pub fn run_randomness_tests<R: RngCore + ?Sized>(
+    generator: &str,
+    rng: &mut R,
+    sample_size: usize,
+    alpha: f64,
+) -> Result<RandomnessReport, String> {
+    if sample_size < 1000 {
+        return Err("Use a sample size of at least 1000 values".to_string());
+    }
+
+    let words: Vec<u32> = (0..sample_size).map(|_| rng.next_u32()).collect();
+    let values: Vec<f64> = words.iter().map(|&w| w as f64 / 4_294_967_296.0).collect();
+
+    let tests = vec![
+        monobit_test(&words, alpha),
+        runs_test(&words, alpha),
+        chi_square_uniformity_test(&values, alpha),
+        serial_correlation_test(&values, alpha),
+        birthday_spacings_test(rng, alpha),
+    ];
+    let passed = tests.iter().filter(|t| t.passed).count();
+
+    Ok(RandomnessReport {
+        generator: generator.to_string(),
+        sample_size,
+        alpha,
+        failed: tests.len() - passed,
+        all_passed: passed == tests.len(),
+        passed,
+        tests,
+    })
+}
+
+/// IBM's RANDU, a famously poor LCG, kept as a known-bad reference.
+#[cfg(feature = "random")]
+pub struct Randu {
+    state: u32,
+}
+
+#[cfg(feature = "random")]
+impl Randu {
+    pub fn new(seed: u32) -> Self {
+        // RANDU needs an odd seed.
+        Randu { state: (seed | 1) & 0x7fff_ffff }
+    }
+}
+
+#[cfg(feature = "random")]
+impl RngCore for Randu {
+    fn next_u32(&mut self) -> u32 {
+        self.state = self.state.wrapping_mul(65539) & 0x7fff_ffff;
+        self.state << 1
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// `generator` is "thread" (`thread_rng`), "seeded" (`StdRng`, ChaCha12),
+/// "os" (`OsRng`) or "randu" (a deliberately weak reference generator).
+#[cfg(feature = "random")]
+#[wasm_bindgen]
+pub fn test_random_generator(generator: &str, sample_size: u32, seed: Option<u32>) -> Result<JsValue, JsValue> {
+    let seed = seed.unwrap_or(0);
+    let mut rng: Box<dyn RngCore> = match generator {
+        "thread" => Box::new(thread_rng()),
+        "seeded" => Box::new(StdRng::seed_from_u64(seed as u64)),
+        "os" => Box::new(OsRng),
+        "randu" => Box::new(Randu::new(seed)),
+        other => return Err(JsValue::from_str(&format!("Unknown generator: {}", other))),
+    };
+    let report = run_randomness_tests(generator, &mut rng, sample_size as usize, DEFAULT_ALPHA)
+        .map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&report).unwrap())
+}
+
+#[cfg(all(test, feature = "random"))]
+mod tests {
+    use super::*;
+
+    fn outcome(report: &RandomnessReport, name: &str) -> bool {
+        report.tests.iter().find(|t| t.name == name).unwrap().passed
+    }
+
+    #[test]
+    fn std_rng_passes_the_battery() {
+        for seed in 1..=3 {
+            let report = run_randomness_tests("seeded", &mut StdRng::seed_from_u64(seed), 50_000, DEFAULT_ALPHA).unwrap();
+            assert!(report.all_passed, "seed {}: {:?}", seed, report.tests);
+        }
+    }
+
+    #[test]
+    fn randu_fails_the_nist_bit_tests() {
+        for seed in [1, 12345] {
+            let report = run_randomness_tests("randu", &mut Randu::new(seed), 50_000, DEFAULT_ALPHA).unwrap();
+            assert!(!outcome(&report, "monobit_frequency"), "seed {}", seed);
+            assert!(!outcome(&report, "runs"), "seed {}", seed);
+            assert!(!report.all_passed);
+        }
+    }
+
+    #[test]
+    fn rejects_tiny_samples() {
+        assert!(run_randomness_tests("seeded", &mut StdRng::seed_from_u64(1), 999, DEFAULT_ALPHA).is_err());
+    }
+}
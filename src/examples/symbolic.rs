@@ -0,0 +1,711 @@
+#[cfg(feature = "math")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "math")]
+use serde::Serialize;
+#[cfg(feature = "math")]
+use std::collections::HashMap;
+#[cfg(feature = "math")]
+use super::expression::{parse_error_to_js, parse_expression_source, BinaryOp, Expr, MathConstant, MathFunction};
+
+/// The simplifier reruns until the printed form stops changing, up to this
+/// many passes.
+#[cfg(feature = "math")]
+const MAX_SIMPLIFY_PASSES: usize = 16;
+
+#[cfg(feature = "math")]
+fn num(value: f64) -> Expr {
+    Expr::number(value)
+}
+
+#[cfg(feature = "math")]
+fn add(a: Expr, b: Expr) -> Expr {
+    Expr::binary(BinaryOp::Add, a, b)
+}
+
+#[cfg(feature = "math")]
+fn sub(a: Expr, b: Expr) -> Expr {
+    Expr::binary(BinaryOp::Sub, a, b)
+}
+
+#[cfg(feature = "math")]
+fn mul(a: Expr, b: Expr) -> Expr {
+    Expr::binary(BinaryOp::Mul, a, b)
+}
+
+#[cfg(feature = "math")]
+fn div(a: Expr, b: Expr) -> Expr {
+    Expr::binary(BinaryOp::Div, a, b)
+}
+
+#[cfg(feature = "math")]
+fn pow(a: Expr, b: Expr) -> Expr {
+    Expr::binary(BinaryOp::Pow, a, b)
+}
+
+#[cfg(feature = "math")]
+fn call(function: MathFunction, arg: Expr) -> Expr {
+    Expr::call(function, vec![arg])
+}
+
+#[cfg(feature = "math")]
+fn depends_on(expr: &Expr, variable: &str) -> bool {
+    expr.variables().contains(variable)
+}
+
+/// Symbolic derivative of `expr` with respect to `variable`, unsimplified.
+#[cfg(feature = "math")]
+pub fn differentiate(expr: &Expr, variable: &str) -> Result<Expr, String> {
+    Ok(match expr {
+        Expr::Number { .. } | Expr::Constant { .. } => num(0.0),
+        Expr::Variable { name } => num(if name == variable { 1.0 } else { 0.0 }),
+        Expr::Negate { operand } => Expr::negate(differentiate(operand, variable)?),
+        Expr::Binary { op, left, right } => {
+            let (u, v) = (left.as_ref().clone(), right.as_ref().clone());
+            let (du, dv) = (differentiate(&u, variable)?, differentiate(&v, variable)?);
+            match op {
+                BinaryOp::Add => add(du, dv),
+                BinaryOp::Sub => sub(du, dv),
+                // Product rule: (uv)' = u'v + uv'
+                BinaryOp::Mul => add(mul(du, v), mul(u, dv)),
+                // Quotient rule: (u/v)' = (u'v - uv') / v^2
+                BinaryOp::Div => div(sub(mul(du, v.clone()), mul(u, dv)), pow(v, num(2.0))),
+                BinaryOp::Pow => {
+                    if !depends_on(&v, variable) {
+                        // Power rule with the chain rule: n u^(n-1) u'
+                        mul(mul(v.clone(), pow(u, sub(v, num(1.0)))), du)
+                    } else if !depends_on(&u, variable) {
+                        // a^v ln(a) v'
+                        mul(mul(expr.clone(), call(MathFunction::Ln, u)), dv)
+                    } else {
+                        // u^v (v' ln u + v u'/u)
+                        mul(expr.clone(), add(mul(dv, call(MathFunction::Ln, u.clone())), div(mul(v, du), u)))
+                    }
+                }
+            }
+        }
+        Expr::Call { function, args } => {
+            if let MathFunction::Atan2 = function {
+                // atan2(y, x)' = (x y' - y x') / (x^2 + y^2)
+                let (y, x) = (args[0].clone(), args[1].clone());
+                let (dy, dx) = (differentiate(&y, variable)?, differentiate(&x, variable)?);
+                return Ok(div(
+                    sub(mul(x.clone(), dy), mul(y.clone(), dx)),
+                    add(pow(x, num(2.0)), pow(y, num(2.0))),
+                ));
+            }
+            if function.arity() != 1 {
+                return Err(format!("'{}' has no symbolic derivative", function.name()));
+            }
+            let u = args[0].clone();
+            let du = differentiate(&u, variable)?;
+            let outer = match function {
+                MathFunction::Sin => call(MathFunction::Cos, u),
+                MathFunction::Cos => Expr::negate(call(MathFunction::Sin, u)),
+                MathFunction::Tan => div(num(1.0), pow(call(MathFunction::Cos, u), num(2.0))),
+                MathFunction::Asin => div(num(1.0), call(MathFunction::Sqrt, sub(num(1.0), pow(u, num(2.0))))),
+                MathFunction::Acos => Expr::negate(div(num(1.0), call(MathFunction::Sqrt, sub(num(1.0), pow(u, num(2.0)))))),
+                MathFunction::Atan => div(num(1.0), add(num(1.0), pow(u, num(2.0)))),
+                MathFunction::Sinh => call(MathFunction::Cosh, u),
+                MathFunction::Cosh => call(MathFunction::Sinh, u),
+                MathFunction::Tanh => div(num(1.0), pow(call(MathFunction::Cosh, u), num(2.0))),
+                MathFunction::Exp => call(MathFunction::Exp, u),
+                MathFunction::Ln => div(num(1.0), u),
+                MathFunction::Log10 => div(num(1.0), mul(u, call(MathFunction::Ln, num(10.0)))),
+                MathFunction::Log2 => div(num(1.0), mul(u, call(MathFunction::Ln, num(2.0)))),
+                MathFunction::Sqrt => div(num(1.0), mul(num(2.0), call(MathFunction::Sqrt, u))),
+                MathFunction::Cbrt => div(num(1.0), mul(num(3.0), pow(call(MathFunction::Cbrt, u), num(2.0)))),
+                MathFunction::Abs => call(MathFunction::Sign, u),
+                // Piecewise constant: zero wherever the derivative exists.
+                MathFunction::Floor | MathFunction::Ceil | MathFunction::Round | MathFunction::Sign => num(0.0),
+                MathFunction::Min | MathFunction::Max | MathFunction::Atan2 => unreachable!(),
+            };
+            mul(outer, du)
+        }
+    })
+}
+
+/// Folds a computed constant only when it prints exactly, so 1/3 stays a
+/// fraction and sqrt(2) stays symbolic.
+#[cfg(feature = "math")]
+fn fold(value: f64) -> Option<Expr> {
+    (value.is_finite() && (value * 1e6).fract() == 0.0).then(|| num(value))
+}
+
+#[cfg(feature = "math")]
+fn as_number(expr: &Expr) -> Option<f64> {
+    match expr {
+        Expr::Number { value } => Some(*value),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "math")]
+fn key(expr: &Expr) -> String {
+    expr.to_string()
+}
+
+/// Flattens sums and differences into signed terms with numeric coefficients.
+#[cfg(feature = "math")]
+fn collect_terms(expr: &Expr, sign: f64, terms: &mut Vec<(f64, Option<Expr>)>) {
+    match expr {
+        Expr::Binary { op: BinaryOp::Add, left, right } => {
+            collect_terms(left, sign, terms);
+            collect_terms(right, sign, terms);
+        }
+        Expr::Binary { op: BinaryOp::Sub, left, right } => {
+            collect_terms(left, sign, terms);
+            collect_terms(right, -sign, terms);
+        }
+        Expr::Negate { operand } => collect_terms(operand, -sign, terms),
+        _ => {
+            let (coefficient, rest) = split_coefficient(expr);
+            terms.push((sign * coefficient, rest));
+        }
+    }
+}
+
+/// Flattens a product into its numeric coefficient and remaining factors.
+#[cfg(feature = "math")]
+fn collect_factors(expr: &Expr, coefficient: &mut f64, factors: &mut Vec<Expr>) {
+    match expr {
+        Expr::Binary { op: BinaryOp::Mul, left, right } => {
+            collect_factors(left, coefficient, factors);
+            collect_factors(right, coefficient, factors);
+        }
+        Expr::Negate { operand } => {
+            *coefficient = -*coefficient;
+            collect_factors(operand, coefficient, factors);
+        }
+        Expr::Number { value } => *coefficient *= value,
+        other => factors.push(other.clone()),
+    }
+}
+
+#[cfg(feature = "math")]
+fn product_of(factors: Vec<Expr>) -> Option<Expr> {
+    factors.into_iter().reduce(mul)
+}
+
+#[cfg(feature = "math")]
+fn split_coefficient(expr: &Expr) -> (f64, Option<Expr>) {
+    if let Expr::Binary { op: BinaryOp::Div, left, right } = expr {
+        let (coefficient, rest) = split_coefficient(left);
+        return (coefficient, Some(div(rest.unwrap_or_else(|| num(1.0)), *right.clone())));
+    }
+    let mut coefficient = 1.0;
+    let mut factors = Vec::new();
+    collect_factors(expr, &mut coefficient, &mut factors);
+    (coefficient, product_of(factors))
+}
+
+/// Like-term collection: 2x + 3x becomes 5x, x - x becomes 0.
+#[cfg(feature = "math")]
+fn simplify_sum(expr: &Expr) -> Expr {
+    let mut terms = Vec::new();
+    collect_terms(expr, 1.0, &mut terms);
+
+    let mut constant = 0.0;
+    let mut grouped: Vec<(String, f64, Expr)> = Vec::new();
+    for (coefficient, term) in terms {
+        match term {
+            None => constant += coefficient,
+            Some(term) => {
+                let k = key(&term);
+                match grouped.iter_mut().find(|(existing, _, _)| *existing == k) {
+                    Some((_, c, _)) => *c += coefficient,
+                    None => grouped.push((k, coefficient, term)),
+                }
+            }
+        }
+    }
+
+    let mut pieces: Vec<(f64, Option<Expr>)> = grouped
+        .into_iter()
+        .filter(|(_, c, _)| *c != 0.0)
+        .map(|(_, c, term)| (c, Some(term)))
+        .collect();
+    if constant != 0.0 {
+        pieces.push((constant, None));
+    }
+
+    let magnitude = |c: f64, term: Option<Expr>| match term {
+        None => num(c.abs()),
+        Some(term) if c.abs() == 1.0 => term,
+        Some(term) => simplify_product(&mul(num(c.abs()), term)),
+    };
+    let mut result: Option<Expr> = None;
+    for (c, term) in pieces {
+        let piece = magnitude(c, term);
+        result = Some(match (result, c < 0.0) {
+            (None, false) => piece,
+            (None, true) => simplify_product(&Expr::negate(piece)),
+            (Some(acc), false) => add(acc, piece),
+            (Some(acc), true) => sub(acc, piece),
+        });
+    }
+    result.unwrap_or_else(|| num(0.0))
+}
+
+/// Flattens products and quotients into numerator and denominator
+/// coefficients plus (base, exponent) factors, with denominator factors
+/// carrying negated exponents.
+#[cfg(feature = "math")]
+fn collect_quotient(expr: &Expr, inverted: bool, coefficients: &mut [f64; 2], factors: &mut Vec<(Expr, Expr)>) {
+    match expr {
+        Expr::Binary { op: BinaryOp::Mul, left, right } => {
+            collect_quotient(left, inverted, coefficients, factors);
+            collect_quotient(right, inverted, coefficients, factors);
+        }
+        Expr::Binary { op: BinaryOp::Div, left, right } => {
+            collect_quotient(left, inverted, coefficients, factors);
+            collect_quotient(right, !inverted, coefficients, factors);
+        }
+        Expr::Negate { operand } => {
+            coefficients[0] = -coefficients[0];
+            collect_quotient(operand, inverted, coefficients, factors);
+        }
+        Expr::Number { value } => coefficients[inverted as usize] *= value,
+        Expr::Binary { op: BinaryOp::Pow, left, right } => {
+            let exponent = if inverted { simplify_once(&Expr::negate(*right.clone())) } else { *right.clone() };
+            factors.push((*left.clone(), exponent));
+        }
+        other => factors.push((other.clone(), num(if inverted { -1.0 } else { 1.0 }))),
+    }
+}
+
+/// Multiplies out coefficients, merges powers of the same base (x * x^2
+/// becomes x^3, x^3 / x becomes x^2) and sorts factors into a canonical
+/// order so equal products print identically.
+#[cfg(feature = "math")]
+fn simplify_product(expr: &Expr) -> Expr {
+    let mut coefficients = [1.0, 1.0];
+    let mut factors = Vec::new();
+    collect_quotient(expr, false, &mut coefficients, &mut factors);
+    let [mut numerator_coefficient, mut denominator_coefficient] = coefficients;
+    if numerator_coefficient == 0.0 {
+        return num(0.0);
+    }
+    // Cancel the denominator's coefficient only when that stays exact.
+    let ratio = numerator_coefficient / denominator_coefficient;
+    let inverse = denominator_coefficient / numerator_coefficient;
+    if ratio.is_finite() && ratio.fract() == 0.0 {
+        numerator_coefficient = ratio;
+        denominator_coefficient = 1.0;
+    } else if inverse.is_finite() && inverse.fract() == 0.0 {
+        numerator_coefficient = inverse.signum();
+        denominator_coefficient = inverse.abs();
+    }
+
+    let mut powers: Vec<(String, Expr, Expr)> = Vec::new();
+    for (base, exponent) in factors {
+        let k = key(&base);
+        match powers.iter_mut().find(|(existing, _, _)| *existing == k) {
+            Some((_, _, total)) => {
+                *total = match (as_number(total), as_number(&exponent)) {
+                    (Some(a), Some(b)) => num(a + b),
+                    _ => simplify_once(&add(total.clone(), exponent)),
+                }
+            }
+            None => powers.push((k, base, exponent)),
+        }
+    }
+    powers.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let (mut numerator, mut denominator) = (Vec::new(), Vec::new());
+    for (_, base, exponent) in powers {
+        match as_number(&exponent) {
+            Some(0.0) => {}
+            Some(1.0) => numerator.push(base),
+            Some(-1.0) => denominator.push(base),
+            Some(e) if e < 0.0 => denominator.push(pow(base, num(-e))),
+            _ => numerator.push(pow(base, exponent)),
+        }
+    }
+
+    // A coefficient of -1 becomes a negation; any other sign stays on the
+    // leading number so "-2 * x" doesn't print as "-(2 * x)".
+    let negate = numerator_coefficient == -1.0 && !numerator.is_empty();
+    if numerator_coefficient.abs() != 1.0 || numerator.is_empty() {
+        numerator.insert(0, num(numerator_coefficient));
+    }
+    if denominator_coefficient != 1.0 {
+        denominator.insert(0, num(denominator_coefficient));
+    }
+    let mut top = product_of(numerator).unwrap();
+    if negate {
+        top = Expr::negate(top);
+    }
+    match product_of(denominator) {
+        None => top,
+        Some(bottom) => div(top, bottom),
+    }
+}
+
+/// One bottom-up simplification pass.
+#[cfg(feature = "math")]
+fn simplify_once(expr: &Expr) -> Expr {
+    match expr {
+        Expr::Number { .. } | Expr::Constant { .. } | Expr::Variable { .. } => expr.clone(),
+        Expr::Negate { operand } => match simplify_once(operand) {
+            Expr::Number { value } => num(-value),
+            Expr::Negate { operand } => *operand,
+            other => simplify_sum(&Expr::negate(other)),
+        },
+        Expr::Binary { op, left, right } => {
+            let (a, b) = (simplify_once(left), simplify_once(right));
+            if let (Some(x), Some(y)) = (as_number(&a), as_number(&b)) {
+                let value = match op {
+                    BinaryOp::Add => x + y,
+                    BinaryOp::Sub => x - y,
+                    BinaryOp::Mul => x * y,
+                    BinaryOp::Div => x / y,
+                    BinaryOp::Pow => x.powf(y),
+                };
+                if let Some(folded) = fold(value) {
+                    return folded;
+                }
+            }
+            match op {
+                BinaryOp::Add | BinaryOp::Sub => simplify_sum(&Expr::binary(*op, a, b)),
+                BinaryOp::Mul => simplify_product(&mul(a, b)),
+                BinaryOp::Div => simplify_product(&div(a, b)),
+                BinaryOp::Pow => match (as_number(&a), as_number(&b)) {
+                    (_, Some(0.0)) => num(1.0),
+                    (_, Some(1.0)) => a,
+                    (Some(1.0), _) => num(1.0),
+                    _ => match a {
+                        // (u^m)^n = u^(mn) only holds for every real u when n is an
+                        // integer or m is odd: (x^2)^0.5 is |x|, not x.
+                        Expr::Binary { op: BinaryOp::Pow, left, right } if merges_exponents(as_number(&right), as_number(&b)) => {
+                            pow(*left, num(as_number(&right).unwrap() * as_number(&b).unwrap()))
+                        }
+                        a => pow(a, b),
+                    },
+                },
+            }
+        }
+        Expr::Call { function, args } => {
+            let args: Vec<Expr> = args.iter().map(simplify_once).collect();
+            if let Some(values) = args.iter().map(as_number).collect::<Option<Vec<f64>>>() {
+                if let Some(folded) = fold(function.apply(&values)) {
+                    return folded;
+                }
+            }
+            // exp(ln(u)) is only u where u > 0, so that pair is left alone.
+            match (function, &args[0]) {
+                (MathFunction::Ln, Expr::Call { function: MathFunction::Exp, args: inner }) => inner[0].clone(),
+                (MathFunction::Ln, Expr::Constant { constant: MathConstant::E }) => num(1.0),
+                _ => Expr::call(*function, args),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "math")]
+fn merges_exponents(inner: Option<f64>, outer: Option<f64>) -> bool {
+    match (inner, outer) {
+        (Some(m), Some(n)) => n.fract() == 0.0 || (m.fract() == 0.0 && m.rem_euclid(2.0) == 1.0),
+        _ => false,
+    }
+}
+
+/// Constant folding, identity elimination (x + 0, x * 1, x^1, ...) and
+/// like-term collection, repeated until nothing changes.
+#[cfg(feature = "math")]
+pub fn simplify(expr: &Expr) -> Expr {
+    let mut current = expr.clone();
+    for _ in 0..MAX_SIMPLIFY_PASSES {
+        let next = simplify_once(&current);
+        if key(&next) == key(&current) {
+            return next;
+        }
+        current = next;
+    }
+    current
+}
+
+/// Central-difference estimate of d(expr)/d(variable) at `point`.
+#[cfg(feature = "math")]
+pub fn numeric_derivative(expr: &Expr, variable: &str, point: &HashMap<String, f64>) -> Result<f64, String> {
+    let x = *point.get(variable).ok_or_else(|| format!("No value for '{}'", variable))?;
+    let h = 1e-6 * x.abs().max(1.0);
+    let at = |value: f64| {
+        expr.eval_with(&|name| if name == variable { Some(value) } else { point.get(name).copied() })
+    };
+    Ok((at(x + h)? - at(x - h)?) / (2.0 * h))
+}
+
+#[cfg(feature = "math")]
+fn latex_variable(name: &str) -> String {
+    let (base, subscript) = match name.split_once('_') {
+        Some((base, subscript)) => (base, Some(subscript)),
+        None => (name, None),
+    };
+    let base = if base.chars().count() > 1 { format!("\\mathrm{{{}}}", base) } else { base.to_string() };
+    match subscript {
+        Some(s) => format!("{}_{{{}}}", base, s),
+        None => base,
+    }
+}
+
+#[cfg(feature = "math")]
+fn latex_number(value: f64) -> String {
+    let text = Expr::number(value).to_string();
+    match text.split_once('e') {
+        Some((mantissa, exponent)) => format!("{} \\times 10^{{{}}}", mantissa, exponent),
+        None => text,
+    }
+}
+
+/// Binding strength for LaTeX parenthesisation; fractions group themselves.
+#[cfg(feature = "math")]
+fn latex_precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Binary { op: BinaryOp::Add | BinaryOp::Sub, .. } => 1,
+        Expr::Binary { op: BinaryOp::Mul | BinaryOp::Div, .. } => 2,
+        Expr::Negate { .. } => 2,
+        Expr::Number { value } if *value < 0.0 => 2,
+        Expr::Binary { op: BinaryOp::Pow, .. } => 3,
+        _ => 4,
+    }
+}
+
+#[cfg(feature = "math")]
+fn latex_wrapped(expr: &Expr, needs: bool) -> String {
+    if needs {
+        format!("\\left({}\\right)", to_latex(expr))
+    } else {
+        to_latex(expr)
+    }
+}
+
+#[cfg(feature = "math")]
+pub fn to_latex(expr: &Expr) -> String {
+    match expr {
+        Expr::Number { value } => latex_number(*value),
+        Expr::Constant { constant } => match constant {
+            MathConstant::Pi => "\\pi".to_string(),
+            MathConstant::E => "e".to_string(),
+            MathConstant::Tau => "\\tau".to_string(),
+        },
+        Expr::Variable { name } => latex_variable(name),
+        Expr::Negate { operand } => format!("-{}", latex_wrapped(operand, latex_precedence(operand) <= 2)),
+        Expr::Binary { op, left, right } => match op {
+            BinaryOp::Add => format!("{} + {}", to_latex(left), to_latex(right)),
+            BinaryOp::Sub => format!("{} - {}", to_latex(left), latex_wrapped(right, latex_precedence(right) <= 1)),
+            BinaryOp::Div => format!("\\frac{{{}}}{{{}}}", to_latex(left), to_latex(right)),
+            BinaryOp::Mul => {
+                let l = latex_wrapped(left, latex_precedence(left) < 2);
+                let r = latex_wrapped(right, latex_precedence(right) <= 2 && !matches!(**right, Expr::Binary { op: BinaryOp::Div, .. }));
+                // Juxtapose "2x" but keep a dot between numbers and before a
+                // leading digit or sign.
+                let starts_numeric = r.starts_with(|c: char| c.is_ascii_digit() || c == '-');
+                if starts_numeric || !matches!(**left, Expr::Number { .. }) {
+                    format!("{} \\cdot {}", l, r)
+                } else {
+                    format!("{}{}", l, r)
+                }
+            }
+            BinaryOp::Pow => {
+                let base = match **left {
+                    Expr::Call { .. } => latex_wrapped(left, true),
+                    _ => latex_wrapped(left, latex_precedence(left) <= 3),
+                };
+                format!("{}^{{{}}}", base, to_latex(right))
+            }
+        },
+        Expr::Call { function, args } => {
+            let a = to_latex(&args[0]);
+            match function {
+                MathFunction::Sqrt => format!("\\sqrt{{{}}}", a),
+                MathFunction::Cbrt => format!("\\sqrt[3]{{{}}}", a),
+                MathFunction::Abs => format!("\\left|{}\\right|", a),
+                MathFunction::Floor => format!("\\left\\lfloor {} \\right\\rfloor", a),
+                MathFunction::Ceil => format!("\\left\\lceil {} \\right\\rceil", a),
+                MathFunction::Exp => format!("e^{{{}}}", a),
+                MathFunction::Log10 => format!("\\log_{{10}}\\left({}\\right)", a),
+                MathFunction::Log2 => format!("\\log_{{2}}\\left({}\\right)", a),
+                MathFunction::Min | MathFunction::Max | MathFunction::Atan2 => {
+                    let name = match function {
+                        MathFunction::Min => "\\min",
+                        MathFunction::Max => "\\max",
+                        _ => "\\operatorname{atan2}",
+                    };
+                    format!("{}\\left({}, {}\\right)", name, a, to_latex(&args[1]))
+                }
+                _ => {
+                    let name = match function {
+                        MathFunction::Sin => "\\sin",
+                        MathFunction::Cos => "\\cos",
+                        MathFunction::Tan => "\\tan",
+                        MathFunction::Asin => "\\arcsin",
+                        MathFunction::Acos => "\\arccos",
+                        MathFunction::Atan => "\\arctan",
+                        MathFunction::Sinh => "\\sinh",
+                        MathFunction::Cosh => "\\cosh",
+                        MathFunction::Tanh => "\\tanh",
+                        MathFunction::Ln => "\\ln",
+                        MathFunction::Round => "\\operatorname{round}",
+                        _ => "\\operatorname{sgn}",
+                    };
+                    format!("{}\\left({}\\right)", name, a)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct DerivativeCheck {
+    pub at: f64,
+    pub symbolic: f64,
+    pub numeric: f64,
+    pub abs_error: f64,
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct DerivativeResult {
+    pub expression: String,
+    pub expression_latex: String,
+    pub variable: String,
+    /// Straight from the differentiation rules, before simplification.
+    pub unsimplified: String,
+    pub derivative: String,
+    pub derivative_latex: String,
+    pub ast: Expr,
+    pub check: Option<DerivativeCheck>,
+}
+
+#[cfg(feature = "math")]
+pub fn derivative_report(expr: &Expr, variable: &str, at: Option<f64>) -> Result<DerivativeResult, String> {
+    let raw = differentiate(expr, variable)?;
+    let derivative = simplify(&raw);
+
+    let check = match at {
+        Some(x) => {
+            let point: HashMap<String, f64> = [(variable.to_string(), x)].into_iter().collect();
+            let symbolic = derivative.eval(&point)?;
+            let numeric = numeric_derivative(expr, variable, &point)?;
+            Some(DerivativeCheck { at: x, symbolic, numeric, abs_error: (symbolic - numeric).abs() })
+        }
+        None => None,
+    };
+
+    Ok(DerivativeResult {
+        expression: expr.to_string(),
+        expression_latex: to_latex(expr),
+        variable: variable.to_string(),
+        unsimplified: raw.to_string(),
+        derivative: derivative.to_string(),
+        derivative_latex: to_latex(&derivative),
+        ast: derivative,
+        check,
+    })
+}
+
+/// Differentiates `source` with respect to `variable`. With `at`, the
+/// result includes a finite-difference check at that point.
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub fn differentiate_expression(source: &str, variable: &str, at: Option<f64>) -> Result<JsValue, JsValue> {
+    let expr = parse_expression_source(source).map_err(parse_error_to_js)?;
+    let report = derivative_report(&expr, variable, at).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&report).unwrap())
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize)]
+struct SimplifiedExpression {
+    text: String,
+    latex: String,
+}
+
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub fn simplify_expression(source: &str) -> Result<JsValue, JsValue> {
+    let expr = simplify(&parse_expression_source(source).map_err(parse_error_to_js)?);
+    let result = SimplifiedExpression { text: expr.to_string(), latex: to_latex(&expr) };
+    Ok(serde_wasm_bindgen::to_value(&result).unwrap())
+}
+
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub fn expression_to_latex(source: &str) -> Result<String, JsValue> {
+    Ok(to_latex(&parse_expression_source(source).map_err(parse_error_to_js)?))
+}
+
+#[cfg(all(test, feature = "math"))]
+mod tests {
+    use super::*;
+
+    /// Checks the simplified symbolic derivative against a central
+    /// difference at each point.
+    fn assert_matches_numeric(source: &str, points: &[f64]) {
+        let expr = parse_expression_source(source).unwrap();
+        let derivative = simplify(&differentiate(&expr, "x").unwrap());
+        for &x in points {
+            let point: HashMap<String, f64> = [("x".to_string(), x)].into_iter().collect();
+            let symbolic = derivative.eval(&point).unwrap();
+            let numeric = numeric_derivative(&expr, "x", &point).unwrap();
+            assert!(
+                (symbolic - numeric).abs() <= 1e-6 * numeric.abs().max(1.0),
+                "d/dx {} at {}: symbolic {} ({}) vs numeric {}",
+                source,
+                x,
+                symbolic,
+                derivative,
+                numeric
+            );
+        }
+    }
+
+    #[test]
+    fn elementary_functions() {
+        let points = [0.3, 0.7, 1.9];
+        for source in [
+            "x^3", "x^-2", "x^0.5", "sqrt(x)", "cbrt(x)", "2^x", "x^x", "exp(x)", "ln(x)", "log(x)", "log2(x)",
+            "sin(x)", "cos(x)", "tan(x)", "sinh(x)", "cosh(x)", "tanh(x)", "atan(x)", "abs(x)",
+        ] {
+            assert_matches_numeric(source, &points);
+        }
+        for source in ["asin(x)", "acos(x)"] {
+            assert_matches_numeric(source, &[-0.6, 0.1, 0.8]);
+        }
+    }
+
+    #[test]
+    fn product_quotient_and_chain_rules() {
+        let points = [-1.3, 0.4, 2.2];
+        for source in [
+            "x^2 * sin(x)",
+            "exp(x) * cos(x) * x",
+            "sin(x) / (1 + x^2)",
+            "(x^2 - 1) / (x^2 + 1)",
+            "sin(x^2)",
+            "exp(cos(3x))",
+            "ln(1 + x^2)",
+            "sqrt(1 + sin(x)^2)",
+            "atan2(x, 2)",
+            "(3x + 1)^4",
+        ] {
+            assert_matches_numeric(source, &points);
+        }
+    }
+
+    #[test]
+    fn nested_powers_keep_their_sign() {
+        assert_matches_numeric("(x^2)^0.5", &[-2.0, -0.5, 1.5]);
+        assert_matches_numeric("(x^3)^2", &[-2.0, 0.5]);
+        assert_eq!(simplify(&parse_expression_source("(x^2)^3").unwrap()).to_string(), "x ^ 6");
+    }
+
+    #[test]
+    fn only_cancels_ln_of_exp() {
+        let simplified = |source: &str| simplify(&parse_expression_source(source).unwrap()).to_string();
+        assert_eq!(simplified("ln(exp(x))"), "x");
+        // Undefined for x <= 0, so it must not collapse to x.
+        assert_eq!(simplified("exp(ln(x))"), "exp(ln(x))");
+    }
+}
@@ -0,0 +1,398 @@
+#[cfg(feature = "math")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "math")]
+use nalgebra as na;
+#[cfg(feature = "math")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "math")]
+use std::fmt;
+#[cfg(feature = "math")]
+use super::matrix::MatrixData;
+
+/// How far `LᵀL` may be from the identity for the linear part `L` to count
+/// as a rotation.
+#[cfg(feature = "math")]
+const ORTHOGONALITY_TOLERANCE: f64 = 1e-9;
+
+/// One step of a transform chain. Angles are in degrees.
+#[cfg(feature = "math")]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransformOp {
+    AxisAngle {
+        axis: [f64; 3],
+        degrees: f64,
+    },
+    /// Rotations about fixed axes, applied in `order`: "xyz" turns about X
+    /// first and Z last. Any three axes without an immediate repeat work,
+    /// so proper Euler orders such as "zxz" are accepted too.
+    Euler {
+        degrees: [f64; 3],
+        #[serde(default = "default_euler_order")]
+        order: String,
+    },
+    /// Normalized before use.
+    Quaternion { w: f64, x: f64, y: f64, z: f64 },
+    Scale { factors: [f64; 3] },
+    Translate { offset: [f64; 3] },
+    /// `xy` adds `xy * y` to x, and so on.
+    Shear {
+        #[serde(default)]
+        xy: f64,
+        #[serde(default)]
+        xz: f64,
+        #[serde(default)]
+        yx: f64,
+        #[serde(default)]
+        yz: f64,
+        #[serde(default)]
+        zx: f64,
+        #[serde(default)]
+        zy: f64,
+    },
+    /// Mirror in the plane `normal · p = offset`.
+    Reflect {
+        normal: [f64; 3],
+        #[serde(default)]
+        offset: f64,
+    },
+}
+
+#[cfg(feature = "math")]
+fn default_euler_order() -> String {
+    "xyz".to_string()
+}
+
+#[cfg(feature = "math")]
+fn unit_vector(v: [f64; 3], what: &str) -> Result<na::Unit<na::Vector3<f64>>, String> {
+    let v = na::Vector3::from(v);
+    if !v.iter().all(|c| c.is_finite()) {
+        return Err(format!("The {} must be finite", what));
+    }
+    na::Unit::try_new(v, 1e-12).ok_or_else(|| format!("The {} can't be the zero vector", what))
+}
+
+#[cfg(feature = "math")]
+fn parse_euler_order(order: &str) -> Result<[usize; 3], String> {
+    let axes: Vec<usize> = order
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            'x' => Ok(0),
+            'y' => Ok(1),
+            'z' => Ok(2),
+            _ => Err(format!("Invalid Euler order '{}': use three of x, y and z", order)),
+        })
+        .collect::<Result<_, _>>()?;
+    if axes.len() != 3 || axes[0] == axes[1] || axes[1] == axes[2] {
+        return Err(format!("Invalid Euler order '{}': three axes, no axis twice in a row", order));
+    }
+    Ok([axes[0], axes[1], axes[2]])
+}
+
+#[cfg(feature = "math")]
+impl TransformOp {
+    /// The orientation, for the rotation variants.
+    pub fn rotation(&self) -> Option<Result<na::UnitQuaternion<f64>, String>> {
+        let angles: &[f64] = match self {
+            TransformOp::AxisAngle { degrees, .. } => std::slice::from_ref(degrees),
+            TransformOp::Euler { degrees, .. } => degrees,
+            _ => &[],
+        };
+        if !angles.iter().all(|a| a.is_finite()) {
+            return Some(Err(format!("{} has a non-finite angle", self)));
+        }
+        match self {
+            TransformOp::AxisAngle { axis, degrees } => Some(
+                unit_vector(*axis, "rotation axis").map(|axis| na::UnitQuaternion::from_axis_angle(&axis, degrees.to_radians())),
+            ),
+            TransformOp::Euler { degrees, order } => Some(parse_euler_order(order).map(|axes| {
+                // Each later rotation is applied on the left.
+                axes.iter().zip(degrees).fold(na::UnitQuaternion::identity(), |acc, (&axis, angle)| {
+                    na::UnitQuaternion::from_axis_angle(&na::Vector3::ith_axis(axis), angle.to_radians()) * acc
+                })
+            })),
+            TransformOp::Quaternion { w, x, y, z } => {
+                let q = na::Quaternion::new(*w, *x, *y, *z);
+                Some(if q.coords.iter().all(|c| c.is_finite()) && q.norm() > 1e-12 {
+                    Ok(na::UnitQuaternion::from_quaternion(q))
+                } else {
+                    Err("A quaternion must be finite and non-zero".to_string())
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The operation as a homogeneous 4x4 matrix acting on column vectors.
+    pub fn matrix(&self) -> Result<na::Matrix4<f64>, String> {
+        let matrix = match self {
+            TransformOp::AxisAngle { .. } | TransformOp::Euler { .. } | TransformOp::Quaternion { .. } => {
+                self.rotation().expect("rotation variant")?.to_homogeneous()
+            }
+            TransformOp::Scale { factors } => na::Matrix4::new_nonuniform_scaling(&na::Vector3::from(*factors)),
+            TransformOp::Translate { offset } => na::Matrix4::new_translation(&na::Vector3::from(*offset)),
+            TransformOp::Shear { xy, xz, yx, yz, zx, zy } => {
+                na::Matrix3::new(1.0, *xy, *xz, *yx, 1.0, *yz, *zx, *zy, 1.0).to_homogeneous()
+            }
+            TransformOp::Reflect { normal, offset } => {
+                let n = unit_vector(*normal, "reflection normal")?.into_inner();
+                // The plane is n̂ · p = offset / |normal| once normal is scaled to n̂.
+                let distance = offset / na::Vector3::from(*normal).norm();
+                let mut m = (na::Matrix3::identity() - 2.0 * n * n.transpose()).to_homogeneous();
+                m.fixed_view_mut::<3, 1>(0, 3).copy_from(&(2.0 * distance * n));
+                m
+            }
+        };
+        if matrix.iter().all(|v| v.is_finite()) {
+            Ok(matrix)
+        } else {
+            Err(format!("{} has non-finite values", self))
+        }
+    }
+}
+
+#[cfg(feature = "math")]
+impl fmt::Display for TransformOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let v = |v: &[f64; 3]| format!("({}, {}, {})", v[0], v[1], v[2]);
+        match self {
+            TransformOp::AxisAngle { axis, degrees } => write!(f, "Rotate {}° about {}", degrees, v(axis)),
+            TransformOp::Euler { degrees, order } => write!(f, "Euler {} {}°", order, v(degrees)),
+            TransformOp::Quaternion { w, x, y, z } => write!(f, "Rotate by quaternion ({}, {}, {}, {})", w, x, y, z),
+            TransformOp::Scale { factors } => write!(f, "Scale {}", v(factors)),
+            TransformOp::Translate { offset } => write!(f, "Translate {}", v(offset)),
+            TransformOp::Shear { xy, xz, yx, yz, zx, zy } => {
+                let terms: Vec<String> = [("xy", xy), ("xz", xz), ("yx", yx), ("yz", yz), ("zx", zx), ("zy", zy)]
+                    .iter()
+                    .filter(|(_, k)| **k != 0.0)
+                    .map(|(name, k)| format!("{}={}", name, k))
+                    .collect();
+                write!(f, "Shear {}", if terms.is_empty() { "none".to_string() } else { terms.join(", ") })
+            }
+            TransformOp::Reflect { normal, offset } => write!(f, "Reflect in n·p = {} with n = {}", offset, v(normal)),
+        }
+    }
+}
+
+/// Composes the chain into one matrix; the first operation is applied to a
+/// point first.
+#[cfg(feature = "math")]
+pub fn compose(operations: &[TransformOp]) -> Result<na::Matrix4<f64>, String> {
+    operations.iter().try_fold(na::Matrix4::identity(), |acc, op| Ok(op.matrix()? * acc))
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct QuaternionData {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+#[cfg(feature = "math")]
+impl From<&na::UnitQuaternion<f64>> for QuaternionData {
+    fn from(q: &na::UnitQuaternion<f64>) -> Self {
+        QuaternionData { w: q.w, x: q.i, y: q.j, z: q.k }
+    }
+}
+
+/// A rotation as quaternion, axis and angle. The axis is None for the
+/// identity.
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct Orientation {
+    pub quaternion: QuaternionData,
+    pub axis: Option<[f64; 3]>,
+    pub degrees: f64,
+    pub matrix: MatrixData,
+}
+
+#[cfg(feature = "math")]
+impl From<&na::UnitQuaternion<f64>> for Orientation {
+    fn from(q: &na::UnitQuaternion<f64>) -> Self {
+        let (axis, degrees) = match q.axis_angle() {
+            Some((axis, angle)) => (Some([axis.x, axis.y, axis.z]), angle.to_degrees()),
+            None => (None, 0.0),
+        };
+        Orientation { quaternion: q.into(), axis, degrees, matrix: matrix_data(&q.to_rotation_matrix().into_inner()) }
+    }
+}
+
+#[cfg(feature = "math")]
+fn matrix_data<const R: usize, const C: usize>(m: &na::SMatrix<f64, R, C>) -> MatrixData {
+    MatrixData::from(&na::DMatrix::from_column_slice(R, C, m.as_slice()))
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct RigidMotion {
+    pub rotation: Orientation,
+    pub translation: [f64; 3],
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct TransformStep {
+    pub operation: String,
+    /// The point after this step.
+    pub point: [f64; 3],
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct TransformReport {
+    pub matrix: MatrixData,
+    /// None when the chain collapses space, e.g. a zero scale factor.
+    pub inverse: Option<MatrixData>,
+    pub determinant: f64,
+    /// The chain as an `Isometry3` (rotation then translation), when it
+    /// preserves distances and handedness.
+    pub isometry: Option<RigidMotion>,
+    pub point: [f64; 3],
+    pub transformed: [f64; 3],
+    pub steps: Vec<TransformStep>,
+}
+
+/// The composed transform for a chain and where it takes `point`.
+#[cfg(feature = "math")]
+pub fn transform_report(operations: &[TransformOp], point: [f64; 3]) -> Result<TransformReport, String> {
+    if !point.iter().all(|c| c.is_finite()) {
+        return Err("The point must be finite".to_string());
+    }
+    let mut steps = Vec::with_capacity(operations.len());
+    let mut current = na::Point3::from(point);
+    for op in operations {
+        current = op.matrix()?.transform_point(&current);
+        steps.push(TransformStep { operation: op.to_string(), point: current.coords.into() });
+    }
+
+    let matrix = compose(operations)?;
+    let linear = matrix.fixed_view::<3, 3>(0, 0).into_owned();
+    let determinant = linear.determinant();
+    let orthogonal = (linear.transpose() * linear - na::Matrix3::identity()).amax() < ORTHOGONALITY_TOLERANCE;
+    let isometry = (orthogonal && determinant > 0.0).then(|| {
+        let rotation = na::UnitQuaternion::from_rotation_matrix(&na::Rotation3::from_matrix_unchecked(linear));
+        let translation = na::Translation3::new(matrix[(0, 3)], matrix[(1, 3)], matrix[(2, 3)]);
+        let isometry = na::Isometry3::from_parts(translation, rotation);
+        RigidMotion { rotation: Orientation::from(&isometry.rotation), translation: isometry.translation.vector.into() }
+    });
+
+    Ok(TransformReport {
+        matrix: matrix_data(&matrix),
+        inverse: matrix.try_inverse().map(|m| matrix_data(&m)),
+        determinant,
+        isometry,
+        point,
+        transformed: matrix.transform_point(&na::Point3::from(point)).coords.into(),
+        steps,
+    })
+}
+
+#[cfg(feature = "math")]
+#[derive(Serialize, Clone, Debug)]
+pub struct SlerpResult {
+    pub t: f64,
+    pub from: Orientation,
+    pub to: Orientation,
+    pub interpolated: Orientation,
+    /// Angle of the rotation taking `from` to `to`, the shorter way round.
+    pub angle_between_degrees: f64,
+}
+
+#[cfg(feature = "math")]
+fn orientation_of(op: &TransformOp) -> Result<na::UnitQuaternion<f64>, String> {
+    op.rotation().unwrap_or_else(|| Err(format!("'{}' is not a rotation; use axis_angle, euler or quaternion", op)))
+}
+
+/// Spherical linear interpolation between two rotations, `t = 0` giving
+/// `from` and `t = 1` giving `to`. `t` must lie in [0, 1].
+#[cfg(feature = "math")]
+pub fn slerp_rotations(from: &TransformOp, to: &TransformOp, t: f64) -> Result<SlerpResult, String> {
+    if !(0.0..=1.0).contains(&t) {
+        return Err("t must be between 0 and 1".to_string());
+    }
+    let (a, b) = (orientation_of(from)?, orientation_of(to)?);
+    // try_slerp flips b to the nearer hemisphere first, so it can only fail
+    // on degenerate input.
+    let interpolated = a.try_slerp(&b, t, 1e-12).ok_or("The two orientations can't be interpolated")?;
+    Ok(SlerpResult {
+        t,
+        from: Orientation::from(&a),
+        to: Orientation::from(&b),
+        interpolated: Orientation::from(&interpolated),
+        angle_between_degrees: a.angle_to(&b).to_degrees(),
+    })
+}
+
+#[cfg(feature = "math")]
+fn parse_operations(operations: JsValue) -> Result<Vec<TransformOp>, JsValue> {
+    serde_wasm_bindgen::from_value(operations).map_err(|e| JsValue::from_str(&format!("Invalid transform: {}", e)))
+}
+
+/// Composes `operations`, e.g.
+/// `[{ type: "euler", degrees: [0, 0, 90], order: "zyx" }, { type: "translate", offset: [1, 0, 0] }]`,
+/// and applies the result to `point` ([x, y, z]).
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub fn compose_transforms(operations: JsValue, point: &[f64]) -> Result<JsValue, JsValue> {
+    let operations = parse_operations(operations)?;
+    let point: [f64; 3] = point.try_into().map_err(|_| JsValue::from_str("The point needs exactly 3 coordinates"))?;
+    let report = transform_report(&operations, point).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&report).unwrap())
+}
+
+/// Interpolates between two rotation operations (axis_angle, euler or
+/// quaternion) at `t` in [0, 1].
+#[cfg(feature = "math")]
+#[wasm_bindgen]
+pub fn slerp_orientations(from: JsValue, to: JsValue, t: f64) -> Result<JsValue, JsValue> {
+    let parse = |value: JsValue| -> Result<TransformOp, JsValue> {
+        serde_wasm_bindgen::from_value(value).map_err(|e| JsValue::from_str(&format!("Invalid rotation: {}", e)))
+    };
+    let result = slerp_rotations(&parse(from)?, &parse(to)?, t).map_err(|e| JsValue::from_str(&e))?;
+    Ok(serde_wasm_bindgen::to_value(&result).unwrap())
+}
+
+#[cfg(all(test, feature = "math"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reflection_plane_uses_the_unscaled_normal() {
+        let unit = TransformOp::Reflect { normal: [0.0, 0.0, 1.0], offset: 1.0 };
+        let scaled = TransformOp::Reflect { normal: [0.0, 0.0, 2.0], offset: 2.0 };
+        for op in [unit, scaled] {
+            let report = transform_report(&[op], [5.0, 5.0, 3.0]).unwrap();
+            assert_eq!(report.transformed, [5.0, 5.0, -1.0]);
+        }
+    }
+
+    #[test]
+    fn rotations_reject_non_finite_angles() {
+        for angle in [f64::NAN, f64::INFINITY] {
+            let ops = [
+                TransformOp::AxisAngle { axis: [0.0, 0.0, 1.0], degrees: angle },
+                TransformOp::Euler { degrees: [0.0, angle, 0.0], order: default_euler_order() },
+            ];
+            for op in ops {
+                assert!(op.matrix().is_err(), "{}", op);
+                assert!(slerp_rotations(&op, &op, 0.5).is_err(), "{}", op);
+            }
+        }
+    }
+
+    #[test]
+    fn slerp_requires_t_in_the_unit_interval() {
+        let from = TransformOp::AxisAngle { axis: [0.0, 0.0, 1.0], degrees: 0.0 };
+        let to = TransformOp::AxisAngle { axis: [0.0, 0.0, 1.0], degrees: 90.0 };
+        for t in [-0.1, 1.1, f64::NAN, f64::INFINITY] {
+            assert!(slerp_rotations(&from, &to, t).is_err(), "t = {}", t);
+        }
+        let halfway = slerp_rotations(&from, &to, 0.5).unwrap();
+        assert!((halfway.angle_between_degrees - 90.0).abs() < 1e-9);
+        let expected = na::UnitQuaternion::from_axis_angle(&na::Vector3::z_axis(), 45f64.to_radians());
+        assert!((halfway.interpolated.quaternion.w - expected.w).abs() < 1e-12);
+        assert!((halfway.interpolated.quaternion.z - expected.k).abs() < 1e-12);
+    }
+}